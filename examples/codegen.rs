@@ -0,0 +1,45 @@
+//! Volatile accessors whose assembly is checked by `tests/codegen.rs`.
+//!
+//! Each `codegen_read_*` and `codegen_write_*` function performs a single volatile access of a
+//! primitive integer, which has to compile to exactly one load or store instruction. The array
+//! copies have to be fully unrolled.
+
+use volatile::{
+    access::{ReadOnly, WriteOnly},
+    VolatilePtr,
+};
+
+macro_rules! accessors {
+    ($($ty:ty => $read:ident, $write:ident;)*) => {
+        $(
+            #[no_mangle]
+            pub fn $read(ptr: VolatilePtr<'_, $ty, ReadOnly>) -> $ty {
+                ptr.read()
+            }
+
+            #[no_mangle]
+            pub fn $write(ptr: VolatilePtr<'_, $ty, WriteOnly>, value: $ty) {
+                ptr.write(value);
+            }
+        )*
+    };
+}
+
+accessors! {
+    u8 => codegen_read_u8, codegen_write_u8;
+    u16 => codegen_read_u16, codegen_write_u16;
+    u32 => codegen_read_u32, codegen_write_u32;
+    u64 => codegen_read_u64, codegen_write_u64;
+}
+
+#[no_mangle]
+pub fn codegen_copy_from_array(ptr: VolatilePtr<'_, [u32; 4], WriteOnly>, src: &[u32; 4]) {
+    ptr.copy_from_array(src);
+}
+
+#[no_mangle]
+pub fn codegen_copy_into_array(ptr: VolatilePtr<'_, [u32; 4], ReadOnly>, dst: &mut [u32; 4]) {
+    ptr.copy_into_array(dst);
+}
+
+fn main() {}
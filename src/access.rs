@@ -46,7 +46,38 @@ restrict_impl!(WriteOnly, WriteOnly, WriteOnly);
 restrict_impl!(WriteOnly, NoAccess, NoAccess);
 
 /// Sealed trait that is implemented for the types in this module.
-pub trait Access: Copy + Default + private::Sealed {}
+pub trait Access: Copy + Default + private::Sealed {
+    /// The runtime representation of this access type.
+    const KIND: AccessKind;
+}
+
+/// Runtime representation of the [`Access`] types.
+///
+/// This is useful for inspecting access permissions at runtime, e.g., in register dumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AccessKind {
+    /// See [`ReadWrite`].
+    ReadWrite,
+    /// See [`ReadOnly`].
+    ReadOnly,
+    /// See [`WriteOnly`].
+    WriteOnly,
+    /// See [`NoAccess`].
+    NoAccess,
+}
+
+impl AccessKind {
+    /// Returns whether this access kind permits reading.
+    pub const fn is_readable(self) -> bool {
+        matches!(self, Self::ReadWrite | Self::ReadOnly)
+    }
+
+    /// Returns whether this access kind permits writing.
+    pub const fn is_writable(self) -> bool {
+        matches!(self, Self::ReadWrite | Self::WriteOnly)
+    }
+}
 
 /// Helper trait that is implemented by [`ReadWrite`] and [`ReadOnly`].
 pub trait Readable: Access {}
@@ -63,22 +94,30 @@ impl<A: RestrictAccess<ReadOnly, Restricted = Self>> Copyable for A {}
 /// Zero-sized marker type for allowing both read and write access.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct ReadWrite;
-impl Access for ReadWrite {}
+impl Access for ReadWrite {
+    const KIND: AccessKind = AccessKind::ReadWrite;
+}
 
 /// Zero-sized marker type for allowing only read access.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct ReadOnly;
-impl Access for ReadOnly {}
+impl Access for ReadOnly {
+    const KIND: AccessKind = AccessKind::ReadOnly;
+}
 
 /// Zero-sized marker type for allowing only write access.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct WriteOnly;
-impl Access for WriteOnly {}
+impl Access for WriteOnly {
+    const KIND: AccessKind = AccessKind::WriteOnly;
+}
 
 /// Zero-sized marker type that grants no access.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct NoAccess;
-impl Access for NoAccess {}
+impl Access for NoAccess {
+    const KIND: AccessKind = AccessKind::NoAccess;
+}
 
 mod private {
     pub trait Sealed {}
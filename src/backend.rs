@@ -0,0 +1,452 @@
+//! Pluggable backends for volatile accesses.
+//!
+//! By default, [`VolatilePtr::read`](crate::VolatilePtr::read) and
+//! [`VolatilePtr::write`](crate::VolatilePtr::write) perform real volatile memory accesses.
+//! With the `test-util` feature, a [`VolatileBackend`] can be installed for the current thread
+//! using [`with_backend`], which routes these accesses through the backend instead. This allows
+//! testing driver logic against a simulated device, e.g., using [`mock::MockBackend`].
+//!
+//! Since every access then looks up the backend of the current thread, only enable
+//! `test-util` for tests, e.g., as a dev-dependency feature. Without it, accesses always go to
+//! memory directly and there is no overhead.
+//!
+//! While a backend is installed, bulk operations on slices, such as `copy_from_slice`,
+//! `fill_bytes`, and the non-temporal copies, access the volatile slices element by element
+//! through the backend, so that it observes every access. Elements are accessed with accesses
+//! as wide as their alignment, up to a word, e.g., 32-bit accesses for a slice of `u32`. Without a backend, they use the faster copies
+//! described on the respective methods.
+//!
+//! In debug builds, every access through a backend or directly to memory asserts that the
+//! pointer is aligned. Additionally, [`set_range_check`] installs a function that checks the
+//! address range of each access, e.g., against the MMIO windows of the platform.
+//!
+//! Accesses of zero-sized types never reach a backend, the checks, or any hooks, since they do
+//! not access memory.
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A backend that handles volatile accesses.
+///
+/// Accesses are described by the accessed pointer and their data as bytes in native byte
+/// order.
+pub trait VolatileBackend {
+    /// Handles a read of `buf.len()` bytes at `src`.
+    ///
+    /// Returns `false` to fall back to a real volatile read. The content of `buf` is ignored
+    /// in this case.
+    ///
+    /// ## Safety
+    ///
+    /// `src` must be valid for volatile reads of `buf.len()` bytes.
+    unsafe fn read(&self, src: *const u8, buf: &mut [u8]) -> bool;
+
+    /// Handles a write of `buf` to `dst`.
+    ///
+    /// Returns `false` to fall back to a real volatile write.
+    ///
+    /// ## Safety
+    ///
+    /// `dst` must be valid for volatile writes of `buf.len()` bytes.
+    unsafe fn write(&self, dst: *mut u8, buf: &[u8]) -> bool;
+}
+
+/// The direction of a volatile access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// A volatile read.
+    Read,
+    /// A volatile write.
+    Write,
+}
+
+#[cfg(feature = "test-util")]
+mod dispatch {
+    use core::{cell::Cell, mem, ptr, slice};
+
+    use super::VolatileBackend;
+
+    std::thread_local! {
+        static BACKEND: Cell<Option<*const (dyn VolatileBackend + 'static)>> = const { Cell::new(None) };
+    }
+
+    /// Restores the previous backend on drop, even if the closure panics.
+    struct Restore(Option<*const (dyn VolatileBackend + 'static)>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BACKEND.with(|backend| backend.set(self.0));
+        }
+    }
+
+    pub unsafe fn with_backend<R>(backend: &dyn VolatileBackend, f: impl FnOnce() -> R) -> R {
+        // SAFETY: The pointer is only used while `f` runs, which `backend` outlives.
+        let backend: *const (dyn VolatileBackend + 'static) = unsafe { mem::transmute(backend) };
+        let _restore = Restore(BACKEND.with(|current| current.replace(Some(backend))));
+        f()
+    }
+
+    pub fn current() -> Option<&'static dyn VolatileBackend> {
+        // SAFETY: `with_backend` removes the backend before it is dropped.
+        BACKEND
+            .try_with(Cell::get)
+            .ok()
+            .flatten()
+            .map(|backend| unsafe { &*backend })
+    }
+
+    pub unsafe fn read<T>(src: *const T) -> Option<T> {
+        let backend = current()?;
+        let mut value = mem::MaybeUninit::<T>::zeroed();
+        // SAFETY: The zeroed bytes of `value` are initialized.
+        let buf =
+            unsafe { slice::from_raw_parts_mut(value.as_mut_ptr().cast(), mem::size_of::<T>()) };
+        // SAFETY: The caller guarantees that `src` is valid for reads.
+        if !unsafe { backend.read(src.cast(), buf) } {
+            return None;
+        }
+        // SAFETY: The caller of `with_backend` guarantees that the bytes are valid for `T`.
+        Some(unsafe { value.assume_init() })
+    }
+
+    pub unsafe fn write<T>(dst: *mut T, value: &T) -> bool {
+        let Some(backend) = current() else {
+            return false;
+        };
+        // SAFETY: The caller of `with_backend` guarantees that `T` has no padding bytes.
+        let buf =
+            unsafe { slice::from_raw_parts(ptr::from_ref(value).cast(), mem::size_of::<T>()) };
+        // SAFETY: The caller guarantees that `dst` is valid for writes.
+        unsafe { backend.write(dst.cast(), buf) }
+    }
+}
+
+/// Routes all volatile reads and writes of the current thread through `backend` while `f`
+/// runs.
+///
+/// Calls can be nested, in which case the innermost backend is used.
+///
+/// ## Safety
+///
+/// - The types that are read and written while `f` runs must not contain padding bytes.
+/// - The data provided by [`VolatileBackend::read`] must be a valid value of the type that is
+///   read.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::backend::{mock::MockBackend, with_backend};
+/// use volatile::VolatilePtr;
+///
+/// let mut status = 0_u32;
+/// let volatile = unsafe { VolatilePtr::new((&mut status).into()) };
+///
+/// let mock = MockBackend::new();
+/// mock.push_read(volatile.as_raw_ptr().as_ptr(), &1_u32.to_ne_bytes());
+/// let value = unsafe { with_backend(&mock, || volatile.read()) };
+///
+/// assert_eq!(value, 1);
+/// assert_eq!(status, 0);
+/// ```
+#[cfg(feature = "test-util")]
+pub unsafe fn with_backend<R>(backend: &dyn VolatileBackend, f: impl FnOnce() -> R) -> R {
+    unsafe { dispatch::with_backend(backend, f) }
+}
+
+/// Returns whether a backend is installed for the current thread.
+///
+/// This is always `false` without the `test-util` feature.
+#[inline(always)]
+pub(crate) fn is_installed() -> bool {
+    #[cfg(feature = "test-util")]
+    {
+        dispatch::current().is_some()
+    }
+    #[cfg(not(feature = "test-util"))]
+    {
+        false
+    }
+}
+
+/// The function installed by [`set_range_check`], or null.
+static RANGE_CHECK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs a function that checks the address and size of every volatile access in debug
+/// builds.
+///
+/// Accesses for which `check` returns `false` panic at the access site. Without debug
+/// assertions, `check` is never called.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::{backend::set_range_check, VolatilePtr};
+///
+/// fn in_mmio_window(addr: usize, size: usize) -> bool {
+///     // Allow everything in this example.
+///     addr.checked_add(size).is_some()
+/// }
+///
+/// set_range_check(in_mmio_window);
+///
+/// let mut value = 0_u32;
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+/// volatile.write(1);
+/// ```
+pub fn set_range_check(check: fn(usize, usize) -> bool) {
+    RANGE_CHECK.store(check as *mut (), Ordering::Relaxed);
+}
+
+/// Asserts that `ptr` is aligned and passes the installed range check.
+#[cfg(debug_assertions)]
+#[track_caller]
+fn debug_check<T>(ptr: *const T) {
+    assert!(ptr.is_aligned(), "misaligned volatile access at {ptr:p}");
+    let check = RANGE_CHECK.load(Ordering::Relaxed);
+    if !check.is_null() {
+        // SAFETY: Only `set_range_check` stores to `RANGE_CHECK`.
+        let check = unsafe { mem::transmute::<*mut (), fn(usize, usize) -> bool>(check) };
+        assert!(
+            check(ptr.addr(), mem::size_of::<T>()),
+            "volatile access at {ptr:p} is outside of the allowed ranges"
+        );
+    }
+}
+
+/// Reports an access of `size` bytes at `ptr` to the enabled hooks.
+///
+/// The access is considered complete when the returned value is dropped. Bulk operations call
+/// this once for each volatile range instead of once per element.
+#[inline(always)]
+#[track_caller]
+#[cfg_attr(
+    not(any(feature = "instrument", feature = "stats")),
+    allow(unused_variables)
+)]
+pub(crate) fn observe(ptr: *const u8, size: usize, direction: Direction) -> Observation {
+    #[cfg(feature = "stats")]
+    crate::stats::record(ptr, size, direction);
+    Observation {
+        #[cfg(feature = "instrument")]
+        _guard: crate::instrument::Guard::new(ptr, size, direction),
+    }
+}
+
+/// Reports a bulk operation on the `len` bytes at `ptr` to the enabled hooks, unless it is
+/// empty.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn observe_bulk(
+    ptr: *const u8,
+    len: usize,
+    direction: Direction,
+) -> Option<Observation> {
+    if len == 0 {
+        return None;
+    }
+    Some(observe(ptr, len, direction))
+}
+
+/// An access that was reported by [`observe`] and completes on drop.
+#[must_use]
+pub(crate) struct Observation {
+    #[cfg(feature = "instrument")]
+    _guard: crate::instrument::Guard,
+}
+
+#[inline(always)]
+#[track_caller]
+pub(crate) unsafe fn read<T>(src: *const T) -> T {
+    if mem::size_of::<T>() == 0 {
+        // SAFETY: Reading a zero-sized value does not access memory.
+        return unsafe { ptr::read(src) };
+    }
+    #[cfg(debug_assertions)]
+    debug_check(src);
+    let _observation = observe(src.cast(), mem::size_of::<T>(), Direction::Read);
+    unsafe { read_unobserved(src) }
+}
+
+#[inline(always)]
+#[track_caller]
+pub(crate) unsafe fn write<T>(dst: *mut T, value: T) {
+    if mem::size_of::<T>() == 0 {
+        // SAFETY: Writing a zero-sized value does not access memory.
+        return unsafe { ptr::write(dst, value) };
+    }
+    #[cfg(debug_assertions)]
+    debug_check(dst);
+    let _observation = observe(dst.cast(), mem::size_of::<T>(), Direction::Write);
+    unsafe { write_unobserved(dst, value) }
+}
+
+/// Reads `src` through the installed backend or directly, without calling any hooks.
+///
+/// This is used by bulk operations that already reported their whole range with [`observe`].
+#[inline(always)]
+pub(crate) unsafe fn read_unobserved<T>(src: *const T) -> T {
+    #[cfg(feature = "test-util")]
+    if let Some(value) = unsafe { dispatch::read(src) } {
+        return value;
+    }
+    unsafe { core::ptr::read_volatile(src) }
+}
+
+/// Writes `dst` through the installed backend or directly, without calling any hooks.
+///
+/// This is used by bulk operations that already reported their whole range with [`observe`].
+#[inline(always)]
+pub(crate) unsafe fn write_unobserved<T>(dst: *mut T, value: T) {
+    #[cfg(feature = "test-util")]
+    if unsafe { dispatch::write(dst, &value) } {
+        return;
+    }
+    unsafe { core::ptr::write_volatile(dst, value) }
+}
+
+#[cfg(feature = "test-util")]
+pub mod mock {
+    //! A backend that records accesses and returns scripted values.
+
+    use core::{cell::RefCell, ptr};
+    use std::{collections::HashMap, collections::VecDeque, vec::Vec};
+
+    use super::{Direction, VolatileBackend};
+
+    /// An access that was recorded by a [`MockBackend`].
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Access {
+        /// Whether this access was a read or a write.
+        pub direction: Direction,
+        /// The address of the access.
+        pub addr: usize,
+        /// The data that was read or written in native byte order.
+        pub data: Vec<u8>,
+    }
+
+    /// A [`VolatileBackend`] that records all accesses and returns scripted values for reads.
+    ///
+    /// Reads of addresses without scripted values and all writes access the underlying memory,
+    /// so that the mock behaves like plain memory by default.
+    #[derive(Debug, Default)]
+    pub struct MockBackend {
+        responses: RefCell<HashMap<usize, VecDeque<Vec<u8>>>>,
+        accesses: RefCell<Vec<Access>>,
+    }
+
+    impl MockBackend {
+        /// Creates a new mock backend without scripted values.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queues `data` as the result of a future read of `ptr`.
+        ///
+        /// Scripted values for the same address are returned in the order in which they were
+        /// queued. A scripted value is only used for a read of the same size.
+        pub fn push_read<T>(&self, ptr: *const T, data: &[u8]) {
+            self.responses
+                .borrow_mut()
+                .entry(ptr as usize)
+                .or_default()
+                .push_back(data.to_vec());
+        }
+
+        /// Returns all accesses that were recorded so far.
+        pub fn accesses(&self) -> Vec<Access> {
+            self.accesses.borrow().clone()
+        }
+
+        /// Returns and clears all accesses that were recorded so far.
+        pub fn take_accesses(&self) -> Vec<Access> {
+            self.accesses.take()
+        }
+
+        fn record(&self, direction: Direction, addr: usize, data: &[u8]) {
+            self.accesses.borrow_mut().push(Access {
+                direction,
+                addr,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    impl VolatileBackend for MockBackend {
+        unsafe fn read(&self, src: *const u8, buf: &mut [u8]) -> bool {
+            let addr = src as usize;
+            let mut responses = self.responses.borrow_mut();
+            let response = responses
+                .get_mut(&addr)
+                .filter(|queue| queue.front().is_some_and(|data| data.len() == buf.len()))
+                .and_then(VecDeque::pop_front);
+            drop(responses);
+
+            match response {
+                Some(data) => buf.copy_from_slice(&data),
+                None => {
+                    for (i, byte) in buf.iter_mut().enumerate() {
+                        // SAFETY: The caller guarantees that `src` is valid for reads.
+                        *byte = unsafe { ptr::read_volatile(src.add(i)) };
+                    }
+                }
+            }
+            self.record(Direction::Read, addr, buf);
+            true
+        }
+
+        unsafe fn write(&self, dst: *mut u8, buf: &[u8]) -> bool {
+            self.record(Direction::Write, dst as usize, buf);
+            false
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use crate::{
+        backend::{
+            mock::{Access, MockBackend},
+            with_backend, Direction,
+        },
+        VolatilePtr,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_mock_backend() {
+        let mut val: u16 = 1;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        let addr = volatile.as_raw_ptr().as_ptr() as usize;
+
+        let mock = MockBackend::new();
+        mock.push_read(volatile.as_raw_ptr().as_ptr(), &5_u16.to_ne_bytes());
+        unsafe {
+            with_backend(&mock, || {
+                volatile.update(|v| v + 1);
+                assert_eq!(volatile.read(), 6);
+            })
+        };
+        assert_eq!(val, 6);
+
+        assert_eq!(
+            mock.take_accesses(),
+            [
+                (Direction::Read, 5_u16),
+                (Direction::Write, 6),
+                (Direction::Read, 6),
+            ]
+            .map(|(direction, value)| Access {
+                direction,
+                addr,
+                data: value.to_ne_bytes().to_vec(),
+            })
+        );
+
+        // The backend is only used inside of `with_backend`.
+        volatile.write(7);
+        assert!(mock.accesses().is_empty());
+    }
+}
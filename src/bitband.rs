@@ -0,0 +1,93 @@
+//! Bit-band aliases on Cortex-M3 and Cortex-M4.
+//!
+//! These cores map every bit of the first megabyte of the SRAM and peripheral regions to a
+//! word in the corresponding bit-band alias region. Writing `1` or `0` to an alias word sets
+//! or clears the bit in a single, atomic bus transaction, and reading an alias word returns
+//! the bit. [`VolatilePtr::bit_band`] returns a pointer to such an alias word, so that single
+//! bits can be modified through the normal [`VolatilePtr`] API without read-modify-write
+//! races.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use core::ptr::NonNull;
+//! use volatile::VolatilePtr;
+//!
+//! // GPIOA_ODR on an STM32F4
+//! let odr = unsafe { VolatilePtr::new(NonNull::new(0x4002_0014 as *mut u32).unwrap()) };
+//! let pin5 = unsafe { odr.bit_band(5) }.unwrap();
+//! pin5.write(1);
+//! ```
+
+use core::ptr::{self, NonNull};
+
+use crate::{access::Access, VolatilePtr};
+
+/// Start of the SRAM bit-band region.
+pub const SRAM_BASE: usize = 0x2000_0000;
+/// Start of the SRAM bit-band alias region.
+pub const SRAM_ALIAS_BASE: usize = 0x2200_0000;
+/// Start of the peripheral bit-band region.
+pub const PERIPHERAL_BASE: usize = 0x4000_0000;
+/// Start of the peripheral bit-band alias region.
+pub const PERIPHERAL_ALIAS_BASE: usize = 0x4200_0000;
+/// Size of each bit-band region in bytes.
+pub const REGION_SIZE: usize = 0x10_0000;
+
+/// Returns the address of the alias word for bit `bit` of the word at `address`.
+///
+/// Returns `None` if the word is not within a bit-band region, is not aligned, or if `bit`
+/// is not less than 32.
+pub const fn alias_address(address: usize, bit: u32) -> Option<usize> {
+    if bit >= u32::BITS || address & 3 != 0 {
+        return None;
+    }
+    let (base, alias_base) = if address >= SRAM_BASE && address - SRAM_BASE < REGION_SIZE {
+        (SRAM_BASE, SRAM_ALIAS_BASE)
+    } else if address >= PERIPHERAL_BASE && address - PERIPHERAL_BASE < REGION_SIZE {
+        (PERIPHERAL_BASE, PERIPHERAL_ALIAS_BASE)
+    } else {
+        return None;
+    };
+    Some(alias_base + (address - base) * 32 + bit as usize * 4)
+}
+
+/// Methods for bit-band regions.
+impl<'a, A> VolatilePtr<'a, u32, A>
+where
+    A: Access,
+{
+    /// Returns a pointer to the bit-band alias word for bit `bit` of this word.
+    ///
+    /// Returns `None` if this word is not within a bit-band region or if `bit` is not less
+    /// than 32. See [`alias_address`] for details.
+    ///
+    /// ## Safety
+    ///
+    /// The target must implement bit-banding, e.g., a Cortex-M3 or Cortex-M4. Accesses through
+    /// the alias word must be valid whenever accesses through this pointer are.
+    pub unsafe fn bit_band(self, bit: u32) -> Option<VolatilePtr<'a, u32, A>> {
+        let address = self.as_raw_ptr().as_ptr().expose_provenance();
+        let alias = alias_address(address, bit)?;
+        let alias = NonNull::new(ptr::with_exposed_provenance_mut::<u32>(alias))?;
+        // SAFETY: The caller guarantees that the alias word is valid.
+        Some(unsafe { self.map(|_| alias) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitband::alias_address;
+
+    #[test]
+    fn test_bit_band_alias_address() {
+        assert_eq!(alias_address(0x2000_0000, 0), Some(0x2200_0000));
+        assert_eq!(alias_address(0x2000_0300, 2), Some(0x2200_6008));
+        assert_eq!(alias_address(0x4002_0014, 5), Some(0x4240_0294));
+        assert_eq!(alias_address(0x400F_FFFC, 31), Some(0x43FF_FFFC));
+        assert_eq!(alias_address(0x4010_0000, 0), None);
+        assert_eq!(alias_address(0x1FFF_FFFC, 0), None);
+        assert_eq!(alias_address(0x2000_0002, 0), None);
+        assert_eq!(alias_address(0x2000_0000, 32), None);
+    }
+}
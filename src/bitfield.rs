@@ -0,0 +1,615 @@
+//! Named bit fields of integer registers.
+//!
+//! The [`register_bitfields!`](crate::register_bitfields) macro defines the fields of an
+//! integer register, including masks and enumerated values. The fields can then be accessed
+//! through [`VolatilePtr::read_field`], [`VolatilePtr::modify_field`],
+//! [`VolatilePtr::write_field`], and [`VolatilePtr::matches`] without manual mask arithmetic.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{register_bitfields, VolatilePtr};
+//!
+//! register_bitfields! {
+//!     u32,
+//!     Control [
+//!         ENABLE OFFSET(0) NUMBITS(1) [],
+//!         MODE OFFSET(1) NUMBITS(2) [
+//!             Pio = 0,
+//!             Dma = 1,
+//!             Mmio = 2,
+//!         ],
+//!     ],
+//! }
+//!
+//! let mut control = 0x100_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut control).into()) };
+//!
+//! volatile.modify_field(Control::MODE::Dma + Control::ENABLE::SET);
+//! assert_eq!(control, 0x103);
+//!
+//! let volatile = unsafe { VolatilePtr::new((&mut control).into()) };
+//! assert_eq!(volatile.read_field(Control::MODE), 1);
+//! assert!(volatile.matches(Control::MODE::Dma));
+//! assert!(!volatile.matches(Control::ENABLE::CLEAR));
+//! ```
+//!
+//! Single bits can also be accessed without defining fields through [`VolatilePtr::bit`], and
+//! boolean registers provide [`set`](VolatilePtr::set), [`clear`](VolatilePtr::clear), and
+//! [`toggle`](VolatilePtr::toggle).
+
+use core::{
+    fmt,
+    marker::PhantomData,
+    ops::{Add, BitAnd, BitOr, Not, Shl, Shr},
+};
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// Integer types that can hold bit fields.
+///
+/// This trait is sealed and implemented for all unsigned primitive integer types.
+pub trait RegisterInt:
+    Copy
+    + Eq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + private::Sealed
+{
+}
+
+mod private {
+    pub trait Sealed {
+        const ZERO: Self;
+        const ONE: Self;
+        const BITS: usize;
+    }
+}
+
+macro_rules! impl_register_int {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const BITS: usize = <$ty>::BITS as usize;
+            }
+            impl RegisterInt for $ty {}
+        )*
+    };
+}
+
+impl_register_int!(u8, u16, u32, u64, u128, usize);
+
+/// A bit field of the register `R`.
+///
+/// Fields are usually defined using [`register_bitfields!`](crate::register_bitfields).
+pub struct Field<T, R> {
+    mask: T,
+    shift: usize,
+    register: PhantomData<R>,
+}
+
+impl<T: Copy, R> Copy for Field<T, R> {}
+
+impl<T: Copy, R> Clone for Field<T, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: fmt::Debug, R> fmt::Debug for Field<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Field")
+            .field("mask", &self.mask)
+            .field("shift", &self.shift)
+            .finish()
+    }
+}
+
+impl<T: Copy, R> Field<T, R> {
+    /// Creates a field of the bits `mask << shift`.
+    ///
+    /// `mask` must be unshifted, i.e., its lowest bit corresponds to bit `shift` of the
+    /// register.
+    pub const fn new(mask: T, shift: usize) -> Self {
+        Self {
+            mask,
+            shift,
+            register: PhantomData,
+        }
+    }
+
+    /// Returns the unshifted mask of the field.
+    pub const fn mask(self) -> T {
+        self.mask
+    }
+
+    /// Returns the position of the lowest bit of the field.
+    pub const fn shift(self) -> usize {
+        self.shift
+    }
+}
+
+impl<T: RegisterInt, R> Field<T, R> {
+    /// Returns a field value that sets this field to `value`.
+    ///
+    /// Bits of `value` that do not fit into the field are ignored.
+    pub fn val(self, value: T) -> FieldValue<T, R> {
+        FieldValue::from_parts(self.mask << self.shift, (value & self.mask) << self.shift)
+    }
+
+    /// Extracts this field from the raw register value `raw`.
+    pub fn read(self, raw: T) -> T {
+        (raw >> self.shift) & self.mask
+    }
+}
+
+/// Values for one or more fields of the register `R`.
+///
+/// Field values can be combined using `+`.
+pub struct FieldValue<T, R> {
+    mask: T,
+    value: T,
+    register: PhantomData<R>,
+}
+
+impl<T: Copy, R> Copy for FieldValue<T, R> {}
+
+impl<T: Copy, R> Clone for FieldValue<T, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: fmt::Debug, R> fmt::Debug for FieldValue<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldValue")
+            .field("mask", &self.mask)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T: Copy, R> FieldValue<T, R> {
+    /// Creates a field value from a shifted mask and a shifted value.
+    ///
+    /// `value` must not have bits set outside of `mask`.
+    pub const fn from_parts(mask: T, value: T) -> Self {
+        Self {
+            mask,
+            value,
+            register: PhantomData,
+        }
+    }
+
+    /// Returns the shifted mask of all affected bits.
+    pub const fn mask(self) -> T {
+        self.mask
+    }
+
+    /// Returns the shifted value of all affected bits.
+    pub const fn value(self) -> T {
+        self.value
+    }
+}
+
+impl<T: RegisterInt, R> FieldValue<T, R> {
+    /// Applies the field value to the raw register value `raw`, keeping all other bits.
+    pub fn modify(self, raw: T) -> T {
+        (raw & !self.mask) | self.value
+    }
+
+    /// Returns whether all affected bits of `raw` are equal to this field value.
+    pub fn matches_all(self, raw: T) -> bool {
+        raw & self.mask == self.value
+    }
+}
+
+impl<T: RegisterInt, R> Add for FieldValue<T, R> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_parts(self.mask | rhs.mask, self.value | rhs.value)
+    }
+}
+
+/// Methods for bit fields.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: RegisterInt,
+{
+    /// Performs a volatile read and extracts the given field.
+    pub fn read_field<R>(self, field: Field<T, R>) -> T
+    where
+        A: Readable,
+    {
+        field.read(self.read())
+    }
+
+    /// Performs a volatile read and checks whether the given fields have the given values.
+    pub fn matches<R>(self, value: FieldValue<T, R>) -> bool
+    where
+        A: Readable,
+    {
+        value.matches_all(self.read())
+    }
+
+    /// Performs a volatile write of the given field values, setting all other bits to zero.
+    pub fn write_field<R>(self, value: FieldValue<T, R>)
+    where
+        A: Writable,
+    {
+        self.write(value.value());
+    }
+
+    /// Updates the given fields using a volatile read followed by a volatile write, keeping
+    /// all other bits.
+    pub fn modify_field<R>(self, value: FieldValue<T, R>)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|raw| value.modify(raw));
+    }
+
+    /// Performs a volatile read and extracts the bits `mask << shift`, shifted down by
+    /// `shift`.
+    ///
+    /// This is the building block of [`read_field`](Self::read_field) for registers that do
+    /// not warrant a [`register_bitfields!`](crate::register_bitfields) definition. `mask`
+    /// must be unshifted, as for [`Field::new`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut status = 0x0000_0a50_u32;
+    /// let volatile = unsafe { VolatilePtr::new((&mut status).into()) };
+    /// assert_eq!(volatile.read_bits(0xff, 4), 0xa5);
+    ///
+    /// volatile.write_bits(0xf, 8, 0x3);
+    /// assert_eq!(status, 0x0000_0350);
+    /// ```
+    pub fn read_bits(self, mask: T, shift: usize) -> T
+    where
+        A: Readable,
+    {
+        self.read_field(Field::<T, ()>::new(mask, shift))
+    }
+
+    /// Replaces the bits `mask << shift` with `value << shift` using a volatile read followed
+    /// by a volatile write, keeping all other bits.
+    ///
+    /// Bits of `value` that do not fit into `mask` are ignored.
+    pub fn write_bits(self, mask: T, shift: usize, value: T)
+    where
+        A: Readable + Writable,
+    {
+        self.modify_field(Field::<T, ()>::new(mask, shift).val(value));
+    }
+}
+
+/// Methods for single bits.
+impl<'a, T, A> VolatilePtr<'a, T, A>
+where
+    T: RegisterInt,
+{
+    /// Returns a pointer to bit `bit` of the register, counted from the least significant bit.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `bit` is not smaller than the number of bits of `T`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut control = 0_u32;
+    /// let volatile = unsafe { VolatilePtr::new((&mut control).into()) };
+    ///
+    /// let enable = volatile.bit(3);
+    /// enable.set();
+    /// assert!(enable.read());
+    /// assert_eq!(control, 0b1000);
+    /// ```
+    #[track_caller]
+    pub fn bit(self, bit: usize) -> BitPtr<'a, T, A> {
+        assert!(bit < T::BITS, "bit {bit} is out of range");
+        BitPtr { pointer: self, bit }
+    }
+}
+
+/// A pointer to a single bit of an integer register.
+///
+/// This type is created by [`VolatilePtr::bit`]. Writes are volatile read-modify-writes of
+/// the whole register.
+pub struct BitPtr<'a, T, A> {
+    pointer: VolatilePtr<'a, T, A>,
+    bit: usize,
+}
+
+impl<T, A> Copy for BitPtr<'_, T, A> {}
+
+impl<T, A> Clone for BitPtr<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> fmt::Debug for BitPtr<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitPtr")
+            .field("pointer", &self.pointer)
+            .field("bit", &self.bit)
+            .finish()
+    }
+}
+
+impl<'a, T, A> BitPtr<'a, T, A>
+where
+    T: RegisterInt,
+{
+    /// Returns the pointer to the whole register.
+    pub fn as_ptr(self) -> VolatilePtr<'a, T, A> {
+        self.pointer
+    }
+
+    /// Reads the register and returns whether the bit is set.
+    pub fn read(self) -> bool
+    where
+        A: Readable,
+    {
+        self.pointer.read() & self.mask() != T::ZERO
+    }
+
+    /// Sets the bit to `value`, keeping all other bits.
+    pub fn write(self, value: bool)
+    where
+        A: Readable + Writable,
+    {
+        let mask = self.mask();
+        self.pointer
+            .update(|raw| if value { raw | mask } else { raw & !mask });
+    }
+
+    /// Sets the bit, keeping all other bits.
+    pub fn set(self)
+    where
+        A: Readable + Writable,
+    {
+        self.write(true);
+    }
+
+    /// Clears the bit, keeping all other bits.
+    pub fn clear(self)
+    where
+        A: Readable + Writable,
+    {
+        self.write(false);
+    }
+
+    /// Inverts the bit, keeping all other bits.
+    pub fn toggle(self)
+    where
+        A: Readable + Writable,
+    {
+        let mask = self.mask();
+        self.pointer.update(|raw| (raw & !mask) | (!raw & mask));
+    }
+
+    fn mask(self) -> T {
+        T::ONE << self.bit
+    }
+}
+
+/// Methods for boolean registers.
+impl<A> VolatilePtr<'_, bool, A> {
+    /// Writes `true`.
+    pub fn set(self)
+    where
+        A: Writable,
+    {
+        self.write(true);
+    }
+
+    /// Writes `false`.
+    pub fn clear(self)
+    where
+        A: Writable,
+    {
+        self.write(false);
+    }
+
+    /// Inverts the value using a volatile read followed by a volatile write.
+    pub fn toggle(self)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|value| !value);
+    }
+}
+
+/// Defines the bit fields of integer registers.
+///
+/// For each register, this macro generates a module containing a `Register` marker type and
+/// a [`Field`] constant for each field. For each field, it also generates a module of the
+/// same name with [`FieldValue`] constants `SET` and `CLEAR` as well as a constant for each
+/// enumerated value.
+///
+/// Fields are declared as `NAME OFFSET(offset) NUMBITS(numbits) [values]`. Fields that do not
+/// fit into the register and enumerated values that do not fit into their field result in a
+/// compile-time error.
+///
+/// See the [`bitfield`](crate::bitfield) module for an example.
+///
+/// ```compile_fail
+/// volatile::register_bitfields! {
+///     u8,
+///     Status [
+///         // error: field `CODE` does not fit into the register
+///         CODE OFFSET(4) NUMBITS(5) [],
+///     ],
+/// }
+/// ```
+#[macro_export]
+macro_rules! register_bitfields {
+    (
+        $ty:ty,
+        $($(#[$attr:meta])* $vis:vis $register:ident [ $($fields:tt)* ]),* $(,)?
+    ) => {
+        $(
+            $(#[$attr])*
+            #[allow(non_snake_case)]
+            $vis mod $register {
+                /// Marker type for the fields of this register.
+                #[derive(Clone, Copy, Debug)]
+                pub struct Register;
+
+                $crate::__register_fields!($ty, $($fields)*);
+            }
+        )*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_fields {
+    (
+        $ty:ty,
+        $(
+            $(#[$attr:meta])*
+            $field:ident OFFSET($offset:expr) NUMBITS($numbits:expr) [
+                $($(#[$value_attr:meta])* $value:ident = $raw:expr),* $(,)?
+            ]
+        ),* $(,)?
+    ) => {
+        $(
+            $(#[$attr])*
+            #[allow(non_upper_case_globals, clippy::int_plus_one)]
+            pub const $field: $crate::bitfield::Field<$ty, Register> = {
+                ::core::assert!(
+                    $numbits > 0 && $offset + $numbits <= <$ty>::BITS as usize,
+                    ::core::concat!("field `", ::core::stringify!($field), "` does not fit into the register")
+                );
+                $crate::bitfield::Field::new(<$ty>::MAX >> (<$ty>::BITS as usize - $numbits), $offset)
+            };
+
+            $(#[$attr])*
+            #[allow(dead_code, non_snake_case, non_upper_case_globals)]
+            pub mod $field {
+                /// Sets all bits of the field.
+                pub const SET: $crate::bitfield::FieldValue<$ty, super::Register> =
+                    $crate::bitfield::FieldValue::from_parts(
+                        super::$field.mask() << super::$field.shift(),
+                        super::$field.mask() << super::$field.shift(),
+                    );
+
+                /// Clears all bits of the field.
+                pub const CLEAR: $crate::bitfield::FieldValue<$ty, super::Register> =
+                    $crate::bitfield::FieldValue::from_parts(
+                        super::$field.mask() << super::$field.shift(),
+                        0,
+                    );
+
+                $(
+                    $(#[$value_attr])*
+                    #[allow(unused_comparisons)]
+                    pub const $value: $crate::bitfield::FieldValue<$ty, super::Register> = {
+                        ::core::assert!(
+                            $raw <= super::$field.mask(),
+                            ::core::concat!("value `", ::core::stringify!($value), "` does not fit into the field")
+                        );
+                        $crate::bitfield::FieldValue::from_parts(
+                            super::$field.mask() << super::$field.shift(),
+                            $raw << super::$field.shift(),
+                        )
+                    };
+                )*
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_register_bitfields() {
+        crate::register_bitfields! {
+            u16,
+            Config [
+                ENABLE OFFSET(0) NUMBITS(1) [],
+                DIVISOR OFFSET(4) NUMBITS(8) [],
+                PARITY OFFSET(12) NUMBITS(2) [
+                    None = 0,
+                    Even = 2,
+                    Odd = 3,
+                ],
+            ],
+        }
+
+        let mut val: u16 = 0xffff;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+
+        volatile
+            .modify_field(Config::ENABLE::CLEAR + Config::DIVISOR.val(0x12) + Config::PARITY::Even);
+        assert_eq!(volatile.read(), 0xe12e);
+        assert_eq!(volatile.read_field(Config::DIVISOR), 0x12);
+        assert_eq!(volatile.read_field(Config::PARITY), 2);
+        assert!(volatile.matches(Config::PARITY::Even + Config::ENABLE::CLEAR));
+        assert!(!volatile.matches(Config::PARITY::Odd));
+
+        volatile.write_field(Config::ENABLE::SET + Config::PARITY::Odd);
+        assert_eq!(volatile.read(), 0x3001);
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut control = 0x8000_0001_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut control)) };
+        volatile.bit(0).toggle();
+        volatile.bit(31).clear();
+        volatile.bit(4).write(true);
+        volatile.bit(5).toggle();
+        assert!(volatile.bit(5).read());
+        assert!(!volatile.bit(0).read());
+        assert_eq!(control, 0x30);
+
+        let mut enable = false;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut enable)) };
+        volatile.set();
+        volatile.toggle();
+        volatile.toggle();
+        assert!(volatile.read());
+        volatile.clear();
+        assert!(!enable);
+    }
+
+    #[test]
+    #[should_panic = "bit 8 is out of range"]
+    fn test_bit_out_of_range() {
+        let mut status = 0_u8;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut status)) };
+        volatile.bit(8);
+    }
+
+    #[test]
+    fn test_read_write_bits() {
+        let mut control = 0xffff_u16;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut control)) };
+        volatile.write_bits(0b111, 3, 0b1010);
+        assert_eq!(volatile.read_bits(0b111, 3), 0b010);
+        assert_eq!(volatile.read_bits(0xff, 8), 0xff);
+        assert_eq!(control, 0xffd7);
+    }
+}
@@ -0,0 +1,239 @@
+//! Iteration over aligned bursts of volatile slices.
+//!
+//! Bulk routines that prefetch, flush, or invalidate caches work on whole cache lines, and DMA
+//! engines often transfer aligned bursts. [`VolatilePtr::bursts`] splits a volatile slice into
+//! an unaligned head, a sequence of aligned subslices of a power-of-two size, and an unaligned
+//! tail, so that such routines can handle the partial lines at the edges separately.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{burst::CACHE_LINE_SIZE, VolatilePtr};
+//!
+//! #[repr(align(64))]
+//! struct Buffer([u8; 256]);
+//!
+//! let mut buffer = Buffer([0; 256]);
+//! let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut buffer.0[8..200])) };
+//!
+//! let bursts = volatile.bursts(CACHE_LINE_SIZE);
+//! assert_eq!(bursts.head().as_raw_ptr().len(), 56);
+//! assert_eq!(bursts.tail().as_raw_ptr().len(), 8);
+//! for line in bursts {
+//!     assert_eq!(line.as_raw_ptr().len(), 64);
+//!     line.fill_bytes(0xff);
+//! }
+//! assert!(buffer.0[64..192].iter().all(|&byte| byte == 0xff));
+//! ```
+
+use core::{fmt, iter::FusedIterator, mem, ops::Range, ptr::NonNull};
+
+use crate::{access::Access, VolatilePtr};
+
+/// A common cache line size in bytes.
+///
+/// Some CPUs use larger cache lines, e.g., 128 bytes. Pass the actual size to
+/// [`VolatilePtr::bursts`] where it matters.
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// An iterator over the aligned bursts of a volatile slice.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Bursts<'a, T, A> {
+    head: VolatilePtr<'a, [T], A>,
+    tail: VolatilePtr<'a, [T], A>,
+    start: NonNull<T>,
+    burst_len: usize,
+    range: Range<usize>,
+}
+
+impl<T, A> fmt::Debug for Bursts<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bursts")
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .field("start", &self.start)
+            .field("burst_len", &self.burst_len)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<'a, T, A> Bursts<'a, T, A>
+where
+    A: Access,
+{
+    /// Returns the elements before the first aligned burst.
+    pub fn head(&self) -> VolatilePtr<'a, [T], A> {
+        self.head
+    }
+
+    /// Returns the elements after the last aligned burst.
+    pub fn tail(&self) -> VolatilePtr<'a, [T], A> {
+        self.tail
+    }
+
+    fn burst(&self, index: usize) -> VolatilePtr<'a, [T], A> {
+        // SAFETY: The burst lies within the original slice.
+        unsafe {
+            let data = self.start.add(index * self.burst_len);
+            VolatilePtr::new_restricted(
+                A::default(),
+                NonNull::slice_from_raw_parts(data, self.burst_len),
+            )
+        }
+    }
+}
+
+impl<'a, T, A> Iterator for Bursts<'a, T, A>
+where
+    A: Access,
+{
+    type Item = VolatilePtr<'a, [T], A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.burst(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T, A> DoubleEndedIterator for Bursts<'_, T, A>
+where
+    A: Access,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.burst(index))
+    }
+}
+
+impl<T, A> ExactSizeIterator for Bursts<'_, T, A> where A: Access {}
+
+impl<T, A> FusedIterator for Bursts<'_, T, A> where A: Access {}
+
+/// Methods for iterating over aligned bursts.
+impl<'a, T, A> VolatilePtr<'a, [T], A>
+where
+    A: Access,
+{
+    /// Splits the slice into an unaligned head, aligned bursts of `burst` bytes, and an
+    /// unaligned tail.
+    ///
+    /// If the elements cannot be aligned to `burst` bytes, or the slice does not contain a
+    /// whole burst, all elements are part of the head.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `burst` is not a power of two or not a multiple of the size of `T`, e.g.,
+    /// because `T` is zero-sized.
+    pub fn bursts(self, burst: usize) -> Bursts<'a, T, A> {
+        let size = mem::size_of::<T>();
+        assert!(burst.is_power_of_two(), "burst size must be a power of two");
+        // Since `burst` is a power of two, its divisors are the powers of two up to `burst`.
+        assert!(
+            size.is_power_of_two() && size <= burst,
+            "burst size must be a multiple of the element size"
+        );
+        let burst_len = burst / size;
+        let pointer = self.as_raw_ptr();
+        let len = pointer.len();
+        let start = pointer.cast::<T>();
+
+        let offset = start.as_ptr().cast::<u8>().align_offset(burst);
+        let head_len = if offset & (size - 1) == 0 {
+            offset / size
+        } else {
+            usize::MAX
+        };
+        let (head_len, count) = match len.checked_sub(head_len) {
+            Some(rest) if rest >= burst_len => (head_len, rest / burst_len),
+            _ => (len, 0),
+        };
+        let tail_start = head_len + count * burst_len;
+
+        // SAFETY: All parts lie within the slice.
+        unsafe {
+            let slice = |from: usize, len: usize| {
+                VolatilePtr::new_restricted(
+                    A::default(),
+                    NonNull::slice_from_raw_parts(start.add(from), len),
+                )
+            };
+            Bursts {
+                head: slice(0, head_len),
+                tail: slice(tail_start, len - tail_start),
+                start: start.add(head_len),
+                burst_len,
+                range: 0..count,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_bursts() {
+        #[repr(C, align(128))]
+        struct Buffer([u32; 64]);
+
+        let mut val = Buffer(core::array::from_fn(|i| i as u32));
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val.0[..])) };
+        let sub = |start: usize, end: usize| unsafe {
+            volatile
+                .map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<u32>().add(start), end - start))
+        };
+        let first =
+            |ptr: VolatilePtr<'_, [u32]>| unsafe { ptr.map(|ptr| ptr.cast::<u32>()) }.read();
+
+        let bursts = sub(3, 61).bursts(64);
+        assert_eq!(bursts.head().as_raw_ptr().len(), 13);
+        assert_eq!(bursts.tail().as_raw_ptr().len(), 13);
+        assert_eq!(bursts.len(), 2);
+        let starts: [u32; 2] = {
+            let mut bursts = bursts;
+            let last = bursts.next_back().unwrap();
+            [first(bursts.next().unwrap()), first(last)]
+        };
+        assert_eq!(starts, [16, 32]);
+
+        let bursts = volatile.bursts(128);
+        assert_eq!(bursts.head().as_raw_ptr().len(), 0);
+        assert_eq!(bursts.tail().as_raw_ptr().len(), 0);
+        assert!(bursts.map(|burst| burst.as_raw_ptr().len()).eq([32, 32]));
+
+        // no whole burst
+        let bursts = sub(1, 20).bursts(64);
+        assert_eq!(bursts.head().as_raw_ptr().len(), 19);
+        assert_eq!(bursts.tail().as_raw_ptr().len(), 0);
+        assert_eq!(bursts.count(), 0);
+
+        // elements that cannot be aligned to the burst size
+        let bytes = unsafe { VolatilePtr::new(NonNull::from(&mut val.0[..])) };
+        let misaligned = unsafe {
+            bytes.map(|ptr| {
+                NonNull::slice_from_raw_parts(ptr.cast::<u8>().add(2).cast::<u16>(), 100)
+            })
+        };
+        let bursts = misaligned.bursts(4);
+        assert_eq!(bursts.head().as_raw_ptr().len(), 1);
+        assert_eq!(bursts.len(), 49);
+        let misaligned =
+            unsafe { bytes.map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<[u16; 2]>(), 8)) };
+        assert_eq!(misaligned.bursts(16).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "burst size must be a power of two")]
+    fn test_bursts_invalid_size() {
+        let mut val = [0_u8; 16];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val[..])) };
+        volatile.bursts(12);
+    }
+}
@@ -0,0 +1,307 @@
+//! Safe casts between volatile views of plain data.
+//!
+//! Shared memory and device buffers are often handed out as byte slices. The methods in this
+//! module reinterpret such views as other types that can hold arbitrary bytes and have no
+//! padding. Size and alignment are checked at runtime, so no `unsafe` is needed at the call
+//! site.
+//!
+//! - With the `zerocopy` feature, [`VolatilePtr::try_cast`] and
+//!   [`VolatilePtr::try_cast_slice`] cast byte slices to types that implement
+//!   [`zerocopy::FromBytes`] and [`zerocopy::IntoBytes`].
+//! - With the `bytemuck` feature, [`VolatilePtr::cast_pod`], [`VolatilePtr::cast_pod_slice`],
+//!   and [`VolatilePtr::as_bytes`] cast between types that implement [`bytemuck::Pod`].
+
+use core::{mem, ptr::NonNull};
+
+use crate::{access::Access, region::VolatileRegion, VolatileError, VolatilePtr};
+
+#[cfg(feature = "zerocopy")]
+mod zerocopy_casts {
+    use zerocopy::{FromBytes, IntoBytes};
+
+    use super::*;
+
+    /// Methods for casting volatile byte slices with `zerocopy`.
+    impl<'a, A> VolatilePtr<'a, [u8], A>
+    where
+        A: Access,
+    {
+        /// Reinterprets this byte slice as a value of type `T`.
+        ///
+        /// Returns [`VolatileError::SizeMismatch`] if the length of the slice is not the size of
+        /// `T` and [`VolatileError::Misaligned`] if the slice is not properly aligned for `T`.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        /// use core::ptr::NonNull;
+        /// use volatile::{VolatileError, VolatilePtr};
+        /// use zerocopy::IntoBytes;
+        ///
+        /// let mut shared = [0_u32; 4];
+        /// let bytes = unsafe { VolatilePtr::new(NonNull::from(shared.as_mut_bytes())) };
+        ///
+        /// let array = bytes.try_cast::<[u32; 4]>()?;
+        /// array.write([1, 2, 3, 4]);
+        /// assert_eq!(shared, [1, 2, 3, 4]);
+        ///
+        /// assert_eq!(
+        ///     bytes.try_cast::<u32>().unwrap_err(),
+        ///     VolatileError::SizeMismatch { size: 4, len: 16 }
+        /// );
+        /// # Ok::<(), VolatileError>(())
+        /// ```
+        pub fn try_cast<T>(self) -> Result<VolatilePtr<'a, T, A>, VolatileError>
+        where
+            T: FromBytes + IntoBytes,
+        {
+            // SAFETY: The value is valid for any bytes. Writing it cannot write uninitialized
+            // bytes since it has no padding.
+            unsafe { cast(self.as_raw_ptr()) }
+        }
+
+        /// Reinterprets this byte slice as a slice of `T`.
+        ///
+        /// Returns [`VolatileError::SizeMismatch`] if the length of the slice is not a multiple
+        /// of the size of `T` and [`VolatileError::Misaligned`] if the slice is not properly
+        /// aligned for `T`.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        /// use core::ptr::NonNull;
+        /// use volatile::{VolatileError, VolatilePtr};
+        /// use zerocopy::IntoBytes;
+        ///
+        /// let mut shared = [0_u32; 4];
+        /// let bytes = unsafe { VolatilePtr::new(NonNull::from(shared.as_mut_bytes())) };
+        ///
+        /// let words = bytes.try_cast_slice::<u32>()?;
+        /// assert_eq!(words.as_raw_ptr().len(), 4);
+        ///
+        /// let odd = bytes.try_cast_slice::<[u8; 3]>();
+        /// assert_eq!(odd.unwrap_err(), VolatileError::SizeMismatch { size: 3, len: 16 });
+        /// # Ok::<(), VolatileError>(())
+        /// ```
+        pub fn try_cast_slice<T>(self) -> Result<VolatilePtr<'a, [T], A>, VolatileError>
+        where
+            T: FromBytes + IntoBytes,
+        {
+            // SAFETY: The elements are valid for any bytes. Writing them cannot write
+            // uninitialized bytes since they have no padding.
+            unsafe { cast_slice(self.as_raw_ptr()) }
+        }
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_casts {
+    use bytemuck::Pod;
+
+    use super::*;
+
+    /// Methods for casting volatile values with `bytemuck`.
+    impl<'a, T, A> VolatilePtr<'a, T, A>
+    where
+        T: Pod,
+        A: Access,
+    {
+        /// Reinterprets this value as a value of type `U`.
+        ///
+        /// Returns [`VolatileError::SizeMismatch`] if `T` and `U` differ in size and
+        /// [`VolatileError::Misaligned`] if the value is not properly aligned for `U`.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        /// use volatile::{VolatileError, VolatilePtr};
+        ///
+        /// let mut value = [0_u16; 2];
+        /// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+        ///
+        /// volatile.cast_pod::<[u8; 4]>()?.write([1, 1, 2, 2]);
+        /// assert_eq!(value, [0x0101, 0x0202]);
+        /// assert_eq!(
+        ///     volatile.cast_pod::<u64>().unwrap_err(),
+        ///     VolatileError::SizeMismatch { size: 8, len: 4 }
+        /// );
+        /// # Ok::<(), VolatileError>(())
+        /// ```
+        pub fn cast_pod<U>(self) -> Result<VolatilePtr<'a, U, A>, VolatileError>
+        where
+            U: Pod,
+        {
+            let pointer =
+                NonNull::slice_from_raw_parts(self.as_raw_ptr().cast(), mem::size_of::<T>());
+            // SAFETY: `U` is `Pod`.
+            unsafe { cast(pointer) }
+        }
+
+        /// Returns a view of the bytes of this value.
+        pub fn as_bytes(self) -> VolatilePtr<'a, [u8], A> {
+            let pointer =
+                NonNull::slice_from_raw_parts(self.as_raw_ptr().cast(), mem::size_of::<T>());
+            // SAFETY: The bytes are within the value and `T` is valid for any bytes.
+            unsafe { self.map(|_| pointer) }
+        }
+    }
+
+    /// Methods for casting volatile slices with `bytemuck`.
+    impl<'a, T, A> VolatilePtr<'a, [T], A>
+    where
+        T: Pod,
+        A: Access,
+    {
+        /// Reinterprets this slice as a slice of `U`.
+        ///
+        /// Returns [`VolatileError::SizeMismatch`] if the size of the slice in bytes is not a
+        /// multiple of the size of `U` and [`VolatileError::Misaligned`] if the slice is not
+        /// properly aligned for `U`.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        /// use core::ptr::NonNull;
+        /// use volatile::{VolatileError, VolatilePtr};
+        ///
+        /// let mut words = [0_u32; 4];
+        /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut words[..])) };
+        ///
+        /// let bytes = volatile.cast_pod_slice::<u8>()?;
+        /// assert_eq!(bytes.as_raw_ptr().len(), 16);
+        /// # Ok::<(), VolatileError>(())
+        /// ```
+        pub fn cast_pod_slice<U>(self) -> Result<VolatilePtr<'a, [U], A>, VolatileError>
+        where
+            U: Pod,
+        {
+            let pointer = self.as_raw_ptr();
+            let byte_len = pointer.len() * mem::size_of::<T>();
+            // SAFETY: `U` is `Pod`.
+            unsafe { cast_slice(NonNull::slice_from_raw_parts(pointer.cast(), byte_len)) }
+        }
+    }
+}
+
+/// Returns a volatile pointer to the value of type `T` that covers exactly `bytes`.
+///
+/// ## Safety
+///
+/// `T` must be valid for any bytes and must not have padding.
+unsafe fn cast<'a, T, A>(bytes: NonNull<[u8]>) -> Result<VolatilePtr<'a, T, A>, VolatileError>
+where
+    A: Access,
+{
+    if bytes.len() != mem::size_of::<T>() {
+        return Err(VolatileError::SizeMismatch {
+            size: mem::size_of::<T>(),
+            len: bytes.len(),
+        });
+    }
+    unsafe { region(bytes).at_unchecked(0) }
+}
+
+/// Returns a volatile pointer to the slice of `T` that covers exactly `bytes`.
+///
+/// ## Safety
+///
+/// `T` must be valid for any bytes and must not have padding.
+unsafe fn cast_slice<'a, T, A>(
+    bytes: NonNull<[u8]>,
+) -> Result<VolatilePtr<'a, [T], A>, VolatileError>
+where
+    A: Access,
+{
+    unsafe { region(bytes).slice_unchecked(0..bytes.len()) }
+}
+
+fn region<'a, A>(bytes: NonNull<[u8]>) -> VolatileRegion<'a, A>
+where
+    A: Access,
+{
+    // SAFETY: The bytes are covered by a volatile pointer with access `A`.
+    unsafe { VolatileRegion::new_restricted(A::default(), bytes.cast(), bytes.len()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn test_try_cast() {
+        let mut array = [0_u64; 2];
+        let bytes = unsafe {
+            VolatilePtr::new(NonNull::slice_from_raw_parts(
+                NonNull::from(&mut array).cast::<u8>(),
+                16,
+            ))
+        };
+
+        assert_eq!(bytes.try_cast::<u128>().map(|_| ()), Ok(()));
+        assert_eq!(
+            bytes.try_cast::<u64>().map(|_| ()),
+            Err(VolatileError::SizeMismatch { size: 8, len: 16 })
+        );
+        let unaligned = unsafe {
+            bytes.map(|pointer| NonNull::slice_from_raw_parts(pointer.cast::<u8>().add(1), 8))
+        };
+        assert_eq!(
+            unaligned.try_cast::<u64>().map(|_| ()),
+            Err(VolatileError::Misaligned {
+                required: 8,
+                found: 1
+            })
+        );
+        assert_eq!(unaligned.try_cast::<[u8; 8]>().map(|_| ()), Ok(()));
+
+        let words = bytes.try_cast_slice::<u32>().unwrap();
+        assert_eq!(words.as_raw_ptr().len(), 4);
+        bytes.try_cast::<[u64; 2]>().unwrap().write([3, 4]);
+        assert_eq!(array, [3, 4]);
+        assert_eq!(
+            bytes.try_cast_slice::<[u8; 3]>().map(|_| ()),
+            Err(VolatileError::SizeMismatch { size: 3, len: 16 })
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_cast_pod() {
+        let mut array = [0_u32; 4];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array)) };
+
+        volatile
+            .cast_pod::<[u64; 2]>()
+            .unwrap()
+            .write([0, u64::MAX]);
+        assert_eq!(array, [0, 0, u32::MAX, u32::MAX]);
+        assert_eq!(
+            volatile.cast_pod::<u64>().map(|_| ()),
+            Err(VolatileError::SizeMismatch { size: 8, len: 16 })
+        );
+        assert_eq!(volatile.as_bytes().as_raw_ptr().len(), 16);
+
+        let slice = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+        let halves = slice.cast_pod_slice::<u16>().unwrap();
+        assert_eq!(halves.as_raw_ptr().len(), 8);
+        assert_eq!(
+            slice.cast_pod_slice::<[u8; 3]>().map(|_| ()),
+            Err(VolatileError::SizeMismatch { size: 3, len: 16 })
+        );
+        let unaligned = unsafe {
+            slice
+                .cast_pod_slice::<u8>()
+                .unwrap()
+                .map(|pointer| NonNull::slice_from_raw_parts(pointer.cast::<u8>().add(1), 8))
+        };
+        assert_eq!(
+            unaligned.cast_pod_slice::<u32>().map(|_| ()),
+            Err(VolatileError::Misaligned {
+                required: 4,
+                found: 1
+            })
+        );
+    }
+}
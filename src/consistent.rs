@@ -0,0 +1,400 @@
+//! Consistent reads of multi-field structures guarded by a generation counter.
+//!
+//! Some devices and firmware interfaces, e.g., the virtio configuration space, expose a
+//! generation counter that changes whenever the guarded fields change. A consistent snapshot
+//! of the fields is obtained by reading the counter, reading the fields, and re-reading the
+//! counter until it did not change in between. [`VolatilePtr::read_consistent`] implements this
+//! loop. [`SeqLock`] additionally implements the writer side of this protocol.
+//!
+//! The counters are accessed atomically, so that a reader synchronizes with a writer that runs
+//! on another core. Only the guarded fields are accessed with volatile accesses.
+//!
+//! Free-running 64-bit counters that are split across two 32-bit registers, e.g., of HPETs,
+//! performance monitors, or timers, can be read consistently with [`read_split_counter`].
+
+use core::{
+    fmt, hint,
+    sync::atomic::{fence, AtomicU32, Ordering},
+};
+
+use crate::{
+    access::{ReadOnly, Readable},
+    width::SplitOrder,
+    VolatilePtr,
+};
+
+/// Unsigned integers that can be used as generation counters.
+///
+/// This trait is sealed and implemented for the unsigned integers that have an atomic
+/// counterpart on the target.
+pub trait Generation: Copy + Eq + generation::Sealed {}
+
+mod generation {
+    use core::sync::atomic::Ordering;
+
+    pub trait Sealed: Sized {
+        /// Loads the value behind `ptr` atomically.
+        ///
+        /// ## Safety
+        ///
+        /// `ptr` must be valid for reads.
+        unsafe fn load(ptr: *mut Self, order: Ordering) -> Self;
+    }
+}
+
+macro_rules! impl_generation {
+    ($($ty:ty => $atomic:ident, $size:literal;)*) => {
+        $(
+            #[cfg(target_has_atomic = $size)]
+            impl generation::Sealed for $ty {
+                unsafe fn load(ptr: *mut Self, order: Ordering) -> Self {
+                    use core::sync::atomic::$atomic;
+
+                    assert!(
+                        ptr.cast::<$atomic>().is_aligned(),
+                        "generation counter is not aligned for atomic accesses"
+                    );
+                    // SAFETY: The caller guarantees that `ptr` is valid for reads and we
+                    // checked the alignment.
+                    unsafe { $atomic::from_ptr(ptr) }.load(order)
+                }
+            }
+
+            #[cfg(target_has_atomic = $size)]
+            impl Generation for $ty {}
+        )*
+    };
+}
+
+impl_generation! {
+    u8 => AtomicU8, "8";
+    u16 => AtomicU16, "16";
+    u32 => AtomicU32, "32";
+    u64 => AtomicU64, "64";
+    usize => AtomicUsize, "ptr";
+}
+
+/// Methods for generation counters.
+impl<G, A> VolatilePtr<'_, G, A>
+where
+    G: Generation,
+    A: Readable,
+{
+    /// Calls `f` until the generation counter behind `self` is stable and returns the result.
+    ///
+    /// `f` is called again whenever the counter changed while `f` was running, so it should
+    /// only read the guarded fields without any other side effects.
+    ///
+    /// The counter is loaded atomically with `Acquire` ordering, so that it synchronizes with a
+    /// writer on another core that stores the counter with `Release` ordering.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the counter is not aligned for atomic accesses, e.g., a `u64` that is only
+    /// 4-byte aligned on a 32-bit target.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{map_field, VolatilePtr};
+    ///
+    /// #[repr(C)]
+    /// struct VirtioCommonCfg {
+    ///     config_generation: u8,
+    ///     mac: [u8; 6],
+    /// }
+    ///
+    /// let mut cfg = VirtioCommonCfg { config_generation: 3, mac: [1, 2, 3, 4, 5, 6] };
+    /// let volatile = unsafe { VolatilePtr::new((&mut cfg).into()) };
+    ///
+    /// let generation = map_field!(volatile.config_generation).read_only();
+    /// let mac = generation.read_consistent(|| map_field!(volatile.mac).read());
+    /// assert_eq!(mac, [1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn read_consistent<R, F>(self, mut f: F) -> R
+    where
+        F: FnMut() -> R,
+    {
+        let load = || {
+            if crate::backend::is_installed() {
+                self.read()
+            } else {
+                // SAFETY: `self` is valid for reads.
+                unsafe { G::load(self.as_raw_ptr().as_ptr(), Ordering::Acquire) }
+            }
+        };
+
+        let mut generation = load();
+        loop {
+            let value = f();
+            // The reads of the fields must not be reordered after the second load.
+            fence(Ordering::Acquire);
+            let next = load();
+            if next == generation {
+                return value;
+            }
+            generation = next;
+        }
+    }
+}
+
+/// A sequence lock that publishes a payload of type `T` from a single writer to readers.
+///
+/// The writer increments the sequence word before and after modifying the payload, so that it
+/// is odd while a write is in progress. Readers retry until they observe the same even
+/// sequence word before and after reading the payload. The sequence word is accessed
+/// atomically, with `Acquire` loads in readers and a `Release` store at the end of a write,
+/// while the payload is accessed with volatile accesses. Unlike [`VolatilePtr::read_consistent`],
+/// this also implements the writer side, e.g., for publishing timestamps or statistics blocks
+/// to another core through shared memory.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::{consistent::SeqLock, map_field, VolatilePtr};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// #[repr(C)]
+/// struct Stats {
+///     packets: u64,
+///     bytes: u64,
+/// }
+///
+/// let mut sequence = 0_u32;
+/// let mut stats = Stats { packets: 0, bytes: 0 };
+/// let lock = unsafe {
+///     SeqLock::new(
+///         VolatilePtr::new((&mut sequence).into()),
+///         VolatilePtr::new((&mut stats).into()),
+///     )
+/// };
+///
+/// lock.write(|stats| {
+///     map_field!(stats.packets).update(|packets| packets + 1);
+///     map_field!(stats.bytes).update(|bytes| bytes + 1500);
+/// });
+/// assert_eq!(lock.read(), Stats { packets: 1, bytes: 1500 });
+/// assert_eq!(sequence, 2);
+/// ```
+pub struct SeqLock<'a, T>
+where
+    T: ?Sized,
+{
+    sequence: &'a AtomicU32,
+    payload: VolatilePtr<'a, T>,
+}
+
+impl<T> Copy for SeqLock<'_, T> where T: ?Sized {}
+
+impl<T> Clone for SeqLock<'_, T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> fmt::Debug for SeqLock<'_, T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqLock")
+            .field("sequence", &self.sequence)
+            .field("payload", &self.payload)
+            .finish()
+    }
+}
+
+impl<'a, T> SeqLock<'a, T>
+where
+    T: ?Sized,
+{
+    /// Creates a sequence lock from its sequence word and its payload.
+    ///
+    /// The sequence word is used as it is, so that both the writer and the readers can create
+    /// a `SeqLock`. Initialize it to zero before the first use.
+    ///
+    /// ## Safety
+    ///
+    /// At most one context may write through the sequence lock at a time. The sequence word
+    /// and the payload must only be written through sequence locks. The sequence word must be
+    /// valid for atomic accesses, i.e., be aligned for [`AtomicU32`] and located in memory that
+    /// supports atomic accesses.
+    pub unsafe fn new(sequence: VolatilePtr<'a, u32>, payload: VolatilePtr<'a, T>) -> Self {
+        // SAFETY: The caller guarantees that the sequence word is valid for atomic accesses
+        // for `'a`.
+        let sequence = unsafe { AtomicU32::from_ptr(sequence.as_raw_ptr().as_ptr()) };
+        Self { sequence, payload }
+    }
+
+    /// Calls `f` with a read-only pointer to the payload until no write happened concurrently
+    /// and returns the result.
+    ///
+    /// `f` is called again whenever a write happened while `f` was running, so it should only
+    /// read the payload without any other side effects.
+    pub fn read_with<R, F>(&self, mut f: F) -> R
+    where
+        F: FnMut(VolatilePtr<'a, T, ReadOnly>) -> R,
+    {
+        loop {
+            // Synchronizes with the end of the last write.
+            let sequence = self.sequence.load(Ordering::Acquire);
+            if sequence & 1 != 0 {
+                hint::spin_loop();
+                continue;
+            }
+            let value = f(self.payload.read_only());
+            // The payload reads must not be reordered after the second load.
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Acquire) == sequence {
+                return value;
+            }
+        }
+    }
+
+    /// Calls `f` with a pointer to the payload while marking a write as in progress.
+    pub fn write<F>(&self, f: F)
+    where
+        F: FnOnce(VolatilePtr<'a, T>),
+    {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        self.sequence
+            .store(sequence.wrapping_add(1), Ordering::Relaxed);
+        // The payload writes must not be reordered before the odd sequence word.
+        fence(Ordering::Release);
+        f(self.payload);
+        // Publishes the payload to the readers.
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl<T> SeqLock<'_, T>
+where
+    T: Copy,
+{
+    /// Reads a consistent copy of the payload.
+    pub fn read(&self) -> T {
+        self.read_with(|payload| payload.read())
+    }
+}
+
+/// Reads a consistent value of a 64-bit counter that is split into two 32-bit registers.
+///
+/// A plain read of both halves is wrong whenever the low half overflows between the two
+/// accesses. With [`SplitOrder::HighFirst`], this reads the high half, the low half, and the
+/// high half again, and retries until both reads of the high half match. With
+/// [`SplitOrder::LowFirst`], e.g., for devices that latch the high half on a read of the low
+/// half, this reads the low half, the high half, and the low half again, and retries if the low
+/// half wrapped around in between. The latter requires the counter to count upwards.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::{consistent::read_split_counter, width::SplitOrder, VolatilePtr};
+///
+/// let mut hpet_counter = [0xffff_fff0_u32, 1];
+/// let volatile = unsafe { VolatilePtr::new((&mut hpet_counter).into()) };
+/// let (low, high) = unsafe {
+///     (
+///         volatile.map(|ptr| ptr.cast::<u32>()),
+///         volatile.map(|ptr| ptr.cast::<u32>().add(1)),
+///     )
+/// };
+/// assert_eq!(
+///     read_split_counter(high, low, SplitOrder::HighFirst),
+///     0x1_ffff_fff0
+/// );
+/// ```
+pub fn read_split_counter<A>(
+    high: VolatilePtr<'_, u32, A>,
+    low: VolatilePtr<'_, u32, A>,
+    order: SplitOrder,
+) -> u64
+where
+    A: Readable,
+{
+    let combine = |high: u32, low: u32| (u64::from(high) << 32) | u64::from(low);
+    match order {
+        SplitOrder::HighFirst => {
+            let mut first = high.read();
+            loop {
+                let value = low.read();
+                let second = high.read();
+                if first == second {
+                    return combine(first, value);
+                }
+                first = second;
+                hint::spin_loop();
+            }
+        }
+        SplitOrder::LowFirst => {
+            let mut first = low.read();
+            loop {
+                let value = high.read();
+                let second = low.read();
+                if second >= first {
+                    return combine(value, first);
+                }
+                first = second;
+                hint::spin_loop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-util")]
+    use crate::backend::{mock::MockBackend, with_backend};
+    use crate::{consistent::SeqLock, VolatilePtr};
+    #[cfg(feature = "test-util")]
+    use core::cell::Cell;
+    use core::ptr::NonNull;
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_read_consistent_retries() {
+        let mut generation = 0_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut generation)) };
+
+        // The generation changes once while the fields are read.
+        let mock = MockBackend::new();
+        for value in [1_u32, 2, 2] {
+            mock.push_read(volatile.as_raw_ptr().as_ptr(), &value.to_ne_bytes());
+        }
+
+        let calls = Cell::new(0);
+        let result = unsafe {
+            with_backend(&mock, || {
+                volatile.read_consistent(|| {
+                    calls.set(calls.get() + 1);
+                    calls.get()
+                })
+            })
+        };
+        assert_eq!(result, 2);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_seqlock() {
+        let mut sequence = 0_u32;
+        let mut payload = [0_u64; 2];
+        let lock = unsafe {
+            SeqLock::new(
+                VolatilePtr::new(NonNull::from(&mut sequence)),
+                VolatilePtr::new(NonNull::from(&mut payload)),
+            )
+        };
+
+        lock.write(|payload| payload.write([1, 2]));
+        assert_eq!(lock.read(), [1, 2]);
+        assert_eq!(lock.read_with(|payload| payload.read()[1]), 2);
+        lock.write(|payload| payload.update(|[a, b]| [a + 1, b + 1]));
+        assert_eq!(lock.read(), [2, 3]);
+        assert_eq!(sequence, 4);
+    }
+}
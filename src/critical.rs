@@ -0,0 +1,77 @@
+//! Interrupt-safe read-modify-write operations.
+//!
+//! On single-core microcontrollers, registers are often shared between thread mode and
+//! interrupt handlers. A plain [`VolatilePtr::update`] races with an interrupt handler that
+//! modifies the same register between the read and the write. With the `critical-section`
+//! feature, the methods in this module perform the read-modify-write within
+//! [`critical_section::with`], which disables interrupts on such targets.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::VolatilePtr;
+//!
+//! let mut value = 0b0101_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+//!
+//! volatile.set_bits_in_critical_section(0b0010);
+//! volatile.clear_bits_in_critical_section(0b0001);
+//! volatile.update_in_critical_section(|value| value << 4);
+//! assert_eq!(value, 0b0110_0000);
+//! ```
+
+use core::ops::{BitAnd, BitOr, Not};
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// Methods for interrupt-safe read-modify-write operations.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: Copy,
+    A: Readable + Writable,
+{
+    /// Updates the contained value like [`Self::update`] within a critical section.
+    pub fn update_in_critical_section<F>(self, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        critical_section::with(|_| self.update(f));
+    }
+
+    /// Sets the bits of `mask` in the contained value within a critical section.
+    pub fn set_bits_in_critical_section(self, mask: T)
+    where
+        T: BitOr<Output = T>,
+    {
+        self.update_in_critical_section(|value| value | mask);
+    }
+
+    /// Clears the bits of `mask` in the contained value within a critical section.
+    pub fn clear_bits_in_critical_section(self, mask: T)
+    where
+        T: BitAnd<Output = T> + Not<Output = T>,
+    {
+        self.update_in_critical_section(|value| value & !mask);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_critical_section_bits() {
+        let mut val: u8 = 0b1010;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        volatile.set_bits_in_critical_section(0b0101);
+        assert_eq!(volatile.read(), 0b1111);
+        volatile.clear_bits_in_critical_section(0b1001);
+        assert_eq!(volatile.read(), 0b0110);
+        volatile.update_in_critical_section(|v| v + 1);
+        assert_eq!(val, 0b0111);
+    }
+}
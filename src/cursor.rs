@@ -0,0 +1,258 @@
+//! Sequential typed access to volatile byte regions.
+//!
+//! A [`VolatileCursor`] tracks a position within a [`VolatileRegion`] and advances it with
+//! every read and write. This removes the manual offset bookkeeping when parsing or emitting
+//! variable-length device structures, e.g., virtio net headers, SCSI command descriptor
+//! blocks, or firmware tables.
+//!
+//! Integers are accessed as byte arrays with an explicit byte order, so they do not need to be
+//! aligned. The width of the volatile accesses for such integers is unspecified, see
+//! [`VolatilePtr::read_u32_le`](crate::VolatilePtr::read_u32_le). Whole values of other types are accessed with [`read_struct`] and
+//! [`write_struct`], which require their natural alignment. Since the region may contain
+//! arbitrary bytes, these types must implement [`NoPadding`].
+//!
+//! All accesses are bounds-checked. A failed access returns an error and does not move the
+//! cursor.
+//!
+//! [`read_struct`]: VolatileCursor::read_struct
+//! [`write_struct`]: VolatileCursor::write_struct
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{cursor::VolatileCursor, region::VolatileRegion, VolatileError};
+//!
+//! let mut buffer = [0_u8; 16];
+//! let region = unsafe { VolatileRegion::new(NonNull::from(&mut buffer).cast(), 16) };
+//!
+//! let mut writer = VolatileCursor::new(region);
+//! writer.write_u8(0x28)?;
+//! writer.skip(1)?;
+//! writer.write_u32_be(0x1234)?;
+//! writer.write_u16_le(8)?;
+//! assert_eq!(writer.position(), 8);
+//! assert_eq!(buffer[..8], [0x28, 0, 0, 0, 0x12, 0x34, 8, 0]);
+//!
+//! let mut reader = VolatileCursor::new(region);
+//! assert_eq!(reader.read_u8()?, 0x28);
+//! reader.skip(1)?;
+//! assert_eq!(reader.read_u32_be()?, 0x1234);
+//! assert_eq!(reader.read_u16_le()?, 8);
+//! assert!(reader.skip(9).is_err());
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::{fmt, mem};
+
+use crate::{
+    access::{Access, ReadWrite, Readable, Writable},
+    endian::int_accessors,
+    padding::NoPadding,
+    region::VolatileRegion,
+    VolatileError, VolatilePtr,
+};
+
+/// A cursor that reads and writes consecutive values in a [`VolatileRegion`].
+///
+/// See the [module-level documentation](self) for details.
+pub struct VolatileCursor<'a, A = ReadWrite> {
+    region: VolatileRegion<'a, A>,
+    position: usize,
+}
+
+impl<A> Clone for VolatileCursor<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            region: self.region,
+            position: self.position,
+        }
+    }
+}
+
+impl<A> fmt::Debug for VolatileCursor<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileCursor")
+            .field("region", &self.region)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl<'a, A> VolatileCursor<'a, A>
+where
+    A: Access,
+{
+    /// Creates a cursor at the start of `region`.
+    pub const fn new(region: VolatileRegion<'a, A>) -> Self {
+        Self {
+            region,
+            position: 0,
+        }
+    }
+
+    /// Creates a cursor at the start of the given volatile byte slice.
+    pub fn from_ptr(slice: VolatilePtr<'a, [u8], A>) -> Self {
+        Self::new(VolatileRegion::from_ptr(slice))
+    }
+
+    /// Returns the underlying region.
+    pub const fn region(&self) -> VolatileRegion<'a, A> {
+        self.region
+    }
+
+    /// Returns the current byte offset within the region.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor to the given byte offset within the region.
+    ///
+    /// The position may be past the end of the region, in which case all further accesses
+    /// fail.
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Returns the number of bytes between the cursor and the end of the region.
+    pub const fn remaining(&self) -> usize {
+        self.region.len().saturating_sub(self.position)
+    }
+
+    /// Advances the cursor by `n` bytes without accessing them.
+    pub fn skip(&mut self, n: usize) -> Result<(), VolatileError> {
+        self.advance(n).map(|_| ())
+    }
+
+    /// Returns a volatile pointer to the value of type `T` at the cursor and advances the
+    /// cursor past it.
+    pub fn next_ptr<T>(&mut self) -> Result<VolatilePtr<'a, T, A>, VolatileError>
+    where
+        T: NoPadding,
+    {
+        let pointer = self.region.at::<T>(self.position)?;
+        self.position += mem::size_of::<T>();
+        Ok(pointer)
+    }
+
+    /// Reads a value of type `T` at the cursor and advances the cursor.
+    ///
+    /// The value must be naturally aligned within the region.
+    pub fn read_struct<T>(&mut self) -> Result<T, VolatileError>
+    where
+        T: NoPadding,
+        A: Readable,
+    {
+        let value = self.region.read_at(self.position)?;
+        self.position += mem::size_of::<T>();
+        Ok(value)
+    }
+
+    /// Writes a value of type `T` at the cursor and advances the cursor.
+    ///
+    /// The value must be naturally aligned within the region.
+    pub fn write_struct<T>(&mut self, value: T) -> Result<(), VolatileError>
+    where
+        T: NoPadding,
+        A: Writable,
+    {
+        self.region.write_at(self.position, value)?;
+        self.position += mem::size_of::<T>();
+        Ok(())
+    }
+
+    /// Reads `buf.len()` bytes at the cursor and advances the cursor.
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), VolatileError>
+    where
+        A: Readable,
+    {
+        let start = self.position;
+        let end = self.advance(buf.len())?;
+        self.region.slice::<u8>(start..end)?.copy_into_slice(buf);
+        Ok(())
+    }
+
+    /// Writes `buf` at the cursor and advances the cursor.
+    pub fn write_bytes(&mut self, buf: &[u8]) -> Result<(), VolatileError>
+    where
+        A: Writable,
+    {
+        let start = self.position;
+        let end = self.advance(buf.len())?;
+        self.region.slice::<u8>(start..end)?.copy_from_slice(buf);
+        Ok(())
+    }
+
+    /// Reads a `u8` and advances the cursor.
+    pub fn read_u8(&mut self) -> Result<u8, VolatileError>
+    where
+        A: Readable,
+    {
+        self.read_struct()
+    }
+
+    /// Writes a `u8` and advances the cursor.
+    pub fn write_u8(&mut self, value: u8) -> Result<(), VolatileError>
+    where
+        A: Writable,
+    {
+        self.write_struct(value)
+    }
+
+    int_accessors! {
+        ([&mut] self), read_struct, write_struct, "and advances the cursor";
+        u16: read_u16_le, read_u16_be, write_u16_le, write_u16_be;
+        u32: read_u32_le, read_u32_be, write_u32_le, write_u32_be;
+        u64: read_u64_le, read_u64_be, write_u64_le, write_u64_be;
+    }
+
+    /// Advances the position by `n` bytes if they are within the region and returns the new
+    /// position.
+    fn advance(&mut self, n: usize) -> Result<usize, VolatileError> {
+        let end = self.position.saturating_add(n);
+        if end > self.region.len() {
+            return Err(VolatileError::OutOfBounds {
+                index: end,
+                len: self.region.len(),
+            });
+        }
+        self.position = end;
+        Ok(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cursor::VolatileCursor, VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_cursor() {
+        let mut buffer = [0_u8; 12];
+        let slice = NonNull::slice_from_raw_parts(NonNull::from(&mut buffer).cast::<u8>(), 12);
+        let volatile = unsafe { VolatilePtr::new(slice) };
+
+        let mut cursor = VolatileCursor::from_ptr(volatile);
+        cursor.write_u8(1).unwrap();
+        cursor.write_u16_be(0x0203).unwrap();
+        cursor.write_bytes(&[4, 5]).unwrap();
+        cursor.write_u32_le(0x0908_0706).unwrap();
+        assert_eq!(cursor.remaining(), 3);
+        assert_eq!(
+            cursor.write_u32_le(0),
+            Err(VolatileError::OutOfBounds { index: 13, len: 12 })
+        );
+        assert_eq!(cursor.position(), 9);
+        assert_eq!(buffer[..9], [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let mut cursor = VolatileCursor::from_ptr(volatile.read_only());
+        cursor.skip(3).unwrap();
+        let mut bytes = [0; 2];
+        cursor.read_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [4, 5]);
+        assert_eq!(cursor.read_u32_be().unwrap(), 0x0607_0809);
+        cursor.set_position(8);
+        assert_eq!(cursor.next_ptr::<u8>().unwrap().read(), 9);
+        assert_eq!(cursor.position(), 9);
+    }
+}
@@ -0,0 +1,131 @@
+//! Formatting of the referenced values.
+//!
+//! The [`Debug`](fmt::Debug) implementations of [`VolatilePtr`] and [`VolatileRef`] only print
+//! the address, since formatting must never read a register implicitly: reading a FIFO or a
+//! clear-on-read status register, e.g., because it is part of a logged struct, would change the
+//! device state. To print the value instead, create a [`DebugValue`] adapter explicitly through
+//! [`VolatilePtr::debug_value`] or [`VolatileRef::debug_value`]. It performs a volatile read
+//! every time it is formatted.
+//!
+//! For structs with fields marked with `#[volatile(read_side_effects)]`, format a snapshot from
+//! `#[derive(VolatileFieldAccess)]` instead, which skips these fields.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::VolatileRef;
+//!
+//! let mut value = 42_u32;
+//! let volatile = VolatileRef::from_mut_ref(&mut value);
+//! assert_eq!(format!("{:?}", volatile.debug_value()), "42");
+//! assert_eq!(format!("{:#x?}", volatile.debug_value()), "0x2a");
+//! ```
+
+use core::fmt;
+
+use crate::{
+    access::{ReadOnly, Readable, RestrictAccess},
+    VolatilePtr, VolatileRef,
+};
+
+/// Formats the value behind a volatile pointer by reading it.
+///
+/// See the [module-level documentation](self) for details.
+pub struct DebugValue<'a, T, A> {
+    pointer: VolatilePtr<'a, T, A>,
+}
+
+impl<T, A> Clone for DebugValue<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> Copy for DebugValue<'_, T, A> {}
+
+impl<T, A> fmt::Debug for DebugValue<'_, T, A>
+where
+    T: Copy + fmt::Debug,
+    A: Readable,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.pointer.read(), f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T, A> defmt::Format for DebugValue<'_, T, A>
+where
+    T: Copy + defmt::Format,
+    A: Readable,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        self.pointer.read().format(f);
+    }
+}
+
+/// Methods for formatting the referenced value.
+impl<'a, T, A> VolatilePtr<'a, T, A> {
+    /// Returns an adapter that volatile-reads the value whenever it is formatted.
+    pub fn debug_value(self) -> DebugValue<'a, T, A>
+    where
+        T: Copy,
+        A: Readable,
+    {
+        DebugValue { pointer: self }
+    }
+}
+
+/// Methods for formatting the referenced value.
+impl<T, A> VolatileRef<'_, T, A> {
+    /// Returns an adapter that volatile-reads the value whenever it is formatted.
+    pub fn debug_value(&self) -> DebugValue<'_, T, ReadOnly>
+    where
+        T: Copy,
+        A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+    {
+        DebugValue {
+            pointer: self.as_ptr(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use crate::{
+        backend::{mock::MockBackend, with_backend},
+        VolatilePtr, VolatileRef,
+    };
+    use core::ptr::NonNull;
+    use std::format;
+
+    #[test]
+    fn test_debug_value() {
+        #[derive(Debug, Clone, Copy)]
+        struct Status {
+            #[allow(dead_code)]
+            ready: bool,
+        }
+
+        let mut val = Status { ready: true };
+        let volatile = VolatileRef::from_mut_ref(&mut val);
+        let mock = MockBackend::new();
+        let addr = format!("{:?}", volatile);
+        unsafe { with_backend(&mock, || format!("{:?}", volatile.as_ptr())) };
+        assert!(mock.take_accesses().is_empty());
+        assert!(addr.starts_with("0x"));
+
+        let debug = volatile.debug_value();
+        unsafe {
+            with_backend(&mock, || {
+                assert_eq!(format!("{debug:?}"), "Status { ready: true }");
+            })
+        };
+        assert_eq!(mock.take_accesses().len(), 1);
+
+        let mut val = 255_u8;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        volatile.write(17);
+        assert_eq!(format!("{:#x?}", volatile.debug_value()), "0x11");
+    }
+}
@@ -0,0 +1,384 @@
+//! Buffers that are shared with a device through DMA.
+//!
+//! A [`VolatileDmaBuffer`] is owned either by the CPU or by the device. The CPU may only
+//! access the buffer while it owns it. [`VolatileDmaBuffer::sync_for_device`] hands the buffer
+//! over to the device and returns a [`DeviceOwned`] buffer, which does not provide any access
+//! to the data. [`DeviceOwned::sync_for_cpu`] hands the buffer back to the CPU. Both
+//! synchronization points issue memory fences and perform cache maintenance using
+//! [`CacheOps`], which does nothing by default for platforms with coherent DMA.
+//!
+//! The ownership states are advisory only: since [`VolatilePtr`] is `Copy`, the pointer that
+//! was passed to [`VolatileDmaBuffer::new`] and the pointers returned by
+//! [`VolatileDmaBuffer::as_ptr`] can still be used while the device owns the buffer. The
+//! states document the protocol and perform the synchronization, but they cannot prevent such
+//! accesses.
+//!
+//! For memory that is shared with a device without ownership transfers,
+//! [`VolatilePtr::copy_from_slice_with`] and [`VolatilePtr::copy_into_slice_with`] perform
+//! the cache maintenance for a single bulk copy.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{dma::VolatileDmaBuffer, VolatilePtr};
+//!
+//! let mut data = [0_u32; 4];
+//! let mut buffer = VolatileDmaBuffer::new(unsafe { VolatilePtr::new((&mut data).into()) });
+//!
+//! buffer.as_ptr().write([1, 2, 3, 4]);
+//!
+//! // Hand the buffer to the device, e.g., by writing its address to a descriptor.
+//! let device_owned = buffer.sync_for_device();
+//! let addr = device_owned.as_raw_ptr();
+//!
+//! // After the device signaled completion, take the buffer back.
+//! let mut buffer = device_owned.sync_for_cpu();
+//! assert_eq!(buffer.as_ptr().read(), [1, 2, 3, 4]);
+//! ```
+
+use core::{
+    fmt,
+    ptr::NonNull,
+    sync::atomic::{fence, Ordering},
+};
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// Cache maintenance for DMA on platforms without coherent DMA.
+///
+/// On such platforms, the CPU caches are not kept coherent with device accesses. Before a
+/// device reads a buffer, dirty cache lines need to be written back (cleaned). Before the
+/// CPU reads data written by a device, stale cache lines need to be discarded (invalidated).
+///
+/// ## Example
+///
+/// ```
+/// use core::ptr::NonNull;
+/// use volatile::{dma::{CacheOps, VolatileDmaBuffer}, VolatilePtr};
+///
+/// struct Aarch64CacheOps;
+///
+/// impl CacheOps for Aarch64CacheOps {
+///     unsafe fn clean(&self, ptr: NonNull<u8>, len: usize) {
+///         // `dc cvac` for each cache line in the range, followed by `dsb sy`
+///     }
+///
+///     unsafe fn invalidate(&self, ptr: NonNull<u8>, len: usize) {
+///         // `dc ivac` for each cache line in the range, followed by `dsb sy`
+///     }
+/// }
+///
+/// let mut data = [0_u8; 64];
+/// let volatile = unsafe { VolatilePtr::new((&mut data).into()) };
+/// let buffer = VolatileDmaBuffer::new(volatile).with_cache_ops(Aarch64CacheOps);
+/// ```
+pub trait CacheOps {
+    /// Writes back dirty cache lines of the memory range `ptr..ptr + len`.
+    ///
+    /// ## Safety
+    ///
+    /// The memory range must be valid.
+    unsafe fn clean(&self, ptr: NonNull<u8>, len: usize);
+
+    /// Discards cache lines of the memory range `ptr..ptr + len`.
+    ///
+    /// ## Safety
+    ///
+    /// The memory range must be valid and must not contain data that is not written back
+    /// yet and still needed, since this data is lost.
+    unsafe fn invalidate(&self, ptr: NonNull<u8>, len: usize);
+}
+
+/// [`CacheOps`] for platforms with coherent DMA, which do nothing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Coherent;
+
+impl CacheOps for Coherent {
+    unsafe fn clean(&self, _ptr: NonNull<u8>, _len: usize) {}
+
+    unsafe fn invalidate(&self, _ptr: NonNull<u8>, _len: usize) {}
+}
+
+/// A DMA buffer that is owned by the CPU.
+///
+/// See the [module-level documentation](self) for details.
+pub struct VolatileDmaBuffer<'a, T, C = Coherent>
+where
+    T: ?Sized,
+{
+    pointer: VolatilePtr<'a, T>,
+    len: usize,
+    cache_ops: C,
+}
+
+/// A DMA buffer that is owned by the device.
+///
+/// Created by [`VolatileDmaBuffer::sync_for_device`]. This state is advisory only, since
+/// copies of the original [`VolatilePtr`] may still be used to access the buffer.
+pub struct DeviceOwned<'a, T, C = Coherent>
+where
+    T: ?Sized,
+{
+    buffer: VolatileDmaBuffer<'a, T, C>,
+}
+
+impl<'a, T> VolatileDmaBuffer<'a, T> {
+    /// Creates a CPU-owned DMA buffer of the given memory.
+    pub fn new(pointer: VolatilePtr<'a, T>) -> Self {
+        Self {
+            pointer,
+            len: core::mem::size_of::<T>(),
+            cache_ops: Coherent,
+        }
+    }
+}
+
+impl<'a, T> VolatileDmaBuffer<'a, [T]> {
+    /// Creates a CPU-owned DMA buffer of the given slice.
+    pub fn from_slice(pointer: VolatilePtr<'a, [T]>) -> Self {
+        Self {
+            pointer,
+            len: core::mem::size_of::<T>() * pointer.as_raw_ptr().len(),
+            cache_ops: Coherent,
+        }
+    }
+}
+
+impl<'a, T, C> VolatileDmaBuffer<'a, T, C>
+where
+    T: ?Sized,
+    C: CacheOps,
+{
+    /// Uses `cache_ops` for cache maintenance at every ownership transfer.
+    pub fn with_cache_ops<D>(self, cache_ops: D) -> VolatileDmaBuffer<'a, T, D>
+    where
+        D: CacheOps,
+    {
+        VolatileDmaBuffer {
+            pointer: self.pointer,
+            len: self.len,
+            cache_ops,
+        }
+    }
+
+    /// Returns a volatile pointer to the buffer for CPU access.
+    pub fn as_ptr(&mut self) -> VolatilePtr<'_, T> {
+        self.pointer
+    }
+
+    /// Extracts the raw pointer to the buffer.
+    pub fn as_raw_ptr(&self) -> NonNull<T> {
+        self.pointer.as_raw_ptr()
+    }
+
+    /// Makes all previous CPU writes visible to the device and hands the buffer over.
+    pub fn sync_for_device(self) -> DeviceOwned<'a, T, C> {
+        fence(Ordering::SeqCst);
+        // SAFETY: The buffer is valid, and the CPU owns it.
+        unsafe {
+            self.cache_ops
+                .clean(self.pointer.as_raw_ptr().cast(), self.len)
+        };
+        fence(Ordering::SeqCst);
+        DeviceOwned { buffer: self }
+    }
+}
+
+impl<'a, T, C> DeviceOwned<'a, T, C>
+where
+    T: ?Sized,
+    C: CacheOps,
+{
+    /// Extracts the raw pointer to the buffer, e.g., for passing it to the device.
+    pub fn as_raw_ptr(&self) -> NonNull<T> {
+        self.buffer.as_raw_ptr()
+    }
+
+    /// Makes all device writes visible to the CPU and hands the buffer back.
+    ///
+    /// Call this only after the device has signaled that it is done with the buffer.
+    pub fn sync_for_cpu(self) -> VolatileDmaBuffer<'a, T, C> {
+        fence(Ordering::SeqCst);
+        // SAFETY: The buffer is valid, and the CPU did not write to it since it was cleaned.
+        unsafe {
+            self.buffer
+                .cache_ops
+                .invalidate(self.buffer.pointer.as_raw_ptr().cast(), self.buffer.len)
+        };
+        fence(Ordering::SeqCst);
+        self.buffer
+    }
+}
+
+/// Methods for copying volatile slices that are shared with a device through DMA.
+impl<T, A> VolatilePtr<'_, [T], A>
+where
+    T: Copy,
+{
+    /// Copies all elements from `src` into `self` and writes the copied range back from the
+    /// CPU caches using `cache_ops`.
+    ///
+    /// Afterwards, the device can read the data. This is a
+    /// [`copy_from_slice`](Self::copy_from_slice) followed by a fence and a
+    /// [`CacheOps::clean`] of the written range.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{dma::Coherent, VolatilePtr};
+    ///
+    /// let mut ring = [0_u32; 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut ring[..]).into()) };
+    /// volatile.copy_from_slice_with(&[1, 2, 3, 4], &Coherent);
+    /// assert_eq!(ring, [1, 2, 3, 4]);
+    /// ```
+    #[track_caller]
+    pub fn copy_from_slice_with<C>(self, src: &[T], cache_ops: &C)
+    where
+        A: Writable,
+        C: CacheOps,
+    {
+        self.copy_from_slice(src);
+        fence(Ordering::SeqCst);
+        let len = core::mem::size_of_val(src);
+        // SAFETY: The slice is valid for `len` bytes.
+        unsafe { cache_ops.clean(self.as_raw_ptr().cast(), len) };
+        fence(Ordering::SeqCst);
+    }
+
+    /// Discards the CPU cache lines of `self` using `cache_ops` and copies all elements from
+    /// `self` into `dst`.
+    ///
+    /// This reads the data that the device wrote instead of stale cached data. This is a
+    /// [`CacheOps::invalidate`] of the read range followed by a fence and a
+    /// [`copy_into_slice`](Self::copy_into_slice).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    ///
+    /// ## Safety
+    ///
+    /// The cache lines that overlap `self` must not contain data that is not written back yet
+    /// and still needed, since this data is lost. This includes data next to the slice if it
+    /// is not aligned to cache lines.
+    #[track_caller]
+    pub unsafe fn copy_into_slice_with<C>(self, dst: &mut [T], cache_ops: &C)
+    where
+        A: Readable,
+        C: CacheOps,
+    {
+        assert_eq!(
+            self.as_raw_ptr().len(),
+            dst.len(),
+            "destination and source slices have different lengths"
+        );
+        fence(Ordering::SeqCst);
+        // SAFETY: The slice is valid for the length of `dst`, and the caller guarantees that
+        // no needed data is lost.
+        unsafe { cache_ops.invalidate(self.as_raw_ptr().cast(), core::mem::size_of_val(dst)) };
+        fence(Ordering::SeqCst);
+        self.copy_into_slice(dst);
+    }
+}
+
+impl<T, C> fmt::Debug for VolatileDmaBuffer<'_, T, C>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VolatileDmaBuffer")
+            .field(&self.pointer)
+            .finish()
+    }
+}
+
+impl<T, C> fmt::Debug for DeviceOwned<'_, T, C>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DeviceOwned")
+            .field(&self.buffer.pointer)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dma::{CacheOps, VolatileDmaBuffer},
+        VolatilePtr,
+    };
+    use core::{cell::Cell, ptr::NonNull};
+
+    #[test]
+    fn test_dma_buffer_cache_ops() {
+        #[derive(Default)]
+        struct Counter {
+            cleaned: Cell<usize>,
+            invalidated: Cell<usize>,
+        }
+
+        impl CacheOps for &Counter {
+            unsafe fn clean(&self, _ptr: NonNull<u8>, len: usize) {
+                self.cleaned.set(self.cleaned.get() + len);
+            }
+
+            unsafe fn invalidate(&self, _ptr: NonNull<u8>, len: usize) {
+                self.invalidated.set(self.invalidated.get() + len);
+            }
+        }
+
+        let counter = Counter::default();
+        let mut data = [0_u16; 8];
+        let slice = unsafe { VolatilePtr::new(NonNull::from(&mut data[..])) };
+        let buffer = VolatileDmaBuffer::from_slice(slice).with_cache_ops(&counter);
+
+        let buffer = buffer.sync_for_device();
+        assert_eq!(counter.cleaned.get(), 16);
+        assert_eq!(counter.invalidated.get(), 0);
+
+        let _buffer = buffer.sync_for_cpu();
+        assert_eq!(counter.invalidated.get(), 16);
+    }
+
+    #[test]
+    fn test_copy_with_cache_ops() {
+        #[derive(Default)]
+        struct Log {
+            cleaned: Cell<Option<(usize, usize)>>,
+            invalidated: Cell<Option<(usize, usize)>>,
+        }
+
+        impl CacheOps for Log {
+            unsafe fn clean(&self, ptr: NonNull<u8>, len: usize) {
+                assert_eq!(self.invalidated.get(), None);
+                self.cleaned.set(Some((ptr.as_ptr().addr(), len)));
+            }
+
+            unsafe fn invalidate(&self, ptr: NonNull<u8>, len: usize) {
+                self.invalidated.set(Some((ptr.as_ptr().addr(), len)));
+            }
+        }
+
+        let log = Log::default();
+        let mut data = [0_u32; 4];
+        let slice = unsafe { VolatilePtr::new(NonNull::from(&mut data[..])) };
+        let addr = slice.as_raw_ptr().as_ptr().addr();
+
+        slice.copy_from_slice_with(&[1, 2, 3, 4], &log);
+        let mut dst = [0; 4];
+        unsafe { slice.copy_into_slice_with(&mut dst, &log) };
+        assert_eq!(dst, [1, 2, 3, 4]);
+        assert_eq!(log.cleaned.get(), Some((addr, 16)));
+        assert_eq!(log.invalidated.get(), Some((addr, 16)));
+    }
+}
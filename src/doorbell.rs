@@ -0,0 +1,89 @@
+//! Doorbell registers that notify a device of new work.
+//!
+//! Drivers for NVMe, virtio, or NICs write descriptors to normal memory and then notify the
+//! device by writing to a doorbell register. All descriptor writes must be visible to the
+//! device before the doorbell write. [`Doorbell::ring`] encapsulates this pattern by issuing
+//! the barriers of an [`OrderingPolicy`] around the write.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{doorbell::Doorbell, VolatilePtr};
+//!
+//! let mut tail = 0_u32;
+//! let doorbell = Doorbell::new(unsafe { VolatilePtr::new((&mut tail).into()) });
+//!
+//! // Write descriptors to the submission queue ...
+//! doorbell.ring(1);
+//! assert_eq!(tail, 1);
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{
+    access::{Writable, WriteOnly},
+    ordering::{MmioStrict, OrderingPolicy},
+    VolatilePtr,
+};
+
+/// A write-only register that notifies a device with the ordering policy `O`.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Doorbell<'a, T, O = MmioStrict> {
+    pointer: VolatilePtr<'a, T, WriteOnly>,
+    ordering: PhantomData<O>,
+}
+
+impl<T, O> Copy for Doorbell<'_, T, O> {}
+
+impl<T, O> Clone for Doorbell<'_, T, O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, O> fmt::Debug for Doorbell<'_, T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Doorbell").field(&self.pointer).finish()
+    }
+}
+
+impl<'a, T> Doorbell<'a, T> {
+    /// Creates a doorbell with [`MmioStrict`] ordering.
+    pub fn new<A>(pointer: VolatilePtr<'a, T, A>) -> Self
+    where
+        A: Writable,
+    {
+        Self::with_ordering(pointer)
+    }
+}
+
+impl<'a, T, O> Doorbell<'a, T, O>
+where
+    O: OrderingPolicy,
+{
+    /// Creates a doorbell with the ordering policy `O`.
+    pub fn with_ordering<A>(pointer: VolatilePtr<'a, T, A>) -> Self
+    where
+        A: Writable,
+    {
+        Self {
+            // SAFETY: The pointer was valid for writes before.
+            pointer: unsafe { VolatilePtr::new_restricted(WriteOnly, pointer.as_raw_ptr()) },
+            ordering: PhantomData,
+        }
+    }
+
+    /// Makes all previous memory writes visible to the device and writes `value`.
+    pub fn ring(&self, value: T)
+    where
+        T: Copy,
+    {
+        self.pointer.write_ordered::<O>(value);
+    }
+
+    /// Returns the underlying write-only pointer.
+    pub fn as_ptr(&self) -> VolatilePtr<'a, T, WriteOnly> {
+        self.pointer
+    }
+}
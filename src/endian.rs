@@ -0,0 +1,390 @@
+//! Support for fixed-endian values.
+//!
+//! Device registers and shared memory are often specified with a fixed byte order that differs
+//! from the native byte order of the CPU. Types implementing [`Endian`] wrap such values and
+//! convert them from and to the native byte order.
+//!
+//! [`VolatilePtr`]s to these types are accessed through
+//! [`read_native`](VolatilePtr::read_native), [`write_native`](VolatilePtr::write_native), and
+//! [`update_native`](VolatilePtr::update_native), which combine the volatile access with the
+//! conversion. These are the accessors to use for fixed-endian fields. The generic
+//! [`read`](VolatilePtr::read) and [`write`](VolatilePtr::write) are inherent methods for all
+//! types and cannot be replaced for the wrappers, so they still access the stored wrapper
+//! without converting it, e.g., to copy a value between two registers unchanged.
+//!
+//! This module provides the [`Le`] and [`Be`] wrappers for little-endian and big-endian integers.
+//! With the `zerocopy` feature, [`Endian`] is also implemented for the byte order aware integer
+//! types of [`zerocopy::byteorder`].
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::endian::{Be, Le};
+//! use volatile::{map_field, VolatilePtr};
+//!
+//! #[repr(C)]
+//! struct VirtioNetConfig { mac: [u8; 6], status: Le<u16>, }
+//! let mut config = VirtioNetConfig { mac: [0; 6], status: Le::new(0) };
+//! let volatile = unsafe { VolatilePtr::new((&mut config).into()) };
+//!
+//! map_field!(volatile.status).write_native(1);
+//! assert_eq!(map_field!(volatile.status).read_native(), 1);
+//! assert_eq!(config.status.get(), 1);
+//! assert_eq!(Be::new(0x1234_u16).to_raw(), 0x1234_u16.to_be());
+//! ```
+
+use core::fmt;
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// A value that is stored in a fixed byte order.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::endian::Endian;
+/// use volatile::VolatilePtr;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(transparent)]
+/// struct BigEndianU32(u32);
+///
+/// impl Endian for BigEndianU32 {
+///     type Native = u32;
+///
+///     fn to_native(self) -> u32 {
+///         u32::from_be(self.0)
+///     }
+///
+///     fn from_native(native: u32) -> Self {
+///         Self(native.to_be())
+///     }
+/// }
+///
+/// let mut value = BigEndianU32(0);
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+/// volatile.write_native(0x1234_5678);
+///
+/// assert_eq!(volatile.read_native(), 0x1234_5678);
+/// assert_eq!(volatile.read().0, 0x1234_5678_u32.to_be());
+/// ```
+pub trait Endian: Copy {
+    /// The corresponding type in native byte order.
+    type Native: Copy;
+
+    /// Converts the value to native byte order.
+    fn to_native(self) -> Self::Native;
+
+    /// Converts a value in native byte order to this type.
+    fn from_native(native: Self::Native) -> Self;
+}
+
+/// Methods for fixed-endian values.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: Endian,
+{
+    /// Performs a volatile read of the contained value and converts it to native byte order.
+    pub fn read_native(self) -> T::Native
+    where
+        A: Readable,
+    {
+        self.read().to_native()
+    }
+
+    /// Converts `value` from native byte order and performs a volatile write of the result.
+    pub fn write_native(self, value: T::Native)
+    where
+        A: Writable,
+    {
+        self.write(T::from_native(value));
+    }
+
+    /// Updates the contained value in native byte order using the given closure.
+    ///
+    /// Like [`update`](Self::update), this performs a volatile read followed by a volatile
+    /// write.
+    pub fn update_native<F>(self, f: F)
+    where
+        A: Readable + Writable,
+        F: FnOnce(T::Native) -> T::Native,
+    {
+        self.write_native(f(self.read_native()));
+    }
+}
+
+/// Integer types that can be wrapped in [`Le`] and [`Be`].
+///
+/// This trait is sealed and implemented for all primitive integer types.
+pub trait Integer: Copy + private::Sealed {}
+
+mod private {
+    pub trait Sealed {
+        fn to_le(self) -> Self;
+        fn from_le(value: Self) -> Self;
+        fn to_be(self) -> Self;
+        fn from_be(value: Self) -> Self;
+    }
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {
+                fn to_le(self) -> Self {
+                    <$ty>::to_le(self)
+                }
+
+                fn from_le(value: Self) -> Self {
+                    <$ty>::from_le(value)
+                }
+
+                fn to_be(self) -> Self {
+                    <$ty>::to_be(self)
+                }
+
+                fn from_be(value: Self) -> Self {
+                    <$ty>::from_be(value)
+                }
+            }
+
+            impl Integer for $ty {}
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! endian_wrapper {
+    ($(#[$attr:meta])* $name:ident, $to:ident, $from:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct $name<T>(T);
+
+        impl<T> $name<T> {
+            /// Creates a value from its raw representation, which is not converted.
+            pub const fn from_raw(raw: T) -> Self {
+                Self(raw)
+            }
+
+            /// Returns the raw representation of the value, which is not converted.
+            pub const fn to_raw(self) -> T
+            where
+                T: Copy,
+            {
+                self.0
+            }
+        }
+
+        impl<T> $name<T>
+        where
+            T: Integer,
+        {
+            /// Creates a value from a value in native byte order.
+            pub fn new(native: T) -> Self {
+                Self(native.$to())
+            }
+
+            /// Returns the value in native byte order.
+            pub fn get(self) -> T {
+                T::$from(self.0)
+            }
+        }
+
+        impl<T> Endian for $name<T>
+        where
+            T: Integer,
+        {
+            type Native = T;
+
+            fn to_native(self) -> T {
+                self.get()
+            }
+
+            fn from_native(native: T) -> Self {
+                Self::new(native)
+            }
+        }
+
+        impl<T> From<T> for $name<T>
+        where
+            T: Integer,
+        {
+            fn from(native: T) -> Self {
+                Self::new(native)
+            }
+        }
+
+        impl<T> fmt::Debug for $name<T>
+        where
+            T: Integer + fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.get()).finish()
+            }
+        }
+    };
+}
+
+endian_wrapper!(
+    /// A little-endian integer.
+    ///
+    /// The value is stored in little-endian byte order independent of the target. Use
+    /// [`VolatilePtr::read_native`] and [`VolatilePtr::write_native`] to access it in native
+    /// byte order.
+    Le,
+    to_le,
+    from_le
+);
+
+endian_wrapper!(
+    /// A big-endian integer.
+    ///
+    /// The value is stored in big-endian byte order independent of the target. Use
+    /// [`VolatilePtr::read_native`] and [`VolatilePtr::write_native`] to access it in native
+    /// byte order.
+    Be,
+    to_be,
+    from_be
+);
+
+/// Generates fixed-endian integer accessors that access the integer as a byte array.
+///
+/// The accessors take the receiver `$self` and the leading arguments `$arg` and delegate to the
+/// methods `$read` and `$write`, which read and write `[u8; N]` at the location given by the
+/// arguments. `$location` completes the doc comments, e.g., "at byte offset `offset`".
+macro_rules! int_accessors {
+    (
+        $signature:tt, $read:ident, $write:ident, $location:literal;
+        $($ty:ident: $read_le:ident, $read_be:ident, $write_le:ident, $write_be:ident;)*
+    ) => {
+        $(
+            $crate::endian::int_accessors!(
+                @int $signature, $read, $write, $location,
+                $ty: $read_le, $read_be, $write_le, $write_be
+            );
+        )*
+    };
+    (
+        @int ([$($receiver:tt)*] $self:ident $(, $arg:ident: $arg_ty:ty)*),
+        $read:ident, $write:ident, $location:literal,
+        $ty:ident: $read_le:ident, $read_be:ident, $write_le:ident, $write_be:ident
+    ) => {
+        #[doc = concat!("Reads a little-endian `", stringify!($ty), "` ", $location, ".")]
+        ///
+        /// The value does not need to be aligned. It is accessed as a `[u8; N]` with a volatile
+        /// access of unspecified width, which the compiler may split into narrower accesses.
+        pub fn $read_le($($receiver)* $self $(, $arg: $arg_ty)*) -> Result<$ty, VolatileError>
+        where
+            A: Readable,
+        {
+            $self.$read($($arg),*).map($ty::from_le_bytes)
+        }
+
+        #[doc = concat!("Reads a big-endian `", stringify!($ty), "` ", $location, ".")]
+        ///
+        /// The value does not need to be aligned. It is accessed as a `[u8; N]` with a volatile
+        /// access of unspecified width, which the compiler may split into narrower accesses.
+        pub fn $read_be($($receiver)* $self $(, $arg: $arg_ty)*) -> Result<$ty, VolatileError>
+        where
+            A: Readable,
+        {
+            $self.$read($($arg),*).map($ty::from_be_bytes)
+        }
+
+        #[doc = concat!("Writes `value` as a little-endian `", stringify!($ty), "` ", $location, ".")]
+        ///
+        /// The value does not need to be aligned. It is accessed as a `[u8; N]` with a volatile
+        /// access of unspecified width, which the compiler may split into narrower accesses.
+        pub fn $write_le(
+            $($receiver)* $self,
+            $($arg: $arg_ty,)*
+            value: $ty,
+        ) -> Result<(), VolatileError>
+        where
+            A: Writable,
+        {
+            $self.$write($($arg,)* value.to_le_bytes())
+        }
+
+        #[doc = concat!("Writes `value` as a big-endian `", stringify!($ty), "` ", $location, ".")]
+        ///
+        /// The value does not need to be aligned. It is accessed as a `[u8; N]` with a volatile
+        /// access of unspecified width, which the compiler may split into narrower accesses.
+        pub fn $write_be(
+            $($receiver)* $self,
+            $($arg: $arg_ty,)*
+            value: $ty,
+        ) -> Result<(), VolatileError>
+        where
+            A: Writable,
+        {
+            $self.$write($($arg,)* value.to_be_bytes())
+        }
+    };
+}
+
+pub(crate) use int_accessors;
+
+#[cfg(feature = "zerocopy")]
+mod zerocopy_impls {
+    use zerocopy::byteorder::{
+        ByteOrder, Isize, Usize, F32, F64, I128, I16, I32, I64, U128, U16, U32, U64,
+    };
+
+    use super::Endian;
+
+    macro_rules! impl_endian {
+        ($($ty:ident => $native:ty),*) => {
+            $(
+                impl<O: ByteOrder> Endian for $ty<O> {
+                    type Native = $native;
+
+                    fn to_native(self) -> $native {
+                        self.get()
+                    }
+
+                    fn from_native(native: $native) -> Self {
+                        Self::new(native)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_endian!(
+        U16 => u16, U32 => u32, U64 => u64, U128 => u128, Usize => usize,
+        I16 => i16, I32 => i32, I64 => i64, I128 => i128, Isize => isize,
+        F32 => f32, F64 => f64
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_endian_byte_accessors() {
+        let mut descriptor = [0_u8; 16];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut descriptor[..])) };
+        volatile.write_u16_be(1, 0x0102).unwrap();
+        volatile.write_u32_le(3, 0x0605_0403).unwrap();
+        volatile.write_u64_be(8, 0x0708_090a_0b0c_0d0e).unwrap();
+        assert_eq!(volatile.read_u16_le(1), Ok(0x0201));
+        assert_eq!(volatile.read_u32_be(3), Ok(0x0304_0506));
+        assert_eq!(volatile.read_u64_le(8), Ok(0x0e0d_0c0b_0a09_0807));
+        assert_eq!(
+            volatile.read_u64_le(9),
+            Err(VolatileError::OutOfBounds { index: 17, len: 16 })
+        );
+        assert_eq!(
+            descriptor,
+            [0, 1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12, 13, 14]
+        );
+    }
+}
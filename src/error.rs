@@ -0,0 +1,95 @@
+use core::fmt;
+
+/// An error returned by the fallible APIs of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum VolatileError {
+    /// The pointer is not properly aligned for the accessed type.
+    Misaligned {
+        /// The alignment of the accessed type.
+        required: usize,
+        /// The alignment of the pointer.
+        found: usize,
+    },
+    /// The access is not within the bounds of the accessed memory.
+    OutOfBounds {
+        /// The end of the attempted access.
+        index: usize,
+        /// The length of the accessed memory.
+        len: usize,
+    },
+    /// The length of the accessed memory does not match the size of the accessed type.
+    SizeMismatch {
+        /// The size of the accessed type.
+        size: usize,
+        /// The length of the accessed memory.
+        len: usize,
+    },
+    /// The pointer is null.
+    Null,
+    /// The value read is not a valid value of the accessed type.
+    InvalidValue,
+    /// The operation did not complete in time.
+    Timeout,
+    /// The address is not mapped, e.g., by a guest memory translation.
+    Unmapped {
+        /// The address that is not mapped.
+        addr: u64,
+    },
+}
+
+impl VolatileError {
+    /// Returns a [`VolatileError::Misaligned`] error for an access of type `T` at `pointer`.
+    pub(crate) fn misaligned<T>(pointer: *const T) -> Self {
+        Self::Misaligned {
+            required: core::mem::align_of::<T>(),
+            found: 1 << pointer.addr().trailing_zeros(),
+        }
+    }
+}
+
+impl fmt::Display for VolatileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Misaligned { required, found } => write!(
+                f,
+                "access requires an alignment of {required} but the pointer is aligned to {found}"
+            ),
+            Self::OutOfBounds { index, len } => {
+                write!(f, "access up to {index} is out of bounds for length {len}")
+            }
+            Self::SizeMismatch { size, len } => {
+                write!(f, "length {len} does not match the type size {size}")
+            }
+            Self::Null => f.write_str("pointer is null"),
+            Self::InvalidValue => f.write_str("invalid value"),
+            Self::Timeout => f.write_str("operation timed out"),
+            Self::Unmapped { addr } => write!(f, "address {addr:#x} is not mapped"),
+        }
+    }
+}
+
+impl core::error::Error for VolatileError {}
+
+/// The error returned by [`VolatilePtr::write_verified`](crate::VolatilePtr::write_verified) if
+/// the value that was read back differs from the written value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mismatch<T> {
+    /// The value that was written.
+    pub wrote: T,
+    /// The value that was read back.
+    pub read: T,
+}
+
+impl<T> fmt::Display for Mismatch<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wrote {:?} but read back {:?}", self.wrote, self.read)
+    }
+}
+
+impl<T> core::error::Error for Mismatch<T> where T: fmt::Debug {}
@@ -0,0 +1,130 @@
+//! Volatile operations on registers modeled as [`bitflags`] types.
+//!
+//! Status and control registers are often modeled as flags types generated by the
+//! [`bitflags::bitflags!`] macro. With the `bitflags` feature, [`VolatilePtr`]s to such types
+//! provide methods that insert, remove, and test flags with volatile accesses. Methods that
+//! modify flags perform a volatile read-modify-write.
+//!
+//! ## Example
+//!
+//! ```
+//! use bitflags::bitflags;
+//! use volatile::VolatilePtr;
+//!
+//! bitflags! {
+//!     #[derive(Clone, Copy)]
+//!     struct Control: u32 {
+//!         const ENABLE = 1 << 0;
+//!         const INTERRUPT = 1 << 1;
+//!     }
+//! }
+//!
+//! let mut control = Control::empty();
+//! let volatile = unsafe { VolatilePtr::new((&mut control).into()) };
+//!
+//! volatile.insert_flags(Control::ENABLE | Control::INTERRUPT);
+//! volatile.remove_flags(Control::INTERRUPT);
+//! assert!(volatile.contains_flags(Control::ENABLE));
+//! assert!(!volatile.intersects_flags(Control::INTERRUPT));
+//! ```
+
+use bitflags::Flags;
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// Methods for volatile flag registers.
+impl<F, A> VolatilePtr<'_, F, A>
+where
+    F: Flags + Copy,
+{
+    /// Sets the given flags through a volatile read-modify-write.
+    pub fn insert_flags(self, flags: F)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|mut value| {
+            value.insert(flags);
+            value
+        });
+    }
+
+    /// Clears the given flags through a volatile read-modify-write.
+    pub fn remove_flags(self, flags: F)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|mut value| {
+            value.remove(flags);
+            value
+        });
+    }
+
+    /// Inverts the given flags through a volatile read-modify-write.
+    pub fn toggle_flags(self, flags: F)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|mut value| {
+            value.toggle(flags);
+            value
+        });
+    }
+
+    /// Sets or clears the given flags through a volatile read-modify-write.
+    pub fn set_flags(self, flags: F, set: bool)
+    where
+        A: Readable + Writable,
+    {
+        self.update(|mut value| {
+            value.set(flags, set);
+            value
+        });
+    }
+
+    /// Returns whether all of the given flags are set.
+    pub fn contains_flags(self, flags: F) -> bool
+    where
+        A: Readable,
+    {
+        self.read().contains(flags)
+    }
+
+    /// Returns whether any of the given flags is set.
+    pub fn intersects_flags(self, flags: F) -> bool
+    where
+        A: Readable,
+    {
+        self.read().intersects(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_flags() {
+        bitflags::bitflags! {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            struct Status: u8 {
+                const READY = 1 << 0;
+                const ERROR = 1 << 1;
+                const BUSY = 1 << 7;
+            }
+        }
+
+        let mut status = Status::BUSY;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut status)) };
+        volatile.insert_flags(Status::READY);
+        volatile.toggle_flags(Status::BUSY | Status::ERROR);
+        volatile.set_flags(Status::READY, false);
+        assert!(volatile.contains_flags(Status::ERROR));
+        assert!(!volatile.intersects_flags(Status::READY | Status::BUSY));
+        volatile.remove_flags(Status::ERROR);
+        assert_eq!(status, Status::empty());
+    }
+}
@@ -0,0 +1,286 @@
+//! Two-dimensional views of volatile slices.
+//!
+//! A [`VolatileGrid`] interprets a volatile slice as rows of `width` elements that start
+//! `stride` elements apart, e.g., a linear framebuffer whose pitch is larger than its visible
+//! width. It provides access to single rows and rectangle operations that use the bulk copies
+//! of [`VolatilePtr`] for each row.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{grid::VolatileGrid, VolatileError, VolatilePtr};
+//!
+//! // A 4x3 framebuffer with a pitch of 6 pixels.
+//! let mut framebuffer = [0_u32; 18];
+//! let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut framebuffer[..])) };
+//! let grid = VolatileGrid::new(volatile, 4, 3, 6)?;
+//!
+//! grid.fill_rect(1, 1, 2, 2, 0xffff_ffff);
+//! grid.copy_rect_from_slice(0, 0, 2, 1, &[1, 2]);
+//! grid.row(2).copy_from_slice(&[3, 3, 3, 3]);
+//!
+//! assert_eq!(framebuffer[..6], [1, 2, 0, 0, 0, 0]);
+//! assert_eq!(framebuffer[6..12], [0, 0xffff_ffff, 0xffff_ffff, 0, 0, 0]);
+//! assert_eq!(framebuffer[12..], [3, 3, 3, 3, 0, 0]);
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::{fmt, ptr::NonNull};
+
+use crate::{
+    access::{Access, ReadWrite, Readable, Writable},
+    VolatileError, VolatilePtr,
+};
+
+/// The number of elements of the buffer that [`VolatileGrid::fill_rect`] copies from.
+const FILL_CHUNK: usize = 32;
+
+/// A rectangular view of a volatile slice with a row stride.
+///
+/// See the [module-level documentation](self) for details.
+pub struct VolatileGrid<'a, T, A = ReadWrite> {
+    elements: VolatilePtr<'a, [T], A>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<T, A> Copy for VolatileGrid<'_, T, A> {}
+
+impl<T, A> Clone for VolatileGrid<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> fmt::Debug for VolatileGrid<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileGrid")
+            .field("elements", &self.elements)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("stride", &self.stride)
+            .finish()
+    }
+}
+
+impl<'a, T, A> VolatileGrid<'a, T, A>
+where
+    A: Access,
+{
+    /// Creates a grid of `height` rows of `width` elements that start `stride` elements apart.
+    ///
+    /// Returns [`VolatileError::OutOfBounds`] if the last row does not fit into `elements`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `stride` is smaller than `width`.
+    pub fn new(
+        elements: VolatilePtr<'a, [T], A>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, VolatileError> {
+        assert!(stride >= width, "stride must not be smaller than the width");
+        let len = elements.as_raw_ptr().len();
+        let required = match height {
+            0 => 0,
+            height => (height - 1)
+                .checked_mul(stride)
+                .and_then(|start| start.checked_add(width))
+                .unwrap_or(usize::MAX),
+        };
+        if required > len {
+            return Err(VolatileError::OutOfBounds {
+                index: required,
+                len,
+            });
+        }
+        Ok(Self {
+            elements,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    /// Returns the number of elements per row.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the distance between the starts of two consecutive rows in elements.
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a volatile pointer to row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `y` is not smaller than the height.
+    pub fn row(&self, y: usize) -> VolatilePtr<'a, [T], A> {
+        self.span(0, y, self.width)
+    }
+
+    /// Returns a volatile pointer to the element in column `x` of row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the position is outside of the grid.
+    pub fn at(&self, x: usize, y: usize) -> VolatilePtr<'a, T, A> {
+        let span = self.span(x, y, 1);
+        // SAFETY: The span contains exactly this element.
+        unsafe { span.map(|ptr| ptr.cast()) }
+    }
+
+    /// Sets all elements of the given rectangle to `value`.
+    ///
+    /// Each row is written with [`VolatilePtr::copy_from_slice`] from a buffer of copies of
+    /// `value`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the rectangle is not contained in the grid.
+    pub fn fill_rect(&self, x: usize, y: usize, width: usize, height: usize, value: T)
+    where
+        T: Copy,
+        A: Writable,
+    {
+        self.check_rect(x, y, width, height);
+        let buf = [value; FILL_CHUNK];
+        for y in y..y + height {
+            let mut x = x;
+            let end = x + width;
+            while x < end {
+                let len = usize::min(FILL_CHUNK, end - x);
+                self.span(x, y, len).copy_from_slice(&buf[..len]);
+                x += len;
+            }
+        }
+    }
+
+    /// Copies the rectangle from `src`, which contains its rows without gaps.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the rectangle is not contained in the grid or if the length of `src` is not
+    /// `width * height`.
+    pub fn copy_rect_from_slice(&self, x: usize, y: usize, width: usize, height: usize, src: &[T])
+    where
+        T: Copy,
+        A: Writable,
+    {
+        self.check_rect(x, y, width, height);
+        assert_eq!(
+            src.len(),
+            width * height,
+            "source length does not match the rectangle"
+        );
+        if width == 0 {
+            return;
+        }
+        for (row, src) in (y..y + height).zip(src.chunks_exact(width)) {
+            self.span(x, row, width).copy_from_slice(src);
+        }
+    }
+
+    /// Copies the rectangle into `dst`, which receives its rows without gaps.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the rectangle is not contained in the grid or if the length of `dst` is not
+    /// `width * height`.
+    pub fn copy_rect_into_slice(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        dst: &mut [T],
+    ) where
+        T: Copy,
+        A: Readable,
+    {
+        self.check_rect(x, y, width, height);
+        assert_eq!(
+            dst.len(),
+            width * height,
+            "destination length does not match the rectangle"
+        );
+        if width == 0 {
+            return;
+        }
+        for (row, dst) in (y..y + height).zip(dst.chunks_exact_mut(width)) {
+            self.span(x, row, width).copy_into_slice(dst);
+        }
+    }
+
+    /// Returns a volatile pointer to `len` elements of row `y`, starting at column `x`.
+    fn span(&self, x: usize, y: usize, len: usize) -> VolatilePtr<'a, [T], A> {
+        self.check_rect(x, y, len, 1);
+        let start = y * self.stride + x;
+        // SAFETY: The span is within the grid, which is within `elements`.
+        unsafe {
+            self.elements
+                .map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<T>().add(start), len))
+        }
+    }
+
+    #[track_caller]
+    fn check_rect(&self, x: usize, y: usize, width: usize, height: usize) {
+        let fits = |start: usize, len: usize, max: usize| {
+            start.checked_add(len).is_some_and(|end| end <= max)
+        };
+        assert!(
+            fits(x, width, self.width) && fits(y, height, self.height),
+            "rectangle is outside of the grid"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::VolatileGrid, VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_grid() {
+        let mut pixels = [0_u16; 100 * 3];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut pixels[..])) };
+        assert_eq!(
+            VolatileGrid::new(volatile, 80, 4, 100).unwrap_err(),
+            VolatileError::OutOfBounds {
+                index: 380,
+                len: 300
+            }
+        );
+
+        let grid = VolatileGrid::new(volatile, 80, 3, 100).unwrap();
+        grid.fill_rect(10, 1, 50, 2, 7);
+        grid.at(79, 0).write(1);
+        let mut rect = [0; 4 * 2];
+        grid.copy_rect_into_slice(58, 1, 4, 2, &mut rect);
+        assert_eq!(rect, [7, 7, 0, 0, 7, 7, 0, 0]);
+        assert_eq!(grid.row(0).as_raw_ptr().len(), 80);
+        assert_eq!(pixels[79], 1);
+        assert!(pixels[..110].iter().all(|&p| p <= 1));
+        assert!(pixels[110..160].iter().all(|&p| p == 7));
+        assert!(pixels[160..210].iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    #[should_panic = "rectangle is outside of the grid"]
+    fn test_grid_out_of_bounds() {
+        let mut pixels = [0_u8; 16];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut pixels[..])) };
+        let grid = crate::grid::VolatileGrid::new(volatile, 4, 4, 4).unwrap();
+        grid.fill_rect(2, 2, 3, 1, 1);
+    }
+}
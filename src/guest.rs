@@ -0,0 +1,237 @@
+//! Checked access to guest memory for hypervisors.
+//!
+//! Device backends of virtual machine monitors, e.g., for virtio devices, access buffers at
+//! guest-physical addresses that the guest provides. A [`GuestRegion`] translates these
+//! addresses through a user-supplied [`GuestMemory`] implementation and hands out
+//! bounds-checked [`VolatileRegion`]s and [`VolatilePtr`]s into the translated memory. Since
+//! the guest may modify its memory concurrently, all accesses are volatile. The guest controls
+//! the contents of its memory, so typed accesses require [`NoPadding`] types, which are valid
+//! for any bytes.
+//!
+//! [`GuestMemory`] is implemented for slices of [`GuestMapping`]s, which map contiguous ranges
+//! of guest-physical memory into the address space of the VMM.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{
+//!     guest::{GuestMapping, GuestRegion},
+//!     region::VolatileRegion,
+//!     VolatileError,
+//! };
+//!
+//! let mut ram = [0_u64; 512];
+//! let host = unsafe { VolatileRegion::new(NonNull::from(&mut ram).cast(), 4096) };
+//! let mappings = [GuestMapping::new(0x8000_0000, host)];
+//! let guest = GuestRegion::new(&mappings[..]);
+//!
+//! guest.write_obj::<u32>(0x8000_0010, 0xcafe)?;
+//! assert_eq!(guest.read_obj::<u32>(0x8000_0010)?, 0xcafe);
+//! assert_eq!(
+//!     guest.read_obj::<u32>(0x8000_1000),
+//!     Err(VolatileError::Unmapped { addr: 0x8000_1000 })
+//! );
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::fmt;
+
+use crate::{padding::NoPadding, region::VolatileRegion, VolatileError, VolatilePtr};
+
+/// Translates guest-physical addresses into host memory.
+///
+/// ## Safety
+///
+/// The regions returned by [`translate`](Self::translate) must be valid for the lifetime `'a`
+/// as required by [`VolatileRegion::new`].
+pub unsafe trait GuestMemory<'a> {
+    /// Returns the host memory for the `len` bytes of guest memory at `addr`.
+    ///
+    /// Returns `None` if the range is not backed by contiguous host memory.
+    fn translate(&self, addr: u64, len: usize) -> Option<VolatileRegion<'a>>;
+}
+
+/// A contiguous range of guest-physical memory that is mapped into the host.
+#[derive(Debug, Clone, Copy)]
+pub struct GuestMapping<'a> {
+    guest_base: u64,
+    host: VolatileRegion<'a>,
+}
+
+impl<'a> GuestMapping<'a> {
+    /// Creates a mapping of the guest memory starting at `guest_base` to `host`.
+    pub const fn new(guest_base: u64, host: VolatileRegion<'a>) -> Self {
+        Self { guest_base, host }
+    }
+
+    /// Returns the guest-physical address of the start of the mapping.
+    pub const fn guest_base(&self) -> u64 {
+        self.guest_base
+    }
+
+    /// Returns the host memory of the mapping.
+    pub const fn host(&self) -> VolatileRegion<'a> {
+        self.host
+    }
+}
+
+unsafe impl<'a> GuestMemory<'a> for [GuestMapping<'a>] {
+    fn translate(&self, addr: u64, len: usize) -> Option<VolatileRegion<'a>> {
+        self.iter().find_map(|mapping| {
+            let offset = usize::try_from(addr.checked_sub(mapping.guest_base)?).ok()?;
+            mapping
+                .host
+                .subregion(offset..offset.checked_add(len)?)
+                .ok()
+        })
+    }
+}
+
+/// Bounds-checked volatile access to guest memory.
+///
+/// See the [module-level documentation](self) for details.
+pub struct GuestRegion<'m, M>
+where
+    M: ?Sized,
+{
+    memory: &'m M,
+}
+
+impl<M> Clone for GuestRegion<'_, M>
+where
+    M: ?Sized,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for GuestRegion<'_, M> where M: ?Sized {}
+
+impl<M> fmt::Debug for GuestRegion<'_, M>
+where
+    M: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuestRegion").finish_non_exhaustive()
+    }
+}
+
+impl<'m, M> GuestRegion<'m, M>
+where
+    M: ?Sized,
+{
+    /// Creates a region that translates guest addresses through `memory`.
+    pub const fn new(memory: &'m M) -> Self {
+        Self { memory }
+    }
+
+    /// Returns the host memory for the `len` bytes of guest memory at `addr`.
+    ///
+    /// Returns [`VolatileError::Unmapped`] if the range is not backed by contiguous host
+    /// memory.
+    pub fn region<'a>(&self, addr: u64, len: usize) -> Result<VolatileRegion<'a>, VolatileError>
+    where
+        M: GuestMemory<'a>,
+    {
+        self.memory
+            .translate(addr, len)
+            .ok_or(VolatileError::Unmapped { addr })
+    }
+
+    /// Returns a volatile pointer to the value of type `T` at guest address `addr`.
+    pub fn at<'a, T>(&self, addr: u64) -> Result<VolatilePtr<'a, T>, VolatileError>
+    where
+        M: GuestMemory<'a>,
+        T: NoPadding,
+    {
+        self.region(addr, core::mem::size_of::<T>())?.at(0)
+    }
+
+    /// Reads the value of type `T` at guest address `addr`.
+    pub fn read_obj<'a, T>(&self, addr: u64) -> Result<T, VolatileError>
+    where
+        M: GuestMemory<'a>,
+        T: NoPadding + 'a,
+    {
+        self.at::<T>(addr).map(VolatilePtr::read)
+    }
+
+    /// Writes `value` to guest address `addr`.
+    pub fn write_obj<'a, T>(&self, addr: u64, value: T) -> Result<(), VolatileError>
+    where
+        M: GuestMemory<'a>,
+        T: NoPadding + 'a,
+    {
+        self.at::<T>(addr).map(|pointer| pointer.write(value))
+    }
+
+    /// Copies the guest memory at `addr` into `buf`.
+    pub fn read_slice<'a>(&self, addr: u64, buf: &mut [u8]) -> Result<(), VolatileError>
+    where
+        M: GuestMemory<'a>,
+    {
+        let region = self.region(addr, buf.len())?;
+        region.slice::<u8>(0..buf.len())?.copy_into_slice(buf);
+        Ok(())
+    }
+
+    /// Copies `buf` into the guest memory at `addr`.
+    pub fn write_slice<'a>(&self, addr: u64, buf: &[u8]) -> Result<(), VolatileError>
+    where
+        M: GuestMemory<'a>,
+    {
+        let region = self.region(addr, buf.len())?;
+        region.slice::<u8>(0..buf.len())?.copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        guest::{GuestMapping, GuestRegion},
+        region::VolatileRegion,
+        VolatileError,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_guest_region() {
+        let mut low = [0_u32; 16];
+        let mut high = [0_u32; 16];
+        let mappings = unsafe {
+            [
+                GuestMapping::new(0, VolatileRegion::new(NonNull::from(&mut low).cast(), 64)),
+                GuestMapping::new(
+                    0x1000,
+                    VolatileRegion::new(NonNull::from(&mut high).cast(), 64),
+                ),
+            ]
+        };
+        let guest = GuestRegion::new(&mappings[..]);
+
+        guest.write_slice(0x1004, &[1, 2, 3, 4]).unwrap();
+        assert_eq!(
+            guest.read_obj::<u32>(0x1004),
+            Ok(u32::from_ne_bytes([1, 2, 3, 4]))
+        );
+        guest.write_obj::<u16>(0x3e, 7).unwrap();
+        let mut buf = [0; 2];
+        guest.read_slice(0x3e, &mut buf).unwrap();
+        assert_eq!(u16::from_ne_bytes(buf), 7);
+
+        assert_eq!(
+            guest.read_obj::<u32>(0x3e),
+            Err(VolatileError::Unmapped { addr: 0x3e })
+        );
+        assert_eq!(
+            guest.read_obj::<u32>(0x1002),
+            Err(VolatileError::Misaligned {
+                required: 4,
+                found: 2
+            })
+        );
+    }
+}
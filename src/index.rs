@@ -0,0 +1,192 @@
+//! Bounds-checked indexing of volatile slices with custom index types.
+//!
+//! [`VolatilePtr::get`] projects a volatile slice to an element or a subslice. It accepts any
+//! type that implements [`VolatileSliceIndex`], which includes `usize` and the range types of
+//! the standard library. Drivers can implement the trait for their own index newtypes, e.g., a
+//! descriptor ID, by delegating to these implementations.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{index::VolatileSliceIndex, VolatilePtr};
+//!
+//! #[derive(Clone, Copy)]
+//! struct DescriptorId(u16);
+//!
+//! // SAFETY: The implementation for `usize` only returns pointers within the slice.
+//! unsafe impl<T> VolatileSliceIndex<T> for DescriptorId {
+//!     type Output = T;
+//!
+//!     fn get(self, slice: NonNull<[T]>) -> Option<NonNull<T>> {
+//!         usize::from(self.0).get(slice)
+//!     }
+//! }
+//!
+//! let mut descriptors = [0_u64; 4];
+//! let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut descriptors[..])) };
+//! volatile.get(DescriptorId(2)).unwrap().write(0x1000);
+//! assert!(volatile.get(DescriptorId(4)).is_none());
+//! assert_eq!(descriptors, [0, 0, 0x1000, 0]);
+//! ```
+
+use core::{
+    ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
+    ptr::NonNull,
+};
+
+use crate::{access::Access, VolatilePtr};
+
+/// Index types for volatile slices of `T`.
+///
+/// ## Safety
+///
+/// [`get`](Self::get) must only return pointers to an `Output` that lies within the given slice.
+pub unsafe trait VolatileSliceIndex<T> {
+    /// The type of the projection, e.g., an element or a subslice.
+    type Output: ?Sized;
+
+    /// Returns a pointer to the indexed part of `slice` or `None` if it is out of bounds.
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<Self::Output>>;
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for usize {
+    type Output = T;
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<T>> {
+        if self < slice.len() {
+            // SAFETY: The index is within the slice.
+            Some(unsafe { slice.cast::<T>().add(self) })
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for Range<usize> {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        if self.start <= self.end && self.end <= slice.len() {
+            // SAFETY: The range is within the slice.
+            let data = unsafe { slice.cast::<T>().add(self.start) };
+            Some(NonNull::slice_from_raw_parts(data, self.end - self.start))
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for RangeInclusive<usize> {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        let end = self.end().checked_add(1)?;
+        (*self.start()..end).get(slice)
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for RangeFrom<usize> {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        (self.start..slice.len()).get(slice)
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for RangeTo<usize> {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        (0..self.end).get(slice)
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for RangeToInclusive<usize> {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        (0..=self.end).get(slice)
+    }
+}
+
+unsafe impl<T> VolatileSliceIndex<T> for RangeFull {
+    type Output = [T];
+
+    fn get(self, slice: NonNull<[T]>) -> Option<NonNull<[T]>> {
+        Some(slice)
+    }
+}
+
+/// Methods for indexing volatile slices.
+impl<'a, T, A> VolatilePtr<'a, [T], A> {
+    /// Returns a pointer to the element or subslice at `index`, or `None` if it is out of
+    /// bounds.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::ptr::NonNull;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut array = [1, 2, 3];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    /// assert_eq!(volatile.get(1).unwrap().read(), 2);
+    /// assert_eq!(volatile.get(1..).unwrap().as_raw_ptr().len(), 2);
+    /// assert!(volatile.get(3).is_none());
+    /// ```
+    pub fn get<I>(self, index: I) -> Option<VolatilePtr<'a, I::Output, A>>
+    where
+        I: VolatileSliceIndex<T>,
+        A: Access,
+    {
+        let pointer = index.get(self.as_raw_ptr())?;
+        // SAFETY: The index implementation guarantees that the pointer is within the slice.
+        Some(unsafe { self.map(|_| pointer) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{index::VolatileSliceIndex, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_slice_index() {
+        struct QueueIndex(u8);
+
+        unsafe impl<T> VolatileSliceIndex<T> for QueueIndex {
+            type Output = T;
+
+            fn get(self, slice: NonNull<[T]>) -> Option<NonNull<T>> {
+                usize::from(self.0).get(slice)
+            }
+        }
+
+        let mut val = [10_u32, 11, 12, 13, 14];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val[..])) };
+        let len = |ptr: Option<VolatilePtr<'_, [u32]>>| ptr.map(|ptr| ptr.as_raw_ptr().len());
+
+        volatile.get(QueueIndex(4)).unwrap().write(40);
+        assert!(volatile.get(QueueIndex(5)).is_none());
+        assert_eq!(volatile.get(0).unwrap().read(), 10);
+        assert!(volatile.get(5).is_none());
+
+        assert_eq!(len(volatile.get(1..3)), Some(2));
+        assert_eq!(len(volatile.get(5..5)), Some(0));
+        let (start, end) = (3, 2);
+        assert_eq!(len(volatile.get(start..end)), None);
+        assert_eq!(len(volatile.get(4..6)), None);
+        assert_eq!(len(volatile.get(1..=4)), Some(4));
+        assert_eq!(len(volatile.get(1..=usize::MAX)), None);
+        assert_eq!(len(volatile.get(2..)), Some(3));
+        assert_eq!(len(volatile.get(6..)), None);
+        assert_eq!(len(volatile.get(..5)), Some(5));
+        assert_eq!(len(volatile.get(..=5)), None);
+        assert_eq!(len(volatile.get(..)), Some(5));
+
+        let tail = volatile.get(3..).unwrap();
+        assert_eq!(tail.get(1).unwrap().read(), 40);
+        assert_eq!(val, [10, 11, 12, 13, 40]);
+    }
+}
@@ -0,0 +1,120 @@
+//! Global instrumentation hooks for volatile accesses.
+//!
+//! With the `instrument` feature, [`VolatilePtr::read`](crate::VolatilePtr::read),
+//! [`VolatilePtr::write`](crate::VolatilePtr::write), and all other volatile accesses of this
+//! crate call the registered [`Hooks`] before and after every access. This allows system-wide
+//! tracing of MMIO accesses, e.g., in hypervisors or for emulator-assisted debugging. Without
+//! the feature, this module does not exist and accesses are not instrumented at all.
+//!
+//! Bulk operations on slices, such as
+//! [`copy_from_slice`](crate::VolatilePtr::copy_from_slice) and
+//! [`fill_bytes`](crate::VolatilePtr::fill_bytes), call the hooks once for the whole volatile
+//! range with its address and length, since the width of their individual accesses depends on
+//! the alignment and the enabled features. Copies between two volatile slices report a read of
+//! the source and a write of the destination.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::sync::atomic::{AtomicUsize, Ordering};
+//! use volatile::instrument::{self, Event, Hooks};
+//! use volatile::VolatilePtr;
+//!
+//! static ACCESSES: AtomicUsize = AtomicUsize::new(0);
+//!
+//! fn count(_event: &Event) {
+//!     ACCESSES.fetch_add(1, Ordering::Relaxed);
+//! }
+//!
+//! static HOOKS: Hooks = Hooks {
+//!     before: count,
+//!     after: |_| {},
+//! };
+//!
+//! instrument::set_hooks(&HOOKS);
+//!
+//! let mut value = 0_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+//! volatile.update(|v| v + 1);
+//! assert_eq!(ACCESSES.load(Ordering::Relaxed), 2);
+//!
+//! instrument::clear_hooks();
+//! ```
+
+use core::{
+    panic::Location,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::backend::Direction;
+
+/// A volatile access or bulk operation that is about to happen or has happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event {
+    /// The address of the access.
+    pub addr: usize,
+    /// The size of the access in bytes, or the length of the range for bulk operations.
+    pub size: usize,
+    /// Whether the access is a read or a write.
+    pub direction: Direction,
+    /// The source location of the access.
+    pub location: &'static Location<'static>,
+}
+
+/// Instrumentation hooks that are called for every volatile access.
+#[derive(Debug, Clone, Copy)]
+pub struct Hooks {
+    /// Called before the access.
+    pub before: fn(&Event),
+    /// Called after the access.
+    pub after: fn(&Event),
+}
+
+static HOOKS: AtomicPtr<Hooks> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `hooks` for all subsequent volatile accesses, replacing any previous hooks.
+pub fn set_hooks(hooks: &'static Hooks) {
+    HOOKS.store(ptr::from_ref(hooks).cast_mut(), Ordering::Release);
+}
+
+/// Removes the registered hooks.
+pub fn clear_hooks() {
+    HOOKS.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Calls the `before` hook on creation and the `after` hook on drop.
+pub(crate) struct Guard {
+    hooks: Option<&'static Hooks>,
+    event: Event,
+}
+
+impl Guard {
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn new(ptr: *const u8, size: usize, direction: Direction) -> Self {
+        // SAFETY: `HOOKS` only ever contains null or a `&'static Hooks`.
+        let hooks = unsafe { HOOKS.load(Ordering::Acquire).as_ref() };
+        let event = Event {
+            addr: ptr.addr(),
+            size,
+            direction,
+            location: Location::caller(),
+        };
+        #[cfg(feature = "watch")]
+        crate::watch::check(&event);
+        if let Some(hooks) = hooks {
+            (hooks.before)(&event);
+        }
+        Self { hooks, event }
+    }
+}
+
+impl Drop for Guard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if let Some(hooks) = self.hooks {
+            (hooks.after)(&self.event);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Shared memory regions between VMs, or between a host and an enclave.
+//!
+//! Inter-VM shared memory devices, e.g., QEMU's `ivshmem`, expose a shared memory BAR and a
+//! small register BAR whose doorbell interrupts other peers. This module provides a
+//! [`SharedMemory`] layout with a [`Header`] that identifies the protocol and its version,
+//! and [`Ivshmem`] for the registers of the device.
+//!
+//! The creator of the region calls [`SharedMemory::init`], which writes the magic value last,
+//! so that peers that check it with [`SharedMemory::open`] observe a complete header. The
+//! payload after the header is accessed through a bounds-checked [`VolatileRegion`].
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{ivshmem::SharedMemory, region::VolatileRegion, VolatileError};
+//!
+//! const MAGIC: u32 = 0x4d45_4853;
+//!
+//! let mut bar = [0_u64; 64];
+//! let region = unsafe { VolatileRegion::new(NonNull::from(&mut bar).cast(), 512) };
+//!
+//! let host = SharedMemory::init(region, MAGIC, 1)?;
+//! host.payload().write_at::<u32>(0, 42)?;
+//!
+//! let guest = SharedMemory::open(region, MAGIC, 1)?;
+//! assert_eq!(guest.payload().len(), 512 - 16);
+//! assert_eq!(guest.payload().read_at::<u32>(0)?, 42);
+//! assert_eq!(
+//!     SharedMemory::open(region, MAGIC, 2).unwrap_err(),
+//!     VolatileError::InvalidValue
+//! );
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::{
+    fmt, mem,
+    sync::atomic::{fence, Ordering},
+};
+
+use crate::{
+    doorbell::Doorbell, map_field, padding::NoPadding, region::VolatileRegion, VolatileError,
+    VolatilePtr,
+};
+
+/// The header at the start of a [`SharedMemory`] region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(C)]
+pub struct Header {
+    /// Identifies the protocol that uses the region.
+    pub magic: u32,
+    /// The version of the protocol.
+    pub version: u32,
+    /// The size of the region in bytes, including the header.
+    pub size: u64,
+}
+
+// SAFETY: The header consists of integers without padding between them.
+unsafe impl NoPadding for Header {}
+
+/// A shared memory region that starts with a [`Header`].
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy)]
+pub struct SharedMemory<'a> {
+    header: VolatilePtr<'a, Header>,
+    payload: VolatileRegion<'a>,
+}
+
+impl fmt::Debug for SharedMemory<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedMemory")
+            .field("header", &self.header)
+            .field("payload", &self.payload)
+            .finish()
+    }
+}
+
+impl<'a> SharedMemory<'a> {
+    /// Initializes the header of `region` and returns the shared memory.
+    ///
+    /// The magic value is written last after a release fence, so that peers never observe it
+    /// together with an incomplete header.
+    pub fn init(
+        region: VolatileRegion<'a>,
+        magic: u32,
+        version: u32,
+    ) -> Result<Self, VolatileError> {
+        let shared = Self::split(region)?;
+        let header = shared.header;
+        map_field!(header.magic).write(0);
+        map_field!(header.version).write(version);
+        map_field!(header.size).write(region.len() as u64);
+        fence(Ordering::Release);
+        map_field!(header.magic).write(magic);
+        Ok(shared)
+    }
+
+    /// Returns the shared memory in `region` after checking its header.
+    ///
+    /// Returns [`VolatileError::InvalidValue`] if the magic value or the version does not
+    /// match, and [`VolatileError::OutOfBounds`] if the size in the header exceeds `region`.
+    pub fn open(
+        region: VolatileRegion<'a>,
+        magic: u32,
+        version: u32,
+    ) -> Result<Self, VolatileError> {
+        let shared = Self::split(region)?;
+        let header = shared.header;
+        if map_field!(header.magic).read() != magic {
+            return Err(VolatileError::InvalidValue);
+        }
+        fence(Ordering::Acquire);
+        if map_field!(header.version).read() != version {
+            return Err(VolatileError::InvalidValue);
+        }
+        let size = map_field!(header.size).read();
+        match usize::try_from(size) {
+            Ok(size) if size <= region.len() => Ok(shared),
+            _ => Err(VolatileError::OutOfBounds {
+                index: usize::try_from(size).unwrap_or(usize::MAX),
+                len: region.len(),
+            }),
+        }
+    }
+
+    /// Returns a pointer to the header.
+    pub fn header(&self) -> VolatilePtr<'a, Header> {
+        self.header
+    }
+
+    /// Returns the region after the header.
+    pub fn payload(&self) -> VolatileRegion<'a> {
+        self.payload
+    }
+
+    fn split(region: VolatileRegion<'a>) -> Result<Self, VolatileError> {
+        let header_size = mem::size_of::<Header>();
+        Ok(Self {
+            header: region.at(0)?,
+            payload: region.subregion(header_size..region.len())?,
+        })
+    }
+}
+
+/// The registers of an `ivshmem` device in its first BAR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(C)]
+pub struct Registers {
+    /// The interrupt mask.
+    pub interrupt_mask: u32,
+    /// The interrupt status.
+    pub interrupt_status: u32,
+    /// The ID of this peer.
+    pub iv_position: u32,
+    /// Writing `peer << 16 | vector` interrupts `vector` of `peer`.
+    pub doorbell: u32,
+}
+
+/// An `ivshmem` device.
+#[derive(Clone, Copy)]
+pub struct Ivshmem<'a> {
+    registers: VolatilePtr<'a, Registers>,
+}
+
+impl fmt::Debug for Ivshmem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ivshmem").field(&self.registers).finish()
+    }
+}
+
+impl<'a> Ivshmem<'a> {
+    /// Creates a device from a pointer to its registers.
+    pub const fn new(registers: VolatilePtr<'a, Registers>) -> Self {
+        Self { registers }
+    }
+
+    /// Returns the ID of this peer.
+    pub fn id(&self) -> u16 {
+        let registers = self.registers;
+        map_field!(registers.iv_position).read() as u16
+    }
+
+    /// Returns the doorbell register.
+    pub fn doorbell(&self) -> Doorbell<'a, u32> {
+        let registers = self.registers;
+        Doorbell::new(map_field!(registers.doorbell))
+    }
+
+    /// Interrupts `vector` of `peer` after making all previous writes visible.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{
+    ///     ivshmem::{Ivshmem, Registers},
+    ///     VolatilePtr,
+    /// };
+    ///
+    /// let mut registers = Registers {
+    ///     interrupt_mask: 0,
+    ///     interrupt_status: 0,
+    ///     iv_position: 1,
+    ///     doorbell: 0,
+    /// };
+    /// let device = Ivshmem::new(unsafe { VolatilePtr::new((&mut registers).into()) });
+    /// device.notify(2, 0);
+    /// assert_eq!(registers.doorbell, 0x2_0000);
+    /// ```
+    pub fn notify(&self, peer: u16, vector: u16) {
+        self.doorbell()
+            .ring((u32::from(peer) << 16) | u32::from(vector));
+    }
+
+    /// Sets the interrupt mask.
+    pub fn set_interrupt_mask(&self, mask: u32) {
+        let registers = self.registers;
+        map_field!(registers.interrupt_mask).write(mask);
+    }
+
+    /// Reads the interrupt status.
+    pub fn interrupt_status(&self) -> u32 {
+        let registers = self.registers;
+        map_field!(registers.interrupt_status).read()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ivshmem::{Ivshmem, Registers, SharedMemory},
+        map_field,
+        region::VolatileRegion,
+        VolatileError, VolatilePtr,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_shared_memory() {
+        let mut bar = [0_u64; 8];
+        let region = unsafe { VolatileRegion::new(NonNull::from(&mut bar).cast(), 64) };
+        assert_eq!(
+            SharedMemory::open(region, 1, 1).unwrap_err(),
+            VolatileError::InvalidValue
+        );
+
+        let shared = SharedMemory::init(region, 1, 1).unwrap();
+        let header = shared.header();
+        map_field!(header.size).write(128);
+        assert_eq!(
+            SharedMemory::open(region, 1, 1).unwrap_err(),
+            VolatileError::OutOfBounds {
+                index: 128,
+                len: 64
+            }
+        );
+
+        let mut registers = Registers {
+            interrupt_mask: 0,
+            interrupt_status: 4,
+            iv_position: 3,
+            doorbell: 0,
+        };
+        let device = Ivshmem::new(unsafe { VolatilePtr::new(NonNull::from(&mut registers)) });
+        assert_eq!(device.id(), 3);
+        assert_eq!(device.interrupt_status(), 4);
+        device.set_interrupt_mask(1);
+        device.notify(1, 5);
+        assert_eq!(registers.interrupt_mask, 1);
+        assert_eq!(registers.doorbell, 0x1_0005);
+    }
+}
@@ -0,0 +1,89 @@
+/// Asserts the layout of a struct at compile time.
+///
+/// Structs that are maintained by hand against a datasheet can silently drift from the
+/// documented register layout. This macro checks the size of a type, optionally its alignment,
+/// and the offsets of selected fields against the given constants, independently of
+/// `#[derive(VolatileFieldAccess)]`. Any mismatch is reported as a compile-time error that names
+/// the type and the field.
+///
+/// Fields are given as `field @ offset`, where `field` is a named field or a tuple index.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::volatile_assert_layout;
+///
+/// #[repr(C)]
+/// struct Uart {
+///     data: u32,
+///     status: u32,
+///     baud_divisor: u16,
+///     _reserved: [u8; 6],
+/// }
+///
+/// volatile_assert_layout!(Uart, size = 0x10, align = 4, data @ 0x0, status @ 0x4, baud_divisor @ 0x8);
+/// ```
+///
+/// A wrong offset does not compile:
+///
+/// ```compile_fail
+/// use volatile::volatile_assert_layout;
+///
+/// #[repr(C)]
+/// struct Uart { data: u8, status: u32, }
+///
+/// volatile_assert_layout!(Uart, size = 8, status @ 0x1);
+/// ```
+#[macro_export]
+macro_rules! volatile_assert_layout {
+    ($ty:ty, size = $size:expr, align = $align:expr $(, $field:tt @ $offset:expr)* $(,)?) => {
+        $crate::volatile_assert_layout!($ty, size = $size $(, $field @ $offset)*);
+        const _: () = ::core::assert!(
+            ::core::mem::align_of::<$ty>() == $align,
+            ::core::concat!(
+                "`", ::core::stringify!($ty), "` does not have an alignment of ",
+                ::core::stringify!($align)
+            ),
+        );
+    };
+    ($ty:ty, size = $size:expr $(, $field:tt @ $offset:expr)* $(,)?) => {
+        const _: () = {
+            ::core::assert!(
+                ::core::mem::size_of::<$ty>() == $size,
+                ::core::concat!(
+                    "`", ::core::stringify!($ty), "` does not have a size of ",
+                    ::core::stringify!($size)
+                ),
+            );
+            $(
+                ::core::assert!(
+                    ::core::mem::offset_of!($ty, $field) == $offset,
+                    ::core::concat!(
+                        "field `", ::core::stringify!($field), "` of `", ::core::stringify!($ty),
+                        "` is not at offset ", ::core::stringify!($offset)
+                    ),
+                );
+            )*
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_assert_layout() {
+        #[repr(C)]
+        struct Descriptor {
+            addr: u64,
+            len: u32,
+            flags: u16,
+            next: u16,
+        }
+
+        #[repr(C, align(16))]
+        struct Aligned(u8, Descriptor);
+
+        crate::volatile_assert_layout!(Descriptor, size = 16, addr @ 0, len @ 8, flags @ 12, next @ 14);
+        crate::volatile_assert_layout!(Aligned, size = 32, align = 16, 0 @ 0, 1 @ 8,);
+    }
+}
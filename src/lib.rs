@@ -30,10 +30,13 @@
 //! [in our repository](https://github.com/rust-osdev/volatile/issues/31)
 //! and
 //! [in the `unsafe-code-guidelines` repository](https://github.com/rust-lang/unsafe-code-guidelines/issues/411).
+//!
+//! For owned storage that is only accessed through volatile operations, e.g., a variable that is
+//! shared with an interrupt handler, use [`VolatileStatic`] and the [`volatile_static!`] macro
+//! instead. They never create references to the stored value.
 
 #![no_std]
 #![cfg_attr(feature = "unstable", feature(core_intrinsics))]
-#![cfg_attr(feature = "unstable", feature(slice_range))]
 #![cfg_attr(feature = "unstable", feature(slice_ptr_get))]
 #![cfg_attr(feature = "very_unstable", feature(const_trait_impl))]
 #![cfg_attr(feature = "very_unstable", feature(unboxed_closures))]
@@ -47,12 +50,80 @@
 #![doc(test(attr(allow(dead_code))))]
 #![doc(test(attr(allow(unused_variables))))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+// Allows the procedural macros to refer to `::volatile` inside of this crate as well.
+extern crate self as volatile;
+
 #[cfg(feature = "derive")]
-pub use volatile_macro::VolatileFieldAccess;
+pub use volatile_macro::{map_field, volatile_register_block, VolatileFieldAccess};
 
+pub use error::{Mismatch, VolatileError};
+#[doc(hidden)]
+pub use volatile_ptr::__MapFieldIndex;
 pub use volatile_ptr::VolatilePtr;
 pub use volatile_ref::VolatileRef;
+pub use volatile_static::VolatileStatic;
 
 pub mod access;
+pub mod backend;
+pub mod bitband;
+pub mod bitfield;
+pub mod burst;
+#[cfg(any(feature = "zerocopy", feature = "bytemuck"))]
+pub mod cast;
+pub mod consistent;
+#[cfg(feature = "critical-section")]
+pub mod critical;
+pub mod cursor;
+pub mod debug;
+pub mod dma;
+pub mod doorbell;
+pub mod endian;
+mod error;
+#[cfg(feature = "bitflags")]
+pub mod flags;
+pub mod grid;
+pub mod guest;
+pub mod index;
+#[cfg(feature = "instrument")]
+pub mod instrument;
+pub mod ivshmem;
+mod layout;
+pub mod metadata;
+pub mod ordering;
+pub mod padding;
+pub mod phys;
+pub mod poll;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod port;
+pub mod prelude;
+pub mod project;
+pub mod region;
+pub mod register;
+pub mod ring;
+pub mod scatter;
+pub mod shadow;
+#[cfg(feature = "simd")]
+pub mod simd;
+#[cfg(feature = "test-util")]
+pub mod simulator;
+pub mod single_access;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod strided;
+pub mod trace;
+#[cfg(any(feature = "stats", feature = "watch"))]
+mod tracked;
+pub mod traits;
+pub mod typestate;
 mod volatile_ptr;
 mod volatile_ref;
+mod volatile_static;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod width;
+pub mod write_combining;
@@ -0,0 +1,61 @@
+//! Runtime descriptions of struct layouts.
+//!
+//! Debug consoles, register dumps, and fuzzers often need to enumerate the registers of a device
+//! at runtime. The [`VolatileFields`] trait provides this information without duplicating the
+//! layout. With the `derive` feature, it can be implemented through
+//! `#[derive(VolatileFieldAccess)]` and `#[volatile(metadata)]`.
+
+use crate::access::AccessKind;
+
+/// Describes a single field of a struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldDescriptor {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The offset of the field in bytes from the start of the struct.
+    pub offset: usize,
+    /// The size of the field in bytes.
+    pub size: usize,
+    /// The access permissions of the field.
+    pub access: AccessKind,
+}
+
+/// Types whose fields are described by a table of [`FieldDescriptor`]s.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::access::AccessKind;
+/// use volatile::metadata::{FieldDescriptor, VolatileFields};
+///
+/// #[repr(C)]
+/// pub struct DeviceConfig {
+///     feature_select: u32,
+///     feature: u16,
+/// }
+///
+/// impl VolatileFields for DeviceConfig {
+///     const FIELDS: &'static [FieldDescriptor] = &[
+///         FieldDescriptor {
+///             name: "feature_select",
+///             offset: core::mem::offset_of!(DeviceConfig, feature_select),
+///             size: core::mem::size_of::<u32>(),
+///             access: AccessKind::ReadWrite,
+///         },
+///         FieldDescriptor {
+///             name: "feature",
+///             offset: core::mem::offset_of!(DeviceConfig, feature),
+///             size: core::mem::size_of::<u16>(),
+///             access: AccessKind::ReadOnly,
+///         },
+///     ];
+/// }
+///
+/// for field in DeviceConfig::FIELDS {
+///     assert!(field.access.is_readable());
+/// }
+/// ```
+pub trait VolatileFields {
+    /// The descriptors of all fields in declaration order.
+    const FIELDS: &'static [FieldDescriptor];
+}
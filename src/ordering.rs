@@ -0,0 +1,167 @@
+//! Memory-ordering policies for volatile accesses.
+//!
+//! Volatile accesses are not reordered with respect to each other by the compiler, but
+//! neither the compiler nor the CPU orders them with respect to normal memory accesses. Device
+//! drivers often need such ordering, e.g., descriptors in normal memory must be visible to
+//! the device before the MMIO write that notifies it.
+//!
+//! An [`OrderingPolicy`] describes the barriers that are issued around an access.
+//! [`VolatilePtr::read_ordered`] and [`VolatilePtr::write_ordered`] perform a single access
+//! with a given policy.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{ordering::MmioStrict, VolatilePtr};
+//!
+//! let mut doorbell = 0_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut doorbell).into()) };
+//!
+//! // All previous writes are visible to the device before it is notified.
+//! volatile.write_ordered::<MmioStrict>(1);
+//! assert_eq!(volatile.read_ordered::<MmioStrict>(), 1);
+//! ```
+
+use core::sync::atomic::{compiler_fence, fence, Ordering};
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+/// Barriers that are issued around volatile accesses.
+///
+/// This trait is sealed and implemented by [`Relaxed`], [`AcquireRelease`], and
+/// [`MmioStrict`].
+pub trait OrderingPolicy: private::Sealed {
+    /// Issued before a read.
+    fn before_read() {}
+
+    /// Issued after a read.
+    fn after_read() {}
+
+    /// Issued before a write.
+    fn before_write() {}
+
+    /// Issued after a write.
+    fn after_write() {}
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Relaxed {}
+    impl Sealed for super::AcquireRelease {}
+    impl Sealed for super::MmioStrict {}
+}
+
+/// No barriers.
+///
+/// This is the behavior of [`VolatilePtr::read`] and [`VolatilePtr::write`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Relaxed;
+
+impl OrderingPolicy for Relaxed {}
+
+/// Reads have acquire semantics and writes have release semantics.
+///
+/// This orders the accesses with respect to normal memory accesses of other CPUs, e.g., for
+/// memory that is shared between cores.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AcquireRelease;
+
+impl OrderingPolicy for AcquireRelease {
+    fn after_read() {
+        fence(Ordering::Acquire);
+    }
+
+    fn before_write() {
+        fence(Ordering::Release);
+    }
+}
+
+/// Emits the barrier instruction for the current architecture.
+///
+/// On x86, MMIO is strongly ordered, so a compiler fence suffices. Other architectures
+/// without a dedicated instruction and Miri, which does not support inline assembly, fall back
+/// to a full fence.
+macro_rules! barrier {
+    (aarch64: $aarch64:literal, riscv: $riscv:literal) => {{
+        #[cfg(all(target_arch = "aarch64", not(miri)))]
+        unsafe {
+            core::arch::asm!($aarch64, options(nostack, preserves_flags));
+        }
+        #[cfg(all(any(target_arch = "riscv32", target_arch = "riscv64"), not(miri)))]
+        unsafe {
+            core::arch::asm!($riscv, options(nostack, preserves_flags));
+        }
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        compiler_fence(Ordering::SeqCst);
+        #[cfg(any(
+            miri,
+            not(any(
+                target_arch = "aarch64",
+                target_arch = "riscv32",
+                target_arch = "riscv64",
+                target_arch = "x86",
+                target_arch = "x86_64"
+            ))
+        ))]
+        fence(Ordering::SeqCst);
+    }};
+}
+
+/// Device-memory semantics for MMIO, similar to Linux' `readl` and `writel`.
+///
+/// Reads complete before any subsequent memory access, and all previous memory accesses are
+/// visible to devices before a write. This issues `dmb oshld`/`dmb oshst` on AArch64,
+/// `fence i,ir`/`fence w,o` on RISC-V, only compiler fences on x86, and full fences on other
+/// architectures.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MmioStrict;
+
+impl OrderingPolicy for MmioStrict {
+    fn before_read() {
+        compiler_fence(Ordering::SeqCst);
+    }
+
+    fn after_read() {
+        barrier!(aarch64: "dmb oshld", riscv: "fence i, ir");
+    }
+
+    fn before_write() {
+        barrier!(aarch64: "dmb oshst", riscv: "fence w, o");
+    }
+
+    fn after_write() {
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+/// Methods for ordered accesses.
+impl<T, A> VolatilePtr<'_, T, A> {
+    /// Performs a volatile read with the barriers of the ordering policy `O`.
+    pub fn read_ordered<O>(self) -> T
+    where
+        T: Copy,
+        A: Readable,
+        O: OrderingPolicy,
+    {
+        O::before_read();
+        let value = self.read();
+        O::after_read();
+        value
+    }
+
+    /// Performs a volatile write with the barriers of the ordering policy `O`.
+    pub fn write_ordered<O>(self, value: T)
+    where
+        T: Copy,
+        A: Writable,
+        O: OrderingPolicy,
+    {
+        O::before_write();
+        self.write(value);
+        O::after_write();
+    }
+}
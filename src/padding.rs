@@ -0,0 +1,149 @@
+//! Types without padding bytes.
+//!
+//! Reading a type with padding from device memory copies whatever the device wrote into the
+//! padding bytes around, and writing it exposes uninitialized bytes to the device. The
+//! [`NoPadding`] marker trait is implemented for types without padding bytes that are valid
+//! for any bit pattern, and [`VolatilePtr::read_no_padding`] and
+//! [`VolatilePtr::write_no_padding`] only accept such types.
+//!
+//! With `#[derive(VolatileFieldAccess)]` and `#[volatile(no_padding)]` on a struct, the macro
+//! implements `NoPadding` for it after checking its layout like `#[volatile(strict_layout)]`.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::VolatilePtr;
+//!
+//! let mut descriptor = [0_u64; 2];
+//! let volatile = unsafe { VolatilePtr::new((&mut descriptor).into()) };
+//! volatile.write_no_padding([0x1000, 64]);
+//! assert_eq!(volatile.read_no_padding(), [0x1000, 64]);
+//! ```
+//!
+//! Types with padding are rejected at compile time:
+//!
+//! ```compile_fail
+//! use volatile::VolatilePtr;
+//!
+//! #[derive(Clone, Copy)]
+//! #[repr(C)]
+//! struct Padded {
+//!     a: u8,
+//!     b: u32,
+//! }
+//!
+//! let mut padded = Padded { a: 0, b: 0 };
+//! let volatile = unsafe { VolatilePtr::new((&mut padded).into()) };
+//! volatile.read_no_padding();
+//! ```
+
+use core::fmt;
+
+use crate::{
+    access::{Readable, Writable},
+    endian::{Be, Le},
+    VolatilePtr,
+};
+
+/// Types without padding bytes that are valid for any bit pattern.
+///
+/// This is similar to implementing both `FromBytes` and `IntoBytes` of `zerocopy` or `Pod` of
+/// `bytemuck`.
+///
+/// ## Safety
+///
+/// The type must not contain any padding bytes, and every bit pattern of its size must be a
+/// valid value.
+pub unsafe trait NoPadding: Copy {}
+
+macro_rules! impl_no_padding {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl NoPadding for $ty {}
+        )*
+    };
+}
+
+impl_no_padding!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T, const N: usize> NoPadding for [T; N] where T: NoPadding {}
+
+unsafe impl<T> NoPadding for Le<T> where T: NoPadding {}
+
+unsafe impl<T> NoPadding for Be<T> where T: NoPadding {}
+
+/// `N` reserved bytes in a register block.
+///
+/// `#[volatile_register_block]` fills the gaps between the fields with this type. Unlike
+/// `[u8; N]`, it implements [`Default`] for all `N`, so register blocks with gaps of more than
+/// 32 bytes can still derive `Default`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Reserved<const N: usize>([u8; N]);
+
+impl<const N: usize> Reserved<N> {
+    /// Creates zeroed reserved bytes.
+    pub const fn new() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> Default for Reserved<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for Reserved<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Reserved<{N}>")
+    }
+}
+
+unsafe impl<const N: usize> NoPadding for Reserved<N> {}
+
+/// Methods for types without padding.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: NoPadding,
+{
+    /// Performs a volatile read of a value that does not contain padding bytes.
+    ///
+    /// This is the same as [`read`](Self::read), but only accepts types whose every byte is
+    /// part of the value.
+    #[inline(always)]
+    #[track_caller]
+    pub fn read_no_padding(self) -> T
+    where
+        A: Readable,
+    {
+        self.read()
+    }
+
+    /// Performs a volatile write of a value that does not contain padding bytes.
+    ///
+    /// This is the same as [`write`](Self::write), but only accepts types whose every byte is
+    /// part of the value.
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_no_padding(self, value: T)
+    where
+        A: Writable,
+    {
+        self.write(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{endian::Le, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_no_padding() {
+        let mut header = [Le::new(0_u16); 2];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut header)) };
+        volatile.write_no_padding([Le::new(1), Le::new(0x0800)]);
+        assert_eq!(volatile.read_no_padding()[1].get(), 0x0800);
+    }
+}
@@ -0,0 +1,168 @@
+//! Translation of physical addresses into virtual addresses.
+//!
+//! Drivers usually know the physical addresses of their devices, e.g., from PCI BARs or the
+//! device tree, but have to access them through a virtual mapping. Kernels implement
+//! [`PhysToVirt`] once for their mapping scheme, and drivers create volatile pointers and
+//! regions with [`VolatilePtr::from_phys`] and [`VolatileRegion::from_phys`] without
+//! hard-coding the offset of the mapping. [`OffsetMapping`] implements the common case of a
+//! linear mapping of all physical memory at a fixed offset, including the identity mapping.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{phys::OffsetMapping, region::VolatileRegion, VolatileError};
+//!
+//! let mut memory = [0_u32; 16];
+//! let virt_base = memory.as_mut_ptr() as usize;
+//!
+//! // Pretend that physical address 0x1000 is mapped at `memory`.
+//! let mapping = unsafe { OffsetMapping::new(virt_base.wrapping_sub(0x1000)) };
+//! let region = unsafe { VolatileRegion::from_phys(0x1000, 64, &mapping)? };
+//! region.write_at::<u32>(8, 5)?;
+//! assert_eq!(memory[2], 5);
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::{mem, ptr::NonNull};
+
+use crate::{region::VolatileRegion, VolatileError, VolatilePtr};
+
+/// Translates physical addresses into virtual addresses of the current address space.
+///
+/// ## Safety
+///
+/// If [`phys_to_virt`](Self::phys_to_virt) returns a pointer, the `len` bytes at the
+/// physical address must be mapped at this pointer.
+pub unsafe trait PhysToVirt {
+    /// Returns the virtual address at which the `len` bytes at physical address `phys` are
+    /// mapped, or `None` if they are not mapped contiguously.
+    fn phys_to_virt(&self, phys: u64, len: usize) -> Option<NonNull<u8>>;
+}
+
+unsafe impl<T> PhysToVirt for &T
+where
+    T: PhysToVirt + ?Sized,
+{
+    fn phys_to_virt(&self, phys: u64, len: usize) -> Option<NonNull<u8>> {
+        (**self).phys_to_virt(phys, len)
+    }
+}
+
+/// A linear mapping of physical memory at a fixed virtual offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OffsetMapping {
+    offset: usize,
+}
+
+impl OffsetMapping {
+    /// Creates a mapping that maps physical address `p` at virtual address `p + offset`.
+    ///
+    /// ## Safety
+    ///
+    /// All physical memory must be mapped at this offset.
+    pub const unsafe fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    /// Creates the identity mapping.
+    ///
+    /// ## Safety
+    ///
+    /// All physical memory must be identity-mapped.
+    pub const unsafe fn identity() -> Self {
+        Self { offset: 0 }
+    }
+
+    /// Returns the offset of the mapping.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+unsafe impl PhysToVirt for OffsetMapping {
+    fn phys_to_virt(&self, phys: u64, len: usize) -> Option<NonNull<u8>> {
+        let virt = usize::try_from(phys).ok()?.wrapping_add(self.offset);
+        virt.checked_add(len)?;
+        NonNull::new(core::ptr::with_exposed_provenance_mut(virt))
+    }
+}
+
+/// Constructors from physical addresses.
+impl<'a> VolatileRegion<'a> {
+    /// Creates a region of `len` bytes at physical address `phys` that is mapped by
+    /// `translator`.
+    ///
+    /// Returns [`VolatileError::Unmapped`] if the range is not mapped contiguously.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`VolatileRegion::new`] apply to the mapped range.
+    pub unsafe fn from_phys<P>(
+        phys: u64,
+        len: usize,
+        translator: &P,
+    ) -> Result<VolatileRegion<'a>, VolatileError>
+    where
+        P: PhysToVirt + ?Sized,
+    {
+        let base = translator
+            .phys_to_virt(phys, len)
+            .ok_or(VolatileError::Unmapped { addr: phys })?;
+        Ok(unsafe { VolatileRegion::new(base, len) })
+    }
+}
+
+/// Constructors from physical addresses.
+impl<'a, T> VolatilePtr<'a, T> {
+    /// Creates a `VolatilePtr` to the value at physical address `phys` that is mapped by
+    /// `translator`.
+    ///
+    /// Returns [`VolatileError::Unmapped`] if the value is not mapped contiguously and
+    /// [`VolatileError::Misaligned`] if the mapped pointer is not aligned for `T`.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`VolatilePtr::new`] apply to the mapped value.
+    pub unsafe fn from_phys<P>(phys: u64, translator: &P) -> Result<Self, VolatileError>
+    where
+        P: PhysToVirt + ?Sized,
+    {
+        let region = unsafe { VolatileRegion::from_phys(phys, mem::size_of::<T>(), translator) }?;
+        // SAFETY: The caller guarantees that the mapped value is valid.
+        unsafe { region.at_unchecked(0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{phys::PhysToVirt, VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_from_phys() {
+        struct Window(NonNull<u8>);
+
+        unsafe impl PhysToVirt for Window {
+            fn phys_to_virt(&self, phys: u64, len: usize) -> Option<NonNull<u8>> {
+                let offset = usize::try_from(phys.checked_sub(0xfee0_0000)?).ok()?;
+                (offset + len <= 16).then(|| unsafe { self.0.add(offset) })
+            }
+        }
+
+        let mut apic = [0_u32; 4];
+        let window = Window(NonNull::from(&mut apic).cast());
+
+        let eoi = unsafe { VolatilePtr::<u32>::from_phys(0xfee0_0008, &window) }.unwrap();
+        eoi.write(1);
+        assert_eq!(
+            unsafe { VolatilePtr::<u32>::from_phys(0xfee0_0010, &window) }.unwrap_err(),
+            VolatileError::Unmapped { addr: 0xfee0_0010 }
+        );
+        assert!(matches!(
+            unsafe { VolatilePtr::<u32>::from_phys(0xfee0_0002, &window) },
+            Err(VolatileError::Misaligned { .. })
+        ));
+        assert_eq!(apic, [0, 0, 1, 0]);
+    }
+}
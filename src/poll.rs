@@ -0,0 +1,462 @@
+//! Waiting for registers to reach a state.
+//!
+//! [`VolatilePtr::wait_for`] returns a future that re-reads a register on every poll until its
+//! value satisfies a predicate. This allows async executors, e.g., of embedded frameworks or
+//! async kernel tasks, to await device readiness without blocking a core.
+//!
+//! By default, the future wakes itself whenever it returns [`Poll::Pending`], so the executor
+//! polls it again as soon as other tasks made progress. If the device raises an interrupt on
+//! the relevant state change, [`WaitFor::with_waker_source`] registers the waker with the
+//! interrupt handler instead, so the task only runs once the interrupt fired.
+//!
+//! Synchronous drivers can busy-wait with [`VolatilePtr::poll_until_timeout`] instead, which
+//! gives up once a [`Timeout`] expired. Timeouts are either a [`RetryBudget`] or a
+//! [`Deadline`] of a [`Clock`], which makes the timeout behavior testable with a simulated
+//! clock. Between two reads, [`VolatilePtr::poll_until_backoff`] waits according to a
+//! [`Backoff`] policy, e.g., an [`Exponential`] backoff or a callback that yields to a
+//! scheduler, so that long waits do not saturate the bus with back-to-back reads.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{poll::RetryBudget, VolatileError, VolatilePtr};
+//!
+//! async fn wait_until_ready(status: VolatilePtr<'_, u32>) -> u32 {
+//!     status.wait_for(|status| status & 1 != 0).await
+//! }
+//!
+//! fn reset(control: VolatilePtr<'_, u32>) -> Result<(), VolatileError> {
+//!     control.write(1);
+//!     control.poll_until_timeout(|control| control & 1 == 0, RetryBudget::new(1000))?;
+//!     Ok(())
+//! }
+//! # let mut control = 0;
+//! # reset(unsafe { VolatilePtr::new((&mut control).into()) }).unwrap_err();
+//! ```
+
+use core::{
+    fmt,
+    future::Future,
+    hint,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{access::Readable, VolatileError, VolatilePtr};
+
+/// A monotonic clock.
+pub trait Clock {
+    /// A point in time, e.g., a tick count.
+    type Instant: Ord;
+
+    /// Returns the current time.
+    fn now(&self) -> Self::Instant;
+}
+
+impl<C> Clock for &C
+where
+    C: Clock + ?Sized,
+{
+    type Instant = C::Instant;
+
+    fn now(&self) -> Self::Instant {
+        (**self).now()
+    }
+}
+
+/// The monotonic clock of the operating system.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    type Instant = std::time::Instant;
+
+    fn now(&self) -> Self::Instant {
+        std::time::Instant::now()
+    }
+}
+
+/// A policy for waiting between two attempts of a polling loop.
+///
+/// Closures implement this trait by calling themselves, e.g., to yield to a scheduler.
+pub trait Backoff {
+    /// Waits before the next attempt.
+    fn backoff(&mut self);
+}
+
+impl<F> Backoff for F
+where
+    F: FnMut(),
+{
+    fn backoff(&mut self) {
+        self()
+    }
+}
+
+/// A backoff that issues a single spin loop hint between attempts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Spin;
+
+impl Backoff for Spin {
+    fn backoff(&mut self) {
+        hint::spin_loop();
+    }
+}
+
+/// A backoff that doubles the number of spin loop hints after every attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Exponential {
+    spins: u32,
+    max: u32,
+}
+
+impl Exponential {
+    /// Creates a backoff that starts with a single spin and doubles up to `max` spins.
+    pub const fn new(max: u32) -> Self {
+        Self { spins: 1, max }
+    }
+}
+
+impl Backoff for Exponential {
+    fn backoff(&mut self) {
+        for _ in 0..self.spins {
+            hint::spin_loop();
+        }
+        self.spins = self.spins.saturating_mul(2).min(self.max);
+    }
+}
+
+/// A condition that ends a polling loop.
+pub trait Timeout {
+    /// Returns whether the timeout expired.
+    ///
+    /// This is called after every unsuccessful attempt.
+    fn expired(&mut self) -> bool;
+}
+
+impl<T> Timeout for &mut T
+where
+    T: Timeout + ?Sized,
+{
+    fn expired(&mut self) -> bool {
+        (**self).expired()
+    }
+}
+
+/// A timeout that expires after a fixed number of retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryBudget {
+    remaining: usize,
+}
+
+impl RetryBudget {
+    /// Creates a timeout that allows `retries` attempts after the first one.
+    pub const fn new(retries: usize) -> Self {
+        Self { remaining: retries }
+    }
+
+    /// Returns the number of remaining retries.
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl Timeout for RetryBudget {
+    fn expired(&mut self) -> bool {
+        match self.remaining.checked_sub(1) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                false
+            }
+            None => true,
+        }
+    }
+}
+
+/// A timeout that expires once a clock reaches a deadline.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline<C>
+where
+    C: Clock,
+{
+    clock: C,
+    deadline: C::Instant,
+}
+
+impl<C> Deadline<C>
+where
+    C: Clock,
+{
+    /// Creates a timeout that expires once `clock` reaches `deadline`.
+    pub const fn new(clock: C, deadline: C::Instant) -> Self {
+        Self { clock, deadline }
+    }
+}
+
+impl<C> Timeout for Deadline<C>
+where
+    C: Clock,
+{
+    fn expired(&mut self) -> bool {
+        self.clock.now() >= self.deadline
+    }
+}
+
+/// A future that completes once the value behind a pointer satisfies a predicate.
+///
+/// This future is created by [`VolatilePtr::wait_for`]. See the
+/// [module-level documentation](self) for details.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitFor<'a, T, A, F, W = fn(&Waker)> {
+    pointer: VolatilePtr<'a, T, A>,
+    predicate: F,
+    register: W,
+}
+
+impl<T, A, F, W> fmt::Debug for WaitFor<'_, T, A, F, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitFor")
+            .field("pointer", &self.pointer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, A, F, W> WaitFor<'a, T, A, F, W> {
+    /// Registers the waker of each pending poll with `register` instead of waking the task
+    /// immediately.
+    ///
+    /// `register` is called before the register is read, so that a state change between the
+    /// read and the end of the poll still wakes the task. It should store the waker where the
+    /// interrupt handler of the device can wake it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::task::Waker;
+    /// use volatile::VolatilePtr;
+    ///
+    /// fn register_irq_waker(waker: &Waker) {
+    ///     // Store the waker for the interrupt handler, e.g., in an `AtomicWaker`.
+    /// }
+    ///
+    /// async fn wait_until_ready(status: VolatilePtr<'_, u32>) -> u32 {
+    ///     status
+    ///         .wait_for(|status| status & 1 != 0)
+    ///         .with_waker_source(register_irq_waker)
+    ///         .await
+    /// }
+    /// ```
+    pub fn with_waker_source<R>(self, register: R) -> WaitFor<'a, T, A, F, R>
+    where
+        R: FnMut(&Waker),
+    {
+        WaitFor {
+            pointer: self.pointer,
+            predicate: self.predicate,
+            register,
+        }
+    }
+}
+
+// The future never pins its fields.
+impl<T, A, F, W> Unpin for WaitFor<'_, T, A, F, W> {}
+
+impl<T, A, F, W> Future for WaitFor<'_, T, A, F, W>
+where
+    T: Copy,
+    A: Readable,
+    F: FnMut(T) -> bool,
+    W: FnMut(&Waker),
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = self.get_mut();
+        (this.register)(cx.waker());
+        let value = this.pointer.read();
+        if (this.predicate)(value) {
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Methods for waiting on registers.
+impl<'a, T, A> VolatilePtr<'a, T, A>
+where
+    T: Copy,
+    A: Readable,
+{
+    /// Returns a future that completes with the value once it satisfies `predicate`.
+    ///
+    /// The value is read once per poll. See the [module-level documentation](crate::poll)
+    /// for details.
+    pub fn wait_for<F>(self, predicate: F) -> WaitFor<'a, T, A, F>
+    where
+        F: FnMut(T) -> bool,
+    {
+        WaitFor {
+            pointer: self,
+            predicate,
+            register: Waker::wake_by_ref,
+        }
+    }
+
+    /// Reads the value until it satisfies `predicate` or `timeout` expired.
+    ///
+    /// The value is read at least once. Returns [`VolatileError::Timeout`] if the last read
+    /// value did not satisfy `predicate` when `timeout` expired. This issues a spin loop hint
+    /// between two reads, see [`poll_until_backoff`](Self::poll_until_backoff) for other
+    /// policies.
+    pub fn poll_until_timeout<F, O>(self, predicate: F, timeout: O) -> Result<T, VolatileError>
+    where
+        F: FnMut(T) -> bool,
+        O: Timeout,
+    {
+        self.poll_until_backoff(predicate, timeout, Spin)
+    }
+
+    /// Reads the value until it satisfies `predicate` or `timeout` expired, waiting according
+    /// to `backoff` between two reads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{
+    ///     poll::{Exponential, RetryBudget},
+    ///     VolatilePtr,
+    /// };
+    ///
+    /// let mut flash_status = 1_u32;
+    /// let volatile = unsafe { VolatilePtr::new((&mut flash_status).into()) };
+    /// let status = volatile.poll_until_backoff(
+    ///     |status| status & 1 != 0,
+    ///     RetryBudget::new(100),
+    ///     Exponential::new(1024),
+    /// );
+    /// assert_eq!(status, Ok(1));
+    /// ```
+    pub fn poll_until_backoff<F, O, B>(
+        self,
+        mut predicate: F,
+        mut timeout: O,
+        mut backoff: B,
+    ) -> Result<T, VolatileError>
+    where
+        F: FnMut(T) -> bool,
+        O: Timeout,
+        B: Backoff,
+    {
+        loop {
+            let value = self.read();
+            if predicate(value) {
+                return Ok(value);
+            }
+            if timeout.expired() {
+                return Err(VolatileError::Timeout);
+            }
+            backoff.backoff();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        poll::{Backoff, Clock, Deadline, Exponential, RetryBudget},
+        VolatileError, VolatilePtr,
+    };
+    use core::{
+        cell::Cell,
+        future::Future,
+        pin::pin,
+        ptr::NonNull,
+        task::{Context, Poll, Waker},
+    };
+
+    #[test]
+    fn test_wait_for() {
+        let mut status = 0_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut status)) };
+        let registered = Cell::new(0);
+
+        let mut future = pin!(volatile
+            .wait_for(|status| status & 1 != 0)
+            .with_waker_source(|_| registered.set(registered.get() + 1)));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+        volatile.write(3);
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(3));
+        assert_eq!(registered.get(), 3);
+    }
+
+    #[test]
+    fn test_poll_until_timeout() {
+        struct TestClock(Cell<u64>);
+
+        impl Clock for TestClock {
+            type Instant = u64;
+
+            fn now(&self) -> u64 {
+                let now = self.0.get();
+                self.0.set(now + 10);
+                now
+            }
+        }
+
+        let mut status = 0_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut status)) };
+
+        let mut budget = RetryBudget::new(3);
+        assert_eq!(
+            volatile.poll_until_timeout(|status| status != 0, &mut budget),
+            Err(VolatileError::Timeout)
+        );
+        assert_eq!(budget.remaining(), 0);
+
+        let clock = TestClock(Cell::new(0));
+        assert_eq!(
+            volatile.poll_until_timeout(|status| status != 0, Deadline::new(&clock, 35)),
+            Err(VolatileError::Timeout)
+        );
+        assert_eq!(clock.0.get(), 50);
+
+        volatile.write(2);
+        assert_eq!(
+            volatile.poll_until_timeout(|status| status != 0, RetryBudget::new(0)),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_poll_until_backoff() {
+        let mut status = 0_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut status)) };
+
+        let mut yields = 0;
+        assert_eq!(
+            volatile.poll_until_backoff(|status| status != 0, RetryBudget::new(4), || yields += 1),
+            Err(VolatileError::Timeout)
+        );
+        assert_eq!(yields, 4);
+
+        let mut backoff = Exponential::new(4);
+        for _ in 0..3 {
+            backoff.backoff();
+        }
+        assert_eq!(backoff, {
+            let mut expected = Exponential::new(4);
+            expected.backoff();
+            expected.backoff();
+            expected
+        });
+    }
+}
@@ -0,0 +1,239 @@
+//! Port-mapped I/O on x86 and x86_64.
+//!
+//! Legacy devices such as the PIC, the PIT, or serial ports are accessed through a separate
+//! I/O address space using the `in` and `out` instructions. [`VolatilePort`] provides the same
+//! typed access discipline for these ports that [`VolatilePtr`](crate::VolatilePtr) provides
+//! for memory-mapped I/O.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use volatile::port::VolatilePort;
+//!
+//! // The data and line status registers of the COM1 serial port.
+//! let data = unsafe { VolatilePort::<u8>::new(0x3f8) };
+//! let line_status = unsafe { VolatilePort::<u8>::new_read_only(0x3f8 + 5) };
+//!
+//! while line_status.read() & 0x20 == 0 {}
+//! data.write(b'a');
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use crate::access::{Access, ReadOnly, ReadWrite, Readable, RestrictAccess, Writable, WriteOnly};
+
+/// Values that can be transferred through an I/O port.
+///
+/// This trait is sealed and implemented for [`u8`], [`u16`], and [`u32`].
+pub trait PortValue: Copy + private::Sealed {}
+
+impl PortValue for u8 {}
+impl PortValue for u16 {}
+impl PortValue for u32 {}
+
+mod private {
+    use core::arch::asm;
+
+    pub trait Sealed {
+        unsafe fn read_from_port(port: u16) -> Self;
+        unsafe fn write_to_port(port: u16, value: Self);
+    }
+
+    impl Sealed for u8 {
+        unsafe fn read_from_port(port: u16) -> Self {
+            let value: u8;
+            unsafe {
+                asm!("in al, dx", out("al") value, in("dx") port, options(nomem, nostack, preserves_flags));
+            }
+            value
+        }
+
+        unsafe fn write_to_port(port: u16, value: Self) {
+            unsafe {
+                asm!("out dx, al", in("dx") port, in("al") value, options(nomem, nostack, preserves_flags));
+            }
+        }
+    }
+
+    impl Sealed for u16 {
+        unsafe fn read_from_port(port: u16) -> Self {
+            let value: u16;
+            unsafe {
+                asm!("in ax, dx", out("ax") value, in("dx") port, options(nomem, nostack, preserves_flags));
+            }
+            value
+        }
+
+        unsafe fn write_to_port(port: u16, value: Self) {
+            unsafe {
+                asm!("out dx, ax", in("dx") port, in("ax") value, options(nomem, nostack, preserves_flags));
+            }
+        }
+    }
+
+    impl Sealed for u32 {
+        unsafe fn read_from_port(port: u16) -> Self {
+            let value: u32;
+            unsafe {
+                asm!("in eax, dx", out("eax") value, in("dx") port, options(nomem, nostack, preserves_flags));
+            }
+            value
+        }
+
+        unsafe fn write_to_port(port: u16, value: Self) {
+            unsafe {
+                asm!("out dx, eax", in("dx") port, in("eax") value, options(nomem, nostack, preserves_flags));
+            }
+        }
+    }
+}
+
+/// An I/O port with typed access.
+///
+/// Like [`VolatilePtr`](crate::VolatilePtr), this type restricts the allowed operations
+/// through the access parameter `A`, which can be one of [`ReadWrite`], [`ReadOnly`], or
+/// [`WriteOnly`]. It defaults to `ReadWrite`.
+pub struct VolatilePort<T, A = ReadWrite> {
+    port: u16,
+    value: PhantomData<T>,
+    access: PhantomData<A>,
+}
+
+impl<T, A> Copy for VolatilePort<T, A> {}
+
+impl<T, A> Clone for VolatilePort<T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> fmt::Debug for VolatilePort<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VolatilePort")
+            .field(&format_args!("{:#x}", self.port))
+            .finish()
+    }
+}
+
+/// Constructor functions.
+impl<T> VolatilePort<T>
+where
+    T: PortValue,
+{
+    /// Creates a new volatile port with read and write access.
+    ///
+    /// ## Safety
+    ///
+    /// Accessing the port must not violate memory safety. This might not be the case, for
+    /// example, if the port controls a DMA engine.
+    pub const unsafe fn new(port: u16) -> VolatilePort<T, ReadWrite> {
+        unsafe { Self::new_restricted(ReadWrite, port) }
+    }
+
+    /// Creates a new read-only volatile port.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    pub const unsafe fn new_read_only(port: u16) -> VolatilePort<T, ReadOnly> {
+        unsafe { Self::new_restricted(ReadOnly, port) }
+    }
+
+    /// Creates a new write-only volatile port.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    pub const unsafe fn new_write_only(port: u16) -> VolatilePort<T, WriteOnly> {
+        unsafe { Self::new_restricted(WriteOnly, port) }
+    }
+
+    /// Creates a new volatile port with restricted access.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    pub const unsafe fn new_restricted<A>(access: A, port: u16) -> VolatilePort<T, A>
+    where
+        A: Access,
+    {
+        let _ = access;
+        VolatilePort {
+            port,
+            value: PhantomData,
+            access: PhantomData,
+        }
+    }
+}
+
+impl<T, A> VolatilePort<T, A>
+where
+    T: PortValue,
+{
+    /// Returns the port number.
+    pub const fn port(self) -> u16 {
+        self.port
+    }
+
+    /// Reads a value from the port using the `in` instruction.
+    ///
+    /// ## Example
+    ///
+    /// ```compile_fail
+    /// # use volatile::port::VolatilePort;
+    /// let port = unsafe { VolatilePort::<u8>::new_write_only(0x80) };
+    /// port.read(); // error: the port is write-only
+    /// ```
+    pub fn read(self) -> T
+    where
+        A: Readable,
+    {
+        // SAFETY: The caller of the constructor guaranteed that accessing the port is safe.
+        unsafe { T::read_from_port(self.port) }
+    }
+
+    /// Writes a value to the port using the `out` instruction.
+    pub fn write(self, value: T)
+    where
+        A: Writable,
+    {
+        // SAFETY: The caller of the constructor guaranteed that accessing the port is safe.
+        unsafe { T::write_to_port(self.port, value) }
+    }
+
+    /// Reads a value from the port, updates it with the given closure, and writes it back.
+    pub fn update<F>(self, f: F)
+    where
+        A: Readable + Writable,
+        F: FnOnce(T) -> T,
+    {
+        self.write(f(self.read()));
+    }
+
+    /// Restricts access permissions to `To`.
+    pub fn restrict<To>(self) -> VolatilePort<T, A::Restricted>
+    where
+        A: RestrictAccess<To>,
+    {
+        VolatilePort {
+            port: self.port,
+            value: PhantomData,
+            access: PhantomData,
+        }
+    }
+}
+
+impl<T> VolatilePort<T, ReadWrite>
+where
+    T: PortValue,
+{
+    /// Restricts access permissions to read-only.
+    pub fn read_only(self) -> VolatilePort<T, ReadOnly> {
+        self.restrict()
+    }
+
+    /// Restricts access permissions to write-only.
+    pub fn write_only(self) -> VolatilePort<T, WriteOnly> {
+        self.restrict()
+    }
+}
@@ -0,0 +1,32 @@
+//! Re-exports of the commonly used items of this crate.
+//!
+//! Driver modules usually need the wrapper types, the access markers, and field projection. They
+//! can import all of them at once:
+//!
+//! ```
+//! use volatile::prelude::*;
+//!
+//! #[repr(C)]
+//! struct Uart { data: u8, status: u8, }
+//!
+//! let mut registers = Uart { data: 0, status: 1 };
+//! let uart: VolatilePtr<'_, Uart, ReadWrite> =
+//!     unsafe { VolatilePtr::new((&mut registers).into()) };
+//! let status: VolatilePtr<'_, u8, ReadOnly> = map_field!(uart.status).read_only();
+//! map_field!(uart.data).write(status.read());
+//! assert_eq!(registers.data, 1);
+//! ```
+//!
+//! The traits are re-exported as well, so that their methods are in scope. The traits generated
+//! by `#[derive(VolatileFieldAccess)]` are named after the annotated struct and have to be
+//! imported from the module that defines it.
+
+pub use crate::access::{Access, NoAccess, ReadOnly, ReadWrite, Readable, Writable, WriteOnly};
+pub use crate::metadata::VolatileFields;
+pub use crate::project::{FieldOffset, VolatileProject};
+pub use crate::traits::{VolatileReadable, VolatileWritable};
+pub use crate::{field_offset, map_field, map_fields};
+pub use crate::{VolatileError, VolatilePtr, VolatileRef, VolatileStatic};
+
+#[cfg(feature = "derive")]
+pub use crate::{volatile_register_block, VolatileFieldAccess};
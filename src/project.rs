@@ -0,0 +1,251 @@
+//! Field projection for generic code.
+//!
+//! [`map_field!`](crate::map_field) needs the concrete field syntax at the call site, so
+//! libraries cannot use it to access a field of a type that is only known through a generic
+//! parameter. A [`FieldOffset`] describes a field of type `F` inside a `T` as a value instead.
+//! It is created with the [`field_offset!`](crate::field_offset) macro and can be passed to
+//! generic code, which projects any [`VolatileProject`] container with it.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{
+//!     access::ReadWrite,
+//!     field_offset,
+//!     project::{FieldOffset, VolatileProject},
+//!     traits::VolatileWritable,
+//!     VolatilePtr, VolatileRef,
+//! };
+//!
+//! #[repr(C)]
+//! struct Queue {
+//!     head: u16,
+//!     tail: u16,
+//! }
+//!
+//! /// Advances any counter that can be projected from `T`.
+//! fn advance<T, C>(container: C, counter: FieldOffset<T, u16>)
+//! where
+//!     C: VolatileProject<T, u16>,
+//!     C::Projected: VolatileWritable<u16, Access = ReadWrite>,
+//! {
+//!     container.project(counter).update(|value| value.wrapping_add(1));
+//! }
+//!
+//! let mut queue = Queue { head: 0, tail: 0 };
+//! advance(VolatileRef::from_mut_ref(&mut queue), field_offset!(Queue, tail));
+//! advance(
+//!     unsafe { VolatilePtr::new((&mut queue).into()) },
+//!     field_offset!(Queue, head),
+//! );
+//! assert_eq!((queue.head, queue.tail), (1, 1));
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{access::Access, VolatilePtr, VolatileRef};
+
+/// The location of a field of type `F` inside a value of type `T`.
+///
+/// The field is always aligned, so projections to it can be read and written. Use the
+/// [`field_offset!`](crate::field_offset) macro to create instances.
+pub struct FieldOffset<T, F> {
+    offset: usize,
+    types: PhantomData<fn(T) -> F>,
+}
+
+impl<T, F> Clone for FieldOffset<T, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, F> Copy for FieldOffset<T, F> {}
+
+impl<T, F> fmt::Debug for FieldOffset<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldOffset")
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// Methods for field offsets.
+impl<T, F> FieldOffset<T, F> {
+    /// Creates a field offset from a byte offset.
+    ///
+    /// ## Safety
+    ///
+    /// Every `T` must contain a valid and aligned `F` at `offset` bytes from its start.
+    pub const unsafe fn new(offset: usize) -> Self {
+        Self {
+            offset,
+            types: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    pub const unsafe fn __new(offset: usize, _field: fn(*mut T) -> *mut F) -> Self {
+        unsafe { Self::new(offset) }
+    }
+
+    /// Returns the offset of the field in bytes.
+    pub const fn offset(self) -> usize {
+        self.offset
+    }
+
+    /// Combines this offset with the offset of a field of `F`, e.g., for nested structs.
+    pub const fn then<G>(self, field: FieldOffset<F, G>) -> FieldOffset<T, G> {
+        // SAFETY: The field of `F` is part of the field of `T`.
+        unsafe { FieldOffset::new(self.offset + field.offset) }
+    }
+
+    /// Projects a volatile pointer to the field.
+    pub fn project_ptr<'a, A>(self, volatile: VolatilePtr<'a, T, A>) -> VolatilePtr<'a, F, A>
+    where
+        A: Access,
+    {
+        // SAFETY: The field lies within the value and is aligned.
+        unsafe { volatile.map(|ptr| ptr.byte_add(self.offset).cast()) }
+    }
+}
+
+/// Volatile containers that can be projected to a field of type `U` of their referenced `T`.
+///
+/// The projection consumes the container, so that exclusive containers like [`VolatileRef`] stay
+/// exclusive. Use [`VolatileRef::borrow_mut`] to project a reference without giving it up.
+pub trait VolatileProject<T, U> {
+    /// The container for the field.
+    type Projected;
+
+    /// Projects the container to the given field.
+    fn project(self, field: FieldOffset<T, U>) -> Self::Projected;
+}
+
+impl<'a, T, U, A> VolatileProject<T, U> for VolatilePtr<'a, T, A>
+where
+    U: 'a,
+    A: Access,
+{
+    type Projected = VolatilePtr<'a, U, A>;
+
+    fn project(self, field: FieldOffset<T, U>) -> VolatilePtr<'a, U, A> {
+        field.project_ptr(self)
+    }
+}
+
+impl<'a, T, U, A> VolatileProject<T, U> for VolatileRef<'a, T, A>
+where
+    U: 'a,
+    A: Access,
+{
+    type Projected = VolatileRef<'a, U, A>;
+
+    fn project(self, field: FieldOffset<T, U>) -> VolatileRef<'a, U, A> {
+        let pointer = field.project_ptr(self.into_ptr()).as_raw_ptr();
+        // SAFETY: The field is part of the exclusively borrowed value.
+        unsafe { VolatileRef::new_restricted(A::default(), pointer) }
+    }
+}
+
+/// Creates a [`FieldOffset`](crate::project::FieldOffset) for a field of a struct.
+///
+/// `field_offset!(Type, field)` describes the field `field` of `Type`, which can be a named
+/// field or a tuple index. Like [`map_field!`](crate::map_field), the macro rejects potentially
+/// unaligned fields of packed structs at compile time.
+///
+/// ## Example
+///
+/// ```
+/// use volatile::{field_offset, VolatilePtr};
+///
+/// #[repr(C)]
+/// struct Queue { desc_addr: u64, size: u16, }
+/// #[repr(C)]
+/// struct Device { status: u32, queue: Queue, }
+///
+/// let size = field_offset!(Device, queue).then(field_offset!(Queue, size));
+/// assert_eq!(size.offset(), 16);
+///
+/// let mut value = Device { status: 0, queue: Queue { desc_addr: 0x1000, size: 256 } };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+/// assert_eq!(size.project_ptr(volatile).read(), 256);
+/// ```
+///
+/// ```compile_fail
+/// use volatile::field_offset;
+///
+/// #[repr(packed)]
+/// struct Example { field_1: u8, field_2: usize, }
+///
+/// let field_2 = field_offset!(Example, field_2);
+/// ```
+#[macro_export]
+macro_rules! field_offset {
+    ($ty:ty, $field:tt) => {{
+        // Reject potentially unaligned fields, see `map_field!`.
+        let _ = |value: &$ty| {
+            let _ref_to_field = &value.$field;
+        };
+        unsafe {
+            $crate::project::FieldOffset::__new(
+                ::core::mem::offset_of!($ty, $field),
+                |value: *mut $ty| ::core::ptr::addr_of_mut!((*value).$field),
+            )
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        project::{FieldOffset, VolatileProject},
+        VolatileRef,
+    };
+
+    #[test]
+    fn test_project() {
+        #[repr(C)]
+        struct Inner {
+            a: u8,
+            b: u32,
+        }
+
+        #[repr(C)]
+        struct Outer {
+            flag: u16,
+            inner: Inner,
+            pair: (u8, u64),
+        }
+
+        fn project<T, C>(container: C, field: FieldOffset<T, u32>) -> C::Projected
+        where
+            C: VolatileProject<T, u32>,
+        {
+            container.project(field)
+        }
+
+        let b = field_offset!(Outer, inner).then(field_offset!(Inner, b));
+        assert_eq!(b.offset(), 8);
+        let second = field_offset!(Outer, pair).then(field_offset!((u8, u64), 1));
+        assert_eq!(second.offset(), 24);
+
+        let mut val = Outer {
+            flag: 1,
+            inner: Inner { a: 2, b: 3 },
+            pair: (4, 5),
+        };
+        let mut volatile = VolatileRef::from_mut_ref(&mut val);
+        project(volatile.borrow_mut(), b).as_mut_ptr().write(30);
+        let flag = field_offset!(Outer, flag);
+        assert_eq!(volatile.borrow_mut().project(flag).as_ptr().read(), 1);
+        let volatile = volatile.into_ptr();
+        assert_eq!(project(volatile, b).read(), 30);
+        let a = field_offset!(Outer, inner).then(field_offset!(Inner, a));
+        a.project_ptr(volatile).write(20);
+        second.project_ptr(volatile).write(50);
+        assert_eq!(val.inner.a, 20);
+        assert_eq!(val.inner.b, 30);
+        assert_eq!(val.pair, (4, 50));
+    }
+}
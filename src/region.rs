@@ -0,0 +1,450 @@
+//! Bounds-checked access into raw memory windows.
+//!
+//! A [`VolatileRegion`] covers a range of bytes, e.g., a PCI BAR or a device-tree `reg`
+//! window. It hands out typed [`VolatilePtr`]s at byte offsets after checking bounds and
+//! alignment, which removes unsafe offset computations from drivers.
+//!
+//! Since the region may contain arbitrary bytes, the typed accessors only accept types that
+//! implement [`NoPadding`]. For other types, use the unsafe [`VolatileRegion::at_unchecked`]
+//! and [`VolatileRegion::slice_unchecked`].
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{region::VolatileRegion, VolatileError};
+//!
+//! let mut bar = [0_u32; 64];
+//! let region = unsafe { VolatileRegion::new(NonNull::from(&mut bar).cast(), 256) };
+//!
+//! let status = region.at::<u32>(0x10)?;
+//! status.write(0x8000_0001);
+//! assert_eq!(bar[4], 0x8000_0001);
+//!
+//! let fifo = region.slice::<u8>(0x80..0x100)?;
+//! assert_eq!(fifo.as_raw_ptr().len(), 0x80);
+//!
+//! // Protocol parsers can read and write values directly.
+//! region.write_at::<u16>(0x20, 0x0800)?;
+//! assert_eq!(region.read_at::<u16>(0x20)?, 0x0800);
+//!
+//! assert_eq!(
+//!     region.at::<u32>(0x100).unwrap_err(),
+//!     VolatileError::OutOfBounds { index: 0x104, len: 0x100 }
+//! );
+//! assert_eq!(
+//!     region.at::<u32>(0x11).unwrap_err(),
+//!     VolatileError::Misaligned { required: 4, found: 1 }
+//! );
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use core::{fmt, marker::PhantomData, mem, ops::Range, ptr::NonNull};
+
+use crate::{
+    access::{Access, ReadOnly, ReadWrite, Readable, RestrictAccess, Writable, WriteOnly},
+    endian::int_accessors,
+    padding::NoPadding,
+    VolatileError, VolatilePtr,
+};
+
+/// A range of bytes that can be accessed through typed volatile pointers at byte offsets.
+///
+/// Like [`VolatilePtr`], this type restricts the allowed operations through the access
+/// parameter `A`, which is passed on to all handed out pointers.
+pub struct VolatileRegion<'a, A = ReadWrite> {
+    base: NonNull<u8>,
+    len: usize,
+    reference: PhantomData<&'a [u8]>,
+    access: PhantomData<A>,
+}
+
+impl<A> Copy for VolatileRegion<'_, A> {}
+
+impl<A> Clone for VolatileRegion<'_, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> fmt::Debug for VolatileRegion<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileRegion")
+            .field("base", &self.base)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Constructor functions.
+impl<'a> VolatileRegion<'a> {
+    /// Creates a region of `len` bytes starting at `base`.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`VolatilePtr::new`] apply to the whole range `base..base + len`.
+    pub const unsafe fn new(base: NonNull<u8>, len: usize) -> VolatileRegion<'a, ReadWrite> {
+        unsafe { Self::new_restricted(ReadWrite, base, len) }
+    }
+
+    /// Creates a region with restricted access.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`Self::new`] apply to this function too.
+    pub const unsafe fn new_restricted<A>(
+        access: A,
+        base: NonNull<u8>,
+        len: usize,
+    ) -> VolatileRegion<'a, A>
+    where
+        A: Access,
+    {
+        let _ = access;
+        VolatileRegion {
+            base,
+            len,
+            reference: PhantomData,
+            access: PhantomData,
+        }
+    }
+}
+
+impl<'a, A> VolatileRegion<'a, A>
+where
+    A: Access,
+{
+    /// Creates a region that covers the given volatile byte slice.
+    pub fn from_ptr(slice: VolatilePtr<'a, [u8], A>) -> Self {
+        let pointer = slice.as_raw_ptr();
+        Self {
+            base: pointer.cast(),
+            len: pointer.len(),
+            reference: PhantomData,
+            access: PhantomData,
+        }
+    }
+
+    /// Returns the length of the region in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the region is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a pointer to the start of the region.
+    pub const fn as_raw_ptr(&self) -> NonNull<u8> {
+        self.base
+    }
+
+    /// Returns a volatile pointer to the value of type `T` at byte offset `offset`.
+    ///
+    /// Types that are not valid for every bit pattern are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use core::ptr::NonNull;
+    /// use volatile::region::VolatileRegion;
+    ///
+    /// let mut bytes = [2_u8; 4];
+    /// let region = unsafe { VolatileRegion::new(NonNull::from(&mut bytes).cast(), 4) };
+    /// region.at::<bool>(0);
+    /// ```
+    pub fn at<T>(&self, offset: usize) -> Result<VolatilePtr<'a, T, A>, VolatileError>
+    where
+        T: NoPadding,
+    {
+        // SAFETY: `T` is valid for any bytes and has no padding.
+        unsafe { self.at_unchecked(offset) }
+    }
+
+    /// Returns a volatile pointer to the value of type `T` at byte offset `offset` for any `T`.
+    ///
+    /// Like [`at`](Self::at), this checks bounds and alignment, but it does not require `T` to
+    /// implement [`NoPadding`].
+    ///
+    /// ## Safety
+    ///
+    /// The bytes at `offset` must be a valid `T` whenever they are read through the returned
+    /// pointer, and writing a `T` to them must not break any other users of the region.
+    pub unsafe fn at_unchecked<T>(
+        &self,
+        offset: usize,
+    ) -> Result<VolatilePtr<'a, T, A>, VolatileError> {
+        let pointer = self.checked_ptr::<T>(offset, mem::size_of::<T>())?;
+        // SAFETY: The value is within the region and properly aligned.
+        Ok(unsafe { VolatilePtr::new_restricted(A::default(), pointer) })
+    }
+
+    /// Returns a volatile pointer to the slice of `T` that covers the given byte range.
+    pub fn slice<T>(&self, range: Range<usize>) -> Result<VolatilePtr<'a, [T], A>, VolatileError>
+    where
+        T: NoPadding,
+    {
+        // SAFETY: `T` is valid for any bytes and has no padding.
+        unsafe { self.slice_unchecked(range) }
+    }
+
+    /// Returns a volatile pointer to the slice of `T` that covers the given byte range for any
+    /// `T`.
+    ///
+    /// Like [`slice`](Self::slice), this checks bounds and alignment, but it does not require
+    /// `T` to implement [`NoPadding`].
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`at_unchecked`](Self::at_unchecked) apply to every element.
+    pub unsafe fn slice_unchecked<T>(
+        &self,
+        range: Range<usize>,
+    ) -> Result<VolatilePtr<'a, [T], A>, VolatileError> {
+        let byte_len = range
+            .end
+            .checked_sub(range.start)
+            .ok_or(self.out_of_bounds(range.start))?;
+        let len = match mem::size_of::<T>() {
+            0 => 0,
+            size if byte_len % size == 0 => byte_len / size,
+            size => {
+                return Err(VolatileError::SizeMismatch {
+                    size,
+                    len: byte_len,
+                })
+            }
+        };
+        let pointer = self.checked_ptr::<T>(range.start, byte_len)?;
+        let pointer = NonNull::slice_from_raw_parts(pointer, len);
+        // SAFETY: The slice is within the region and properly aligned.
+        Ok(unsafe { VolatilePtr::new_restricted(A::default(), pointer) })
+    }
+
+    /// Reads the value of type `T` at byte offset `offset`.
+    ///
+    /// This is a shorthand for `self.at::<T>(offset)?.read()`.
+    pub fn read_at<T>(&self, offset: usize) -> Result<T, VolatileError>
+    where
+        T: NoPadding,
+        A: Readable,
+    {
+        self.at::<T>(offset).map(VolatilePtr::read)
+    }
+
+    /// Writes `value` to byte offset `offset`.
+    ///
+    /// This is a shorthand for `self.at::<T>(offset)?.write(value)`.
+    pub fn write_at<T>(&self, offset: usize, value: T) -> Result<(), VolatileError>
+    where
+        T: NoPadding,
+        A: Writable,
+    {
+        self.at::<T>(offset).map(|pointer| pointer.write(value))
+    }
+
+    /// Returns the region that covers the given byte range of this region.
+    pub fn subregion(&self, range: Range<usize>) -> Result<Self, VolatileError> {
+        let len = range
+            .end
+            .checked_sub(range.start)
+            .ok_or(self.out_of_bounds(range.start))?;
+        let base = self.checked_ptr::<u8>(range.start, len)?;
+        Ok(Self {
+            base,
+            len,
+            reference: PhantomData,
+            access: PhantomData,
+        })
+    }
+
+    /// Restricts access permissions to `To`.
+    pub fn restrict<To>(self) -> VolatileRegion<'a, A::Restricted>
+    where
+        A: RestrictAccess<To>,
+    {
+        VolatileRegion {
+            base: self.base,
+            len: self.len,
+            reference: PhantomData,
+            access: PhantomData,
+        }
+    }
+
+    /// Restricts access permissions to read-only.
+    ///
+    /// This is a shorthand for `restrict::<ReadOnly>()` and requires read access.
+    pub fn read_only(self) -> VolatileRegion<'a, ReadOnly>
+    where
+        A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+    {
+        self.restrict()
+    }
+
+    /// Restricts access permissions to write-only.
+    ///
+    /// This is a shorthand for `restrict::<WriteOnly>()` and requires write access.
+    pub fn write_only(self) -> VolatileRegion<'a, WriteOnly>
+    where
+        A: RestrictAccess<WriteOnly, Restricted = WriteOnly>,
+    {
+        self.restrict()
+    }
+
+    fn checked_ptr<T>(&self, offset: usize, len: usize) -> Result<NonNull<T>, VolatileError> {
+        let end = offset.saturating_add(len);
+        if end > self.len {
+            return Err(self.out_of_bounds(end));
+        }
+        // SAFETY: The offset is within the region.
+        let pointer = unsafe { self.base.add(offset) }.cast::<T>();
+        if pointer.as_ptr().is_aligned() {
+            Ok(pointer)
+        } else {
+            Err(VolatileError::misaligned(pointer.as_ptr()))
+        }
+    }
+
+    fn out_of_bounds(&self, index: usize) -> VolatileError {
+        VolatileError::OutOfBounds {
+            index,
+            len: self.len,
+        }
+    }
+}
+
+/// Methods for byte-offset accesses.
+///
+/// The fixed-endian integer accessors access the integer as a byte array, so they work for
+/// descriptor formats that are defined as unaligned byte streams. The byte array is read or
+/// written with a single volatile access of an unspecified width: the compiler may use one
+/// access for the whole array or split it into narrower accesses. Use [`read_at`](Self::read_at)
+/// and [`write_at`](Self::write_at) with an integer type for registers that require a specific
+/// access width.
+impl<A> VolatilePtr<'_, [u8], A>
+where
+    A: Access,
+{
+    /// Reads the value of type `T` at byte offset `offset` within the slice.
+    ///
+    /// See [`VolatileRegion::read_at`] for details. Since the slice may contain arbitrary
+    /// bytes, `T` must implement [`NoPadding`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::ptr::NonNull;
+    /// use volatile::{VolatileError, VolatilePtr};
+    ///
+    /// let mut packet = [0_u16; 8];
+    /// packet[1] = 0x0800;
+    /// let bytes = NonNull::slice_from_raw_parts(NonNull::from(&mut packet).cast::<u8>(), 16);
+    /// let volatile = unsafe { VolatilePtr::new(bytes) };
+    /// assert_eq!(volatile.read_at::<u16>(2)?, 0x0800);
+    /// assert!(volatile.read_at::<u16>(15).is_err());
+    /// # Ok::<(), VolatileError>(())
+    /// ```
+    ///
+    /// Types that are not valid for every bit pattern are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use core::ptr::NonNull;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut bytes = [2_u8; 4];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut bytes[..])) };
+    /// volatile.read_at::<bool>(0);
+    /// ```
+    pub fn read_at<T>(self, offset: usize) -> Result<T, VolatileError>
+    where
+        T: NoPadding,
+        A: Readable,
+    {
+        VolatileRegion::from_ptr(self).read_at(offset)
+    }
+
+    /// Writes `value` to byte offset `offset` within the slice.
+    ///
+    /// See [`VolatileRegion::write_at`] for details. `T` must implement [`NoPadding`], so that no
+    /// uninitialized padding bytes are written into the slice.
+    pub fn write_at<T>(self, offset: usize, value: T) -> Result<(), VolatileError>
+    where
+        T: NoPadding,
+        A: Writable,
+    {
+        VolatileRegion::from_ptr(self).write_at(offset, value)
+    }
+
+    int_accessors! {
+        ([] self, offset: usize), read_at, write_at, "at byte offset `offset`";
+        u16: read_u16_le, read_u16_be, write_u16_le, write_u16_be;
+        u32: read_u32_le, read_u32_be, write_u32_le, write_u32_be;
+        u64: read_u64_le, read_u64_be, write_u64_le, write_u64_be;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{region::VolatileRegion, VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_region() {
+        let mut val = [0_u16; 8];
+        let region = unsafe { VolatileRegion::new(NonNull::from(&mut val).cast(), 16) };
+
+        let sub = region.subregion(4..12).unwrap();
+        assert_eq!(sub.len(), 8);
+        sub.at::<u16>(2).unwrap().write(7);
+        assert_eq!(val[3], 7);
+
+        assert_eq!(
+            sub.subregion(4..13).unwrap_err(),
+            VolatileError::OutOfBounds { index: 13, len: 8 }
+        );
+        assert_eq!(
+            sub.slice::<u16>(0..3).unwrap_err(),
+            VolatileError::SizeMismatch { size: 2, len: 3 }
+        );
+        assert_eq!(
+            sub.at::<u16>(usize::MAX).unwrap_err(),
+            VolatileError::OutOfBounds {
+                index: usize::MAX,
+                len: 8
+            }
+        );
+        assert_eq!(
+            sub.at::<u16>(1).unwrap_err(),
+            VolatileError::Misaligned {
+                required: 2,
+                found: 1
+            }
+        );
+
+        let words = region.read_only().slice::<u16>(6..10).unwrap();
+        assert_eq!(words.as_raw_ptr().len(), 2);
+        let read_only = region.read_only().read_only();
+        assert_eq!(read_only.at::<u16>(6).unwrap().read(), 7);
+    }
+
+    #[test]
+    fn test_read_write_at() {
+        let mut buf = [0_u32; 4];
+        let bytes = NonNull::slice_from_raw_parts(NonNull::from(&mut buf).cast::<u8>(), 16);
+        let volatile = unsafe { VolatilePtr::new(bytes) };
+
+        volatile.write_at::<u32>(4, 0xdead_beef).unwrap();
+        assert_eq!(volatile.read_at::<u32>(4), Ok(0xdead_beef));
+        assert_eq!(
+            volatile.write_at::<u64>(12, 0),
+            Err(VolatileError::OutOfBounds { index: 20, len: 16 })
+        );
+        assert_eq!(
+            volatile.read_at::<u32>(2),
+            Err(VolatileError::Misaligned {
+                required: 4,
+                found: 2
+            })
+        );
+        assert_eq!(buf, [0, 0xdead_beef, 0, 0]);
+    }
+}
@@ -0,0 +1,181 @@
+//! Const register descriptors.
+//!
+//! A [`RegisterAt`] describes a register by its type, its byte offset, and its access as
+//! generic parameters. Since it is zero-sized and can be created in const contexts, register
+//! maps can be declared as plain constants and shared across drivers without the derive
+//! macro.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{
+//!     access::{ReadOnly, WriteOnly},
+//!     register::RegisterAt,
+//!     VolatilePtr,
+//! };
+//!
+//! const UART_DATA: RegisterAt<u32, 0x00, WriteOnly> = RegisterAt::new();
+//! const UART_STATUS: RegisterAt<u32, 0x04, ReadOnly> = RegisterAt::new();
+//!
+//! let mut registers = [0_u32, 0x20];
+//! let base = unsafe { VolatilePtr::new((&mut registers).into()) };
+//!
+//! assert_eq!(UART_STATUS.resolve(base).read(), 0x20);
+//! UART_DATA.resolve(base).write(b'a'.into());
+//! assert_eq!(registers[0], 0x61);
+//! ```
+
+use core::{fmt, marker::PhantomData, mem};
+
+use crate::{
+    access::{Access, AccessKind, ReadWrite, RestrictAccess},
+    padding::NoPadding,
+    region::VolatileRegion,
+    VolatileError, VolatilePtr,
+};
+
+/// A register of type `T` at byte offset `OFFSET` with access `A`.
+///
+/// See the [module-level documentation](self) for details.
+pub struct RegisterAt<T, const OFFSET: usize, A = ReadWrite> {
+    ty: PhantomData<fn() -> T>,
+    access: PhantomData<A>,
+}
+
+impl<T, const OFFSET: usize, A> Copy for RegisterAt<T, OFFSET, A> {}
+
+impl<T, const OFFSET: usize, A> Clone for RegisterAt<T, OFFSET, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const OFFSET: usize, A> fmt::Debug for RegisterAt<T, OFFSET, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisterAt")
+            .field("offset", &OFFSET)
+            .field("size", &mem::size_of::<T>())
+            .finish()
+    }
+}
+
+impl<T, const OFFSET: usize, A> Default for RegisterAt<T, OFFSET, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const OFFSET: usize, A> RegisterAt<T, OFFSET, A> {
+    /// The byte offset of the register.
+    pub const OFFSET: usize = OFFSET;
+
+    /// Creates the register descriptor.
+    pub const fn new() -> Self {
+        Self {
+            ty: PhantomData,
+            access: PhantomData,
+        }
+    }
+
+    /// Returns a volatile pointer to this register relative to `base`.
+    ///
+    /// The access of the returned pointer is the intersection of the access of `base` and
+    /// `A`. It is a compile-time error if the register does not fit into `B` or is not
+    /// properly aligned. This method can be used in `const` initializers.
+    ///
+    /// ```compile_fail
+    /// use volatile::{register::RegisterAt, VolatilePtr};
+    ///
+    /// let mut registers = [0_u32; 2];
+    /// let base = unsafe { VolatilePtr::new((&mut registers).into()) };
+    ///
+    /// // error: the register does not fit into the base
+    /// RegisterAt::<u32, 0x08>::new().resolve(base);
+    /// ```
+    pub const fn resolve<'a, B, BA>(
+        self,
+        base: VolatilePtr<'a, B, BA>,
+    ) -> VolatilePtr<'a, T, BA::Restricted>
+    where
+        BA: RestrictAccess<A>,
+    {
+        const {
+            assert!(
+                OFFSET <= mem::size_of::<B>()
+                    && mem::size_of::<T>() <= mem::size_of::<B>() - OFFSET,
+                "the register does not fit into the base"
+            );
+            assert!(
+                mem::align_of::<T>() <= mem::align_of::<B>()
+                    && OFFSET & (mem::align_of::<T>() - 1) == 0,
+                "the register is not properly aligned"
+            );
+        };
+        // SAFETY: The register is within the base and properly aligned, as checked above.
+        unsafe { base.map_offset::<T>(OFFSET).restrict::<A>() }
+    }
+
+    /// Returns a volatile pointer to this register within `region`.
+    ///
+    /// The access of the returned pointer is the intersection of the access of `region` and
+    /// `A`.
+    pub fn resolve_in<'a, RA>(
+        self,
+        region: VolatileRegion<'a, RA>,
+    ) -> Result<VolatilePtr<'a, T, RA::Restricted>, VolatileError>
+    where
+        T: NoPadding,
+        RA: RestrictAccess<A>,
+    {
+        Ok(region.at::<T>(OFFSET)?.restrict::<A>())
+    }
+}
+
+impl<T, const OFFSET: usize, A> RegisterAt<T, OFFSET, A>
+where
+    A: Access,
+{
+    /// The access of the register.
+    pub const ACCESS: AccessKind = A::KIND;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        access::{AccessKind, ReadOnly, WriteOnly},
+        region::VolatileRegion,
+        register::RegisterAt,
+        VolatilePtr,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_register_at() {
+        const CONTROL: RegisterAt<u16, 2, WriteOnly> = RegisterAt::new();
+        const ID: RegisterAt<u32, 4, ReadOnly> = RegisterAt::new();
+
+        let mut val = [0_u32, 0x1234_5678];
+        let base = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        assert_eq!(ID.resolve(base).read(), 0x1234_5678);
+        assert_eq!(ID.resolve(base.read_only()).read(), 0x1234_5678);
+
+        let region = unsafe { VolatileRegion::new(NonNull::from(&mut val).cast(), 8) };
+        CONTROL.resolve_in(region).unwrap().write(0xffff);
+        assert!(ID.resolve_in(region.subregion(0..6).unwrap()).is_err());
+        assert_eq!(
+            val[0],
+            0xffff
+                << if cfg!(target_endian = "little") {
+                    16
+                } else {
+                    0
+                }
+        );
+
+        assert_eq!(RegisterAt::<u16, 2, WriteOnly>::OFFSET, 2);
+        assert_eq!(
+            RegisterAt::<u16, 2, WriteOnly>::ACCESS,
+            AccessKind::WriteOnly
+        );
+    }
+}
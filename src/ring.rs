@@ -0,0 +1,251 @@
+//! A single-producer single-consumer ring buffer in shared memory.
+//!
+//! [`VolatileRing`] stores its elements in a volatile slice and its indices in two `u32`
+//! words, so that it can be placed in memory that is shared between cores, VMs, or a CPU and a
+//! device. The producer only writes the head index and the consumer only writes the tail
+//! index. Both indices run freely and wrap around at `u32::MAX`, which is why the capacity
+//! must be a power of two.
+//!
+//! The indices are accessed atomically: each side publishes its index with a `Release` store
+//! and observes the other index with an `Acquire` load, which orders the volatile element
+//! accesses. Since the indices synchronize the two sides, a `VolatileRing` is [`Send`] and
+//! [`Sync`], so the producer and the consumer can run on different threads.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{ring::VolatileRing, VolatilePtr};
+//!
+//! let mut head = 0_u32;
+//! let mut tail = 0_u32;
+//! let mut storage = [0_u64; 4];
+//!
+//! let ring = unsafe {
+//!     VolatileRing::new(
+//!         VolatilePtr::new((&mut head).into()),
+//!         VolatilePtr::new((&mut tail).into()),
+//!         VolatilePtr::new(NonNull::from(&mut storage[..])),
+//!     )
+//! };
+//!
+//! ring.push(1).unwrap();
+//! ring.push(2).unwrap();
+//! assert_eq!(ring.len(), 2);
+//! assert_eq!(ring.pop(), Some(1));
+//! assert_eq!(ring.pop(), Some(2));
+//! assert_eq!(ring.pop(), None);
+//! ```
+
+use core::{
+    fmt,
+    ptr::NonNull,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::VolatilePtr;
+
+/// A single-producer single-consumer ring buffer over volatile memory.
+///
+/// See the [module-level documentation](self) for details.
+pub struct VolatileRing<'a, T> {
+    head: &'a AtomicU32,
+    tail: &'a AtomicU32,
+    storage: VolatilePtr<'a, [T]>,
+}
+
+// SAFETY: The contract of `VolatileRing::new` ensures that there is at most one producer and at
+// most one consumer, and the atomic indices order their element accesses.
+unsafe impl<T: Send> Send for VolatileRing<'_, T> {}
+
+// SAFETY: See above.
+unsafe impl<T: Send> Sync for VolatileRing<'_, T> {}
+
+impl<T> fmt::Debug for VolatileRing<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileRing")
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .field("storage", &self.storage)
+            .finish()
+    }
+}
+
+impl<'a, T> VolatileRing<'a, T>
+where
+    T: Copy,
+{
+    /// Creates a ring buffer from its head index, its tail index, and its storage.
+    ///
+    /// The indices are used as they are, so that both sides of a shared ring buffer can
+    /// create a `VolatileRing`. Initialize both indices to zero for an empty ring buffer.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the length of `storage` is not a power of two or exceeds `2^31`.
+    ///
+    /// ## Safety
+    ///
+    /// At most one context may push to and at most one context may pop from the ring buffer
+    /// at a time, even if the ring buffer is shared between threads. The indices and the
+    /// storage must only be written through ring buffers. The indices must be valid for atomic
+    /// accesses, i.e., be aligned for [`AtomicU32`] and located in memory that supports atomic
+    /// accesses.
+    pub unsafe fn new(
+        head: VolatilePtr<'a, u32>,
+        tail: VolatilePtr<'a, u32>,
+        storage: VolatilePtr<'a, [T]>,
+    ) -> Self {
+        let capacity = storage.as_raw_ptr().len();
+        assert!(
+            capacity.is_power_of_two() && capacity <= 1 << 31,
+            "capacity must be a power of two not exceeding 2^31"
+        );
+        // SAFETY: The caller guarantees that the indices are valid for atomic accesses for `'a`.
+        let (head, tail) = unsafe {
+            (
+                AtomicU32::from_ptr(head.as_raw_ptr().as_ptr()),
+                AtomicU32::from_ptr(tail.as_raw_ptr().as_ptr()),
+            )
+        };
+        Self {
+            head,
+            tail,
+            storage,
+        }
+    }
+
+    /// Returns the maximum number of elements in the ring buffer.
+    pub fn capacity(&self) -> usize {
+        self.storage.as_raw_ptr().len()
+    }
+
+    /// Returns the number of elements in the ring buffer.
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        head.wrapping_sub(tail) as usize
+    }
+
+    /// Returns whether the ring buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the ring buffer is full.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Appends `value` to the ring buffer.
+    ///
+    /// Returns `value` back if the ring buffer is full. Must only be called by the producer.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let head = self.head.load(Ordering::Relaxed);
+        // Synchronizes with the consumer, which must be done reading the slot before it is
+        // overwritten.
+        let tail = self.tail.load(Ordering::Acquire);
+        if head.wrapping_sub(tail) as usize == self.capacity() {
+            return Err(value);
+        }
+        self.slot(head).write(value);
+        // Publishes the element to the consumer.
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Removes the oldest element from the ring buffer.
+    ///
+    /// Returns `None` if the ring buffer is empty. Must only be called by the consumer.
+    pub fn pop(&self) -> Option<T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        // Synchronizes with the producer, which published the element.
+        let head = self.head.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let value = self.slot(tail).read();
+        // Releases the slot to the producer.
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn slot(&self, index: u32) -> VolatilePtr<'a, T> {
+        let index = index as usize & (self.capacity() - 1);
+        // SAFETY: The index is within the storage.
+        unsafe {
+            self.storage
+                .map(|storage| NonNull::new_unchecked(storage.as_ptr().cast::<T>().add(index)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ring::VolatileRing, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_ring_wrap_around() {
+        let mut head = u32::MAX - 1;
+        let mut tail = u32::MAX - 1;
+        let mut storage = [0_u8; 2];
+        let ring = unsafe {
+            VolatileRing::new(
+                VolatilePtr::new(NonNull::from(&mut head)),
+                VolatilePtr::new(NonNull::from(&mut tail)),
+                VolatilePtr::new(NonNull::from(&mut storage[..])),
+            )
+        };
+
+        for i in 0..5 {
+            ring.push(i).unwrap();
+            ring.push(i + 10).unwrap();
+            assert!(ring.is_full());
+            assert_eq!(ring.push(0), Err(0));
+            assert_eq!(ring.pop(), Some(i));
+            assert_eq!(ring.pop(), Some(i + 10));
+            assert!(ring.is_empty());
+        }
+        assert_eq!(head, 8);
+        assert_eq!(tail, 8);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ring_threads() {
+        const COUNT: u32 = if cfg!(miri) { 100 } else { 10_000 };
+
+        let mut head = 0_u32;
+        let mut tail = 0_u32;
+        let mut storage = [0_u32; 4];
+        let ring = unsafe {
+            VolatileRing::new(
+                VolatilePtr::new(NonNull::from(&mut head)),
+                VolatilePtr::new(NonNull::from(&mut tail)),
+                VolatilePtr::new(NonNull::from(&mut storage[..])),
+            )
+        };
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 0..COUNT {
+                    while ring.push(i).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+            for i in 0..COUNT {
+                let value = loop {
+                    match ring.pop() {
+                        Some(value) => break value,
+                        None => std::thread::yield_now(),
+                    }
+                };
+                assert_eq!(value, i);
+            }
+        });
+        assert!(ring.is_empty());
+        assert_eq!(head, COUNT);
+    }
+}
@@ -0,0 +1,129 @@
+//! Copies between contiguous buffers and byte ranges that are split across several volatile
+//! slices.
+//!
+//! Some devices place a logical buffer into several mapped windows or into chunks that are
+//! referenced by descriptors. [`gather`] and [`scatter`] copy such a buffer, described by an
+//! iterator of `(slice, len)` segments, from and to a contiguous buffer in memory. Each
+//! segment covers the first `len` bytes of its slice. The segments are copied in order with
+//! the bulk copies of [`VolatilePtr`].
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{scatter, VolatileError, VolatilePtr};
+//!
+//! let mut first = [0_u8; 4];
+//! let mut second = [0_u8; 8];
+//! let segments = unsafe {
+//!     [
+//!         (VolatilePtr::new(NonNull::from(&mut first[..])), 3),
+//!         (VolatilePtr::new(NonNull::from(&mut second[..])), 8),
+//!     ]
+//! };
+//!
+//! assert_eq!(scatter::scatter(segments, b"hello")?, 5);
+//!
+//! let mut buf = [0; 16];
+//! assert_eq!(scatter::gather(segments, &mut buf)?, 11);
+//! assert_eq!(buf[..5], *b"hello");
+//! # Ok::<(), VolatileError>(())
+//! ```
+
+use crate::{
+    access::{Access, Readable, Writable},
+    region::VolatileRegion,
+    VolatileError, VolatilePtr,
+};
+
+/// Copies the bytes of `segments` into `dst` and returns the number of copied bytes.
+///
+/// The copy stops when `dst` is full or when all segments are copied, whichever comes first.
+/// Returns [`VolatileError::OutOfBounds`] if the length of a segment exceeds its slice.
+/// Segments before the failing segment have been copied in this case.
+pub fn gather<'a, A, I>(segments: I, dst: &mut [u8]) -> Result<usize, VolatileError>
+where
+    A: Access + Readable,
+    I: IntoIterator<Item = (VolatilePtr<'a, [u8], A>, usize)>,
+{
+    let mut copied = 0;
+    for (slice, len) in segments {
+        let remaining = dst.len() - copied;
+        if remaining == 0 {
+            break;
+        }
+        let segment = VolatileRegion::from_ptr(slice).slice::<u8>(0..len)?;
+        let len = usize::min(len, remaining);
+        segment_prefix(segment, len).copy_into_slice(&mut dst[copied..copied + len]);
+        copied += len;
+    }
+    Ok(copied)
+}
+
+/// Copies `src` into the bytes of `segments` and returns the number of copied bytes.
+///
+/// The copy stops when `src` is exhausted or when all segments are filled, whichever comes
+/// first. Returns [`VolatileError::OutOfBounds`] if the length of a segment exceeds its slice.
+/// Segments before the failing segment have been written in this case.
+pub fn scatter<'a, A, I>(segments: I, src: &[u8]) -> Result<usize, VolatileError>
+where
+    A: Access + Writable,
+    I: IntoIterator<Item = (VolatilePtr<'a, [u8], A>, usize)>,
+{
+    let mut copied = 0;
+    for (slice, len) in segments {
+        let remaining = src.len() - copied;
+        if remaining == 0 {
+            break;
+        }
+        let segment = VolatileRegion::from_ptr(slice).slice::<u8>(0..len)?;
+        let len = usize::min(len, remaining);
+        segment_prefix(segment, len).copy_from_slice(&src[copied..copied + len]);
+        copied += len;
+    }
+    Ok(copied)
+}
+
+/// Returns the first `len` bytes of `segment`, which must not be longer than `segment`.
+fn segment_prefix<'a, A>(segment: VolatilePtr<'a, [u8], A>, len: usize) -> VolatilePtr<'a, [u8], A>
+where
+    A: Access,
+{
+    VolatileRegion::from_ptr(segment)
+        .slice::<u8>(0..len)
+        .expect("prefix is within the segment")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{scatter, VolatileError, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_scatter_gather() {
+        let mut windows = [[0_u8; 4]; 3];
+        let [first, second, third] = &mut windows;
+        let segments = unsafe {
+            [
+                (VolatilePtr::new(NonNull::from(&mut first[..])), 4),
+                (VolatilePtr::new(NonNull::from(&mut second[..])), 2),
+                (VolatilePtr::new(NonNull::from(&mut third[..])), 4),
+            ]
+        };
+
+        assert_eq!(scatter::scatter(segments, &[1, 2, 3, 4, 5, 6, 7]), Ok(7));
+        let mut buf = [0; 12];
+        assert_eq!(scatter::gather(segments, &mut buf), Ok(10));
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0, 0]);
+        let mut short = [0; 5];
+        assert_eq!(scatter::gather(segments, &mut short), Ok(5));
+        assert_eq!(short, [1, 2, 3, 4, 5]);
+
+        let invalid = [segments[0], (segments[1].0, 5)];
+        assert_eq!(
+            scatter::gather(invalid, &mut buf),
+            Err(VolatileError::OutOfBounds { index: 5, len: 4 })
+        );
+        assert_eq!(windows, [[1, 2, 3, 4], [5, 6, 0, 0], [7, 0, 0, 0]]);
+    }
+}
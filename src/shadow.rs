@@ -0,0 +1,131 @@
+//! Software copies of write-only registers.
+//!
+//! Many interrupt-mask and control registers are write-only in hardware, so their current
+//! value cannot be read back to modify individual bits. A [`Shadowed`] register keeps a copy of
+//! the last written value next to the write-only pointer. [`Shadowed::update`] modifies this
+//! copy and writes it to the register.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{shadow::Shadowed, VolatilePtr};
+//!
+//! let mut interrupt_mask = 0_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut interrupt_mask).into()) };
+//!
+//! // The register is all zeros after reset.
+//! let mut mask = Shadowed::new(volatile.write_only(), 0);
+//! mask.update(|mask| mask | 1 << 3);
+//! mask.update(|mask| mask | 1 << 5);
+//! assert_eq!(mask.get(), 0x28);
+//! assert_eq!(interrupt_mask, 0x28);
+//! ```
+
+use core::fmt;
+
+use crate::{
+    access::{Writable, WriteOnly},
+    VolatilePtr,
+};
+
+/// A write-only register with a software copy of its last written value.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Shadowed<'a, T> {
+    pointer: VolatilePtr<'a, T, WriteOnly>,
+    shadow: T,
+}
+
+impl<T> fmt::Debug for Shadowed<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shadowed")
+            .field("pointer", &self.pointer)
+            .field("shadow", &self.shadow)
+            .finish()
+    }
+}
+
+impl<'a, T> Shadowed<'a, T>
+where
+    T: Copy,
+{
+    /// Creates a shadowed register whose current value is `value`, without writing it.
+    ///
+    /// `value` is usually the reset value of the register. All writes to the register have to
+    /// go through this `Shadowed` afterwards, since the copy is outdated otherwise.
+    pub fn new<A>(pointer: VolatilePtr<'a, T, A>, value: T) -> Self
+    where
+        A: Writable,
+    {
+        Self {
+            // SAFETY: The pointer was valid for writes before.
+            pointer: unsafe { VolatilePtr::new_restricted(WriteOnly, pointer.as_raw_ptr()) },
+            shadow: value,
+        }
+    }
+
+    /// Creates a shadowed register and initializes it by writing `value`.
+    pub fn with_value<A>(pointer: VolatilePtr<'a, T, A>, value: T) -> Self
+    where
+        A: Writable,
+    {
+        let mut shadowed = Self::new(pointer, value);
+        shadowed.write(value);
+        shadowed
+    }
+
+    /// Returns the last written value.
+    pub fn get(&self) -> T {
+        self.shadow
+    }
+
+    /// Writes `value` to the register and the copy.
+    pub fn write(&mut self, value: T) {
+        self.pointer.write(value);
+        self.shadow = value;
+    }
+
+    /// Passes the last written value to `f` and writes the returned value.
+    pub fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.write(f(self.shadow));
+    }
+
+    /// Writes the last written value to the register again.
+    ///
+    /// This is useful after the device was reset, e.g., when resuming from a low-power state.
+    pub fn restore(&self) {
+        self.pointer.write(self.shadow);
+    }
+
+    /// Returns the underlying write-only pointer.
+    pub fn as_ptr(&self) -> VolatilePtr<'a, T, WriteOnly> {
+        self.pointer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shadow::Shadowed, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_shadowed() {
+        let mut register = 0xff_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+        let mut shadowed = Shadowed::with_value(volatile, 0x0f);
+        assert_eq!(volatile.read(), 0x0f);
+
+        shadowed.update(|value| value & !1);
+        assert_eq!((shadowed.get(), volatile.read()), (0x0e, 0x0e));
+
+        volatile.write(0);
+        shadowed.restore();
+        assert_eq!(volatile.read(), 0x0e);
+    }
+}
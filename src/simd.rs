@@ -0,0 +1,241 @@
+//! SIMD-accelerated volatile slice copies.
+//!
+//! With the `simd` feature, [`VolatilePtr::copy_from_slice`](crate::VolatilePtr::copy_from_slice),
+//! [`VolatilePtr::copy_into_slice`](crate::VolatilePtr::copy_into_slice), and
+//! [`VolatilePtr::copy_within`](crate::VolatilePtr::copy_within) copy large slices with
+//! 128-bit or 256-bit vector loads and stores instead of word-wide accesses. This
+//! substantially increases the throughput for framebuffers and NIC buffers. The vector
+//! accesses are performed using inline assembly, so the compiler never splits, merges, or
+//! elides them.
+//!
+//! On aarch64, pairs of 128-bit NEON registers are used. On x86_64, 128-bit SSE2 accesses are
+//! used by default, since SSE2 is part of the baseline. 256-bit AVX accesses are used once AVX
+//! support is known: with the `std` feature, it is detected at runtime. Otherwise, it is only
+//! assumed if AVX is enabled at compile time, so kernels that enable AVX themselves should call
+//! [`set_avx_available`] afterwards. On other architectures and under Miri, this feature has no
+//! effect.
+//!
+//! The vector accesses are only used if both slices have the same alignment modulo 16 bytes and
+//! at least [`THRESHOLD`] bytes are copied.
+
+use core::mem;
+
+/// The minimum number of bytes that are copied with vector accesses.
+pub const THRESHOLD: usize = 256;
+
+const ALIGN: usize = 16;
+
+const WORD: usize = mem::size_of::<usize>();
+
+/// Sets whether 256-bit AVX accesses may be used for copies.
+///
+/// This overrides the runtime detection, e.g., after the kernel enabled AVX in `XCR0`.
+#[cfg(target_arch = "x86_64")]
+pub fn set_avx_available(available: bool) {
+    use core::sync::atomic::Ordering;
+
+    let state = if available {
+        avx::AVAILABLE
+    } else {
+        avx::UNAVAILABLE
+    };
+    avx::STATE.store(state, Ordering::Relaxed);
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    pub(super) const UNKNOWN: u8 = 0;
+    pub(super) const UNAVAILABLE: u8 = 1;
+    pub(super) const AVAILABLE: u8 = 2;
+
+    pub(super) static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    /// Returns whether AVX may be used, detecting it on first use.
+    #[cfg_attr(any(miri, not(target_feature = "sse2")), allow(dead_code))]
+    pub(super) fn available() -> bool {
+        match STATE.load(Ordering::Relaxed) {
+            AVAILABLE => true,
+            UNAVAILABLE => false,
+            _ => {
+                let available = detect();
+                let state = if available { AVAILABLE } else { UNAVAILABLE };
+                // Do not overwrite a state that was set in the meantime.
+                let _ =
+                    STATE.compare_exchange(UNKNOWN, state, Ordering::Relaxed, Ordering::Relaxed);
+                STATE.load(Ordering::Relaxed) == AVAILABLE
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn detect() -> bool {
+        std::is_x86_feature_detected!("avx")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn detect() -> bool {
+        cfg!(target_feature = "avx")
+    }
+}
+
+/// Copies the longest prefix of the `len` bytes at `src` to `dst` that can be copied with
+/// vector accesses and returns its length.
+///
+/// Both pointers must be word-aligned.
+pub(crate) unsafe fn copy_forward(dst: *mut u8, src: *const u8, len: usize) -> usize {
+    if !imp::ENABLED || len < THRESHOLD || (dst.addr() ^ src.addr()) & (ALIGN - 1) != 0 {
+        return 0;
+    }
+    let head = dst.align_offset(ALIGN);
+    let mut i = 0;
+    while i < head {
+        unsafe {
+            let word = src.add(i).cast::<usize>().read_volatile();
+            dst.add(i).cast::<usize>().write_volatile(word);
+        }
+        i += WORD;
+    }
+    i + unsafe { imp::copy(dst.add(i), src.add(i), len - i) }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", not(miri)))]
+mod imp {
+    use core::arch::asm;
+
+    pub(super) const ENABLED: bool = true;
+
+    /// Copies whole 16-byte chunks from `src` to `dst`, which are 16-byte aligned.
+    pub(super) unsafe fn copy(dst: *mut u8, src: *const u8, len: usize) -> usize {
+        let mut i = 0;
+        if super::avx::available() {
+            // SAFETY: AVX is available.
+            i = unsafe { copy_avx(dst, src, len) };
+        }
+        while len - i >= 16 {
+            unsafe {
+                asm!(
+                    "movdqa {tmp}, xmmword ptr [{src}]",
+                    "movdqa xmmword ptr [{dst}], {tmp}",
+                    src = in(reg) src.add(i),
+                    dst = in(reg) dst.add(i),
+                    tmp = out(xmm_reg) _,
+                    options(nostack, preserves_flags),
+                );
+            }
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn copy_avx(dst: *mut u8, src: *const u8, len: usize) -> usize {
+        let mut i = 0;
+        while len - i >= 32 {
+            unsafe {
+                asm!(
+                    "vmovdqu {tmp}, ymmword ptr [{src}]",
+                    "vmovdqu ymmword ptr [{dst}], {tmp}",
+                    src = in(reg) src.add(i),
+                    dst = in(reg) dst.add(i),
+                    tmp = out(ymm_reg) _,
+                    options(nostack, preserves_flags),
+                );
+            }
+            i += 32;
+        }
+        i
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon", not(miri)))]
+mod imp {
+    use core::arch::asm;
+
+    pub(super) const ENABLED: bool = true;
+
+    /// Copies whole 16-byte chunks from `src` to `dst`, which are 16-byte aligned.
+    pub(super) unsafe fn copy(dst: *mut u8, src: *const u8, len: usize) -> usize {
+        let mut i = 0;
+        while len - i >= 32 {
+            unsafe {
+                asm!(
+                    "ldp {a:q}, {b:q}, [{src}]",
+                    "stp {a:q}, {b:q}, [{dst}]",
+                    src = in(reg) src.add(i),
+                    dst = in(reg) dst.add(i),
+                    a = out(vreg) _,
+                    b = out(vreg) _,
+                    options(nostack, preserves_flags),
+                );
+            }
+            i += 32;
+        }
+        if len - i >= 16 {
+            unsafe {
+                asm!(
+                    "ldr {a:q}, [{src}]",
+                    "str {a:q}, [{dst}]",
+                    src = in(reg) src.add(i),
+                    dst = in(reg) dst.add(i),
+                    a = out(vreg) _,
+                    options(nostack, preserves_flags),
+                );
+            }
+            i += 16;
+        }
+        i
+    }
+}
+
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_feature = "sse2", not(miri)),
+    all(target_arch = "aarch64", target_feature = "neon", not(miri)),
+)))]
+mod imp {
+    pub(super) const ENABLED: bool = false;
+
+    pub(super) unsafe fn copy(_dst: *mut u8, _src: *const u8, _len: usize) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_copy_simd() {
+        #[repr(align(32))]
+        struct Aligned([u8; 1100]);
+
+        let mut src = Aligned([0; 1100]);
+        for (i, byte) in src.0.iter_mut().enumerate() {
+            *byte = (i * 7) as u8;
+        }
+
+        for avx in [false, true] {
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            crate::simd::set_avx_available(avx && std::is_x86_feature_detected!("avx"));
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            crate::simd::set_avx_available(avx && cfg!(target_feature = "avx"));
+            for (src_offset, dst_offset, len) in
+                [(0, 0, 1024), (8, 8, 1000), (3, 19, 777), (5, 6, 300)]
+            {
+                let mut dst = Aligned([0; 1100]);
+                let volatile = unsafe {
+                    VolatilePtr::new(NonNull::from(&mut dst.0[dst_offset..dst_offset + len]))
+                };
+                volatile.copy_from_slice(&src.0[src_offset..src_offset + len]);
+                assert_eq!(
+                    dst.0[dst_offset..dst_offset + len],
+                    src.0[src_offset..src_offset + len]
+                );
+                assert!(dst.0[..dst_offset].iter().all(|&byte| byte == 0));
+                assert!(dst.0[dst_offset + len..].iter().all(|&byte| byte == 0));
+            }
+        }
+    }
+}
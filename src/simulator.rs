@@ -0,0 +1,311 @@
+//! Deterministic device simulation for host-side testing.
+//!
+//! A [`SimulatedDevice`] responds to individual register accesses, which allows testing,
+//! fuzzing, and property-testing driver state machines on the host. A [`SimulatedRegion`]
+//! backs a [`VolatileRegion`] with such a device: all volatile reads and writes through the
+//! region are routed to the device using the [`backend`](crate::backend) mechanism. This
+//! includes bulk operations on slices, which the device observes as element-wide accesses.
+//!
+//! This module is only available with the `test-util` feature.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{
+//!     backend::Direction,
+//!     simulator::{DeviceAccess, SimulatedRegion},
+//! };
+//!
+//! // A device whose status register at offset 4 becomes ready after a write to offset 0.
+//! let mut ready = false;
+//! let region = SimulatedRegion::new(
+//!     move |access: DeviceAccess| match (access.direction, access.offset) {
+//!         (Direction::Write, 0) => {
+//!             ready = true;
+//!             0
+//!         }
+//!         (Direction::Read, 4) => u64::from(ready),
+//!         _ => 0,
+//!     },
+//!     8,
+//! );
+//!
+//! let status = unsafe {
+//!     region.run(|region| {
+//!         let status = region.at::<u32>(4).unwrap();
+//!         assert_eq!(status.read(), 0);
+//!         region.at::<u32>(0).unwrap().write(1);
+//!         status.read()
+//!     })
+//! };
+//! assert_eq!(status, 1);
+//! ```
+
+use core::{
+    cell::{RefCell, UnsafeCell},
+    fmt,
+    ptr::NonNull,
+};
+use std::boxed::Box;
+
+use crate::{
+    backend::{with_backend, Direction, VolatileBackend},
+    region::VolatileRegion,
+};
+
+/// A single access to a [`SimulatedDevice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceAccess {
+    /// The byte offset of the access within the region.
+    pub offset: usize,
+    /// The width of the access in bytes, which is 1, 2, 4, or 8.
+    pub width: usize,
+    /// Whether this access is a read or a write.
+    pub direction: Direction,
+    /// The written value, or zero for reads.
+    pub value: u64,
+}
+
+/// A simulated device that responds to register accesses.
+///
+/// This trait is implemented for all closures that take a [`DeviceAccess`] and return a
+/// `u64`.
+pub trait SimulatedDevice {
+    /// Handles `access` and returns the value that is read.
+    ///
+    /// The return value is ignored for writes.
+    fn access(&mut self, access: DeviceAccess) -> u64;
+}
+
+impl<F> SimulatedDevice for F
+where
+    F: FnMut(DeviceAccess) -> u64,
+{
+    fn access(&mut self, access: DeviceAccess) -> u64 {
+        self(access)
+    }
+}
+
+/// A memory region whose accesses are handled by a [`SimulatedDevice`].
+///
+/// Accesses that are wider than 8 bytes or whose size is not a power of two are split into
+/// multiple device accesses of the largest width that divides the size.
+pub struct SimulatedRegion<D> {
+    memory: Box<[UnsafeCell<u64>]>,
+    len: usize,
+    device: RefCell<D>,
+}
+
+impl<D> fmt::Debug for SimulatedRegion<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulatedRegion")
+            .field("base", &self.memory.as_ptr())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<D> SimulatedRegion<D>
+where
+    D: SimulatedDevice,
+{
+    /// Creates a region of `len` bytes that is backed by `device`.
+    pub fn new(device: D, len: usize) -> Self {
+        Self {
+            memory: (0..len.div_ceil(8)).map(|_| UnsafeCell::new(0)).collect(),
+            len,
+            device: RefCell::new(device),
+        }
+    }
+
+    /// Calls `f` with a [`VolatileRegion`] whose accesses are handled by the device.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`with_backend`] apply to this function too.
+    pub unsafe fn run<R>(&self, f: impl FnOnce(VolatileRegion<'_>) -> R) -> R {
+        let base = NonNull::from(&*self.memory).cast::<u8>();
+        // SAFETY: The memory is valid for `len` bytes and may be written through the shared
+        // reference since it is wrapped in `UnsafeCell`. Accesses within the region are handled
+        // by the backend.
+        let region = unsafe { VolatileRegion::new(base, self.len) };
+        unsafe { with_backend(self, || f(region)) }
+    }
+
+    /// Returns a mutable reference to the device.
+    pub fn device_mut(&mut self) -> &mut D {
+        self.device.get_mut()
+    }
+
+    /// Consumes the region and returns the device.
+    pub fn into_device(self) -> D {
+        self.device.into_inner()
+    }
+
+    /// Returns the offset of `ptr` within the region if the `len` bytes at `ptr` are within
+    /// the region.
+    fn offset(&self, ptr: *const u8, len: usize) -> Option<usize> {
+        let offset = (ptr as usize).checked_sub(self.memory.as_ptr() as usize)?;
+        (offset.checked_add(len)? <= self.len).then_some(offset)
+    }
+
+    fn access(&self, offset: usize, direction: Direction, buf: &mut [u8]) {
+        let width = [8, 4, 2, 1]
+            .into_iter()
+            .find(|width| buf.len() & (width - 1) == 0)
+            .unwrap();
+        let mut device = self.device.borrow_mut();
+        for (i, chunk) in buf.chunks_exact_mut(width).enumerate() {
+            let value = match direction {
+                Direction::Read => 0,
+                Direction::Write => to_value(chunk),
+            };
+            let value = device.access(DeviceAccess {
+                offset: offset + i * width,
+                width,
+                direction,
+                value,
+            });
+            if direction == Direction::Read {
+                from_value(value, chunk);
+            }
+        }
+    }
+}
+
+impl<D> VolatileBackend for SimulatedRegion<D>
+where
+    D: SimulatedDevice,
+{
+    unsafe fn read(&self, src: *const u8, buf: &mut [u8]) -> bool {
+        let Some(offset) = self.offset(src, buf.len()) else {
+            return false;
+        };
+        self.access(offset, Direction::Read, buf);
+        true
+    }
+
+    unsafe fn write(&self, dst: *mut u8, buf: &[u8]) -> bool {
+        let Some(offset) = self.offset(dst, buf.len()) else {
+            return false;
+        };
+        self.access(offset, Direction::Write, &mut buf.to_vec());
+        true
+    }
+}
+
+/// Converts `bytes` in native byte order to a value.
+fn to_value(bytes: &[u8]) -> u64 {
+    match bytes.len() {
+        1 => bytes[0].into(),
+        2 => u16::from_ne_bytes(bytes.try_into().unwrap()).into(),
+        4 => u32::from_ne_bytes(bytes.try_into().unwrap()).into(),
+        8 => u64::from_ne_bytes(bytes.try_into().unwrap()),
+        _ => unreachable!(),
+    }
+}
+
+/// Converts `value` to `bytes` in native byte order, truncating it to their length.
+fn from_value(value: u64, bytes: &mut [u8]) {
+    match bytes.len() {
+        1 => bytes.copy_from_slice(&(value as u8).to_ne_bytes()),
+        2 => bytes.copy_from_slice(&(value as u16).to_ne_bytes()),
+        4 => bytes.copy_from_slice(&(value as u32).to_ne_bytes()),
+        8 => bytes.copy_from_slice(&value.to_ne_bytes()),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::Direction,
+        simulator::{DeviceAccess, SimulatedRegion},
+    };
+    use std::vec::Vec;
+
+    #[test]
+    fn test_simulated_region() {
+        let mut accesses = Vec::new();
+        let region = SimulatedRegion::new(
+            |access: DeviceAccess| {
+                accesses.push(access);
+                access.offset as u64
+            },
+            16,
+        );
+        let value = unsafe {
+            region.run(|region| {
+                region.at::<u64>(8).unwrap().write(0x1234);
+                region.at::<[u64; 2]>(0).unwrap().read()
+            })
+        };
+        assert_eq!(value, [0, 8]);
+        drop(region);
+
+        assert_eq!(
+            accesses,
+            [
+                DeviceAccess {
+                    offset: 8,
+                    width: 8,
+                    direction: Direction::Write,
+                    value: 0x1234
+                },
+                DeviceAccess {
+                    offset: 0,
+                    width: 8,
+                    direction: Direction::Read,
+                    value: 0
+                },
+                DeviceAccess {
+                    offset: 8,
+                    width: 8,
+                    direction: Direction::Read,
+                    value: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simulated_region_bulk() {
+        let mut accesses = Vec::new();
+        let region = SimulatedRegion::new(
+            |access: DeviceAccess| {
+                accesses.push(access);
+                0xaa
+            },
+            16,
+        );
+        let mut buf = [0; 2];
+        unsafe {
+            region.run(|region| {
+                let slice = region.slice::<u8>(4..6).unwrap();
+                slice.copy_from_slice(&[1, 2]);
+                slice.fill_bytes(3);
+                slice.copy_into_slice(&mut buf);
+            })
+        };
+        assert_eq!(buf, [0xaa; 2]);
+        drop(region);
+
+        let access = |offset, direction, value| DeviceAccess {
+            offset,
+            width: 1,
+            direction,
+            value,
+        };
+        assert_eq!(
+            accesses,
+            [
+                access(4, Direction::Write, 1),
+                access(5, Direction::Write, 2),
+                access(4, Direction::Write, 3),
+                access(5, Direction::Write, 3),
+                access(4, Direction::Read, 0),
+                access(5, Direction::Read, 0),
+            ]
+        );
+    }
+}
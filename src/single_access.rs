@@ -0,0 +1,153 @@
+//! Accesses that are guaranteed to be a single bus transaction.
+//!
+//! [`VolatilePtr::read`] and [`VolatilePtr::write`] accept values of any size, but a value that
+//! is wider than the native word is read or written with several accesses, which can tear
+//! if the device changes the value in between. The [`SingleAccess`] marker trait is only
+//! implemented for types that the target accesses with a single instruction, and
+//! [`VolatilePtr::read_single`] and [`VolatilePtr::write_single`] only accept such types.
+//!
+//! Larger values can be accessed explicitly through [`VolatilePtr::read_multiword`] and
+//! [`VolatilePtr::write_multiword`]. In particular, `u128` is never a single access, not even on
+//! 64-bit targets. [`VolatilePtr::read_as_chunks`] and [`VolatilePtr::write_as_chunks`] split such
+//! integers into accesses of a given width in a documented order.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::VolatilePtr;
+//!
+//! let mut status = 0_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut status).into()) };
+//! volatile.write_single(1);
+//! assert_eq!(volatile.read_single(), 1);
+//! ```
+//!
+//! Types that are wider than a single access are rejected at compile time:
+//!
+//! ```compile_fail
+//! use volatile::VolatilePtr;
+//!
+//! let mut descriptor = [0_u64; 8];
+//! let volatile = unsafe { VolatilePtr::new((&mut descriptor).into()) };
+//! volatile.read_single();
+//! ```
+
+use crate::{
+    access::{Readable, Writable},
+    endian::{Be, Le},
+    VolatilePtr,
+};
+
+/// Types that are read and written with a single access.
+///
+/// This is implemented for the primitive types up to the width of a pointer.
+///
+/// ## Safety
+///
+/// Volatile reads and writes of the type must compile to a single access of its full size on
+/// all targets for which the implementation exists.
+pub unsafe trait SingleAccess: Copy {}
+
+macro_rules! impl_single_access {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl SingleAccess for $ty {}
+        )*
+    };
+}
+
+impl_single_access!(u8, u16, u32, usize, i8, i16, i32, isize, f32, bool);
+
+#[cfg(target_pointer_width = "64")]
+impl_single_access!(u64, i64, f64);
+
+unsafe impl<T> SingleAccess for *const T {}
+
+unsafe impl<T> SingleAccess for *mut T {}
+
+unsafe impl<T> SingleAccess for Le<T> where T: SingleAccess {}
+
+unsafe impl<T> SingleAccess for Be<T> where T: SingleAccess {}
+
+/// Methods for values that are accessed with a single access.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: SingleAccess,
+{
+    /// Performs a volatile read of the value with a single access.
+    ///
+    /// This is the same as [`read`](Self::read), but only accepts types that cannot tear.
+    #[inline(always)]
+    #[track_caller]
+    pub fn read_single(self) -> T
+    where
+        A: Readable,
+    {
+        self.read()
+    }
+
+    /// Performs a volatile write of the value with a single access.
+    ///
+    /// This is the same as [`write`](Self::write), but only accepts types that cannot tear.
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_single(self, value: T)
+    where
+        A: Writable,
+    {
+        self.write(value);
+    }
+}
+
+/// Methods for values that may need several accesses.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: Copy,
+{
+    /// Performs a volatile read of a value that may be wider than a single access.
+    ///
+    /// The compiler may split the read into several accesses of unspecified width and order,
+    /// so the result can mix bytes from before and after a concurrent change. Use
+    /// [`read_single`](Self::read_single) for values that must not tear.
+    #[inline(always)]
+    #[track_caller]
+    pub fn read_multiword(self) -> T
+    where
+        A: Readable,
+    {
+        self.read()
+    }
+
+    /// Performs a volatile write of a value that may be wider than a single access.
+    ///
+    /// The compiler may split the write into several accesses of unspecified width and order,
+    /// so the device can observe a partially written value. Use
+    /// [`write_single`](Self::write_single) for values that must not tear.
+    #[inline(always)]
+    #[track_caller]
+    pub fn write_multiword(self, value: T)
+    where
+        A: Writable,
+    {
+        self.write(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_single_access() {
+        let mut val: u32 = 1;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        volatile.write_single(2);
+        assert_eq!(volatile.read_single(), 2);
+
+        let mut block = [0_u64; 8];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut block)) };
+        volatile.write_multiword([1; 8]);
+        assert_eq!(volatile.read_multiword(), [1; 8]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-region access statistics.
+//!
+//! MMIO accesses can take hundreds of cycles each, so drivers that poll registers more often
+//! than necessary are slow. With the `stats` feature, an [`AccessStats`] counts the reads,
+//! writes, and accessed bytes of [`VolatilePtr::read`](crate::VolatilePtr::read),
+//! [`VolatilePtr::write`](crate::VolatilePtr::write), and all other volatile accesses that
+//! start within a tracked address range. Bulk operations on slices, such as
+//! [`copy_from_slice`](crate::VolatilePtr::copy_from_slice), count as a single access of their
+//! whole length. All tracked statistics can be enumerated at runtime using [`for_each`].
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::{region::VolatileRegion, stats::AccessStats};
+//!
+//! static UART_STATS: AccessStats = AccessStats::new("uart");
+//!
+//! let mut registers = [0_u32; 4];
+//! let uart = unsafe { VolatileRegion::new(NonNull::from(&mut registers).cast(), 16) };
+//! UART_STATS.track(uart);
+//!
+//! let status = uart.at::<u32>(4).unwrap();
+//! while status.read() != 0 {}
+//! uart.at::<u32>(0).unwrap().write(u32::from(b'a'));
+//!
+//! let counts = UART_STATS.counts();
+//! assert_eq!((counts.reads, counts.writes), (1, 1));
+//! assert_eq!(counts.bytes_written, 4);
+//! ```
+
+use core::{
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{access::Access, backend::Direction, region::VolatileRegion, tracked::TrackedRange};
+
+/// A snapshot of the counters of an [`AccessStats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Counts {
+    /// The number of reads.
+    pub reads: usize,
+    /// The number of writes.
+    pub writes: usize,
+    /// The number of bytes read.
+    pub bytes_read: usize,
+    /// The number of bytes written.
+    pub bytes_written: usize,
+}
+
+/// Access counters for a named address range.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Debug)]
+pub struct AccessStats {
+    name: &'static str,
+    range: TrackedRange,
+    reads: AtomicUsize,
+    writes: AtomicUsize,
+    bytes_read: AtomicUsize,
+    bytes_written: AtomicUsize,
+    registered: AtomicBool,
+    next: AtomicPtr<AccessStats>,
+}
+
+static HEAD: AtomicPtr<AccessStats> = AtomicPtr::new(ptr::null_mut());
+
+impl AccessStats {
+    /// Creates statistics with the given name that do not track any range yet.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            range: TrackedRange::new(0),
+            reads: AtomicUsize::new(0),
+            writes: AtomicUsize::new(0),
+            bytes_read: AtomicUsize::new(0),
+            bytes_written: AtomicUsize::new(0),
+            registered: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Returns the name of these statistics.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Counts all subsequent accesses within `region`, replacing the previously tracked range.
+    pub fn track<A>(&'static self, region: VolatileRegion<'_, A>)
+    where
+        A: Access,
+    {
+        self.track_range(region.as_raw_ptr().as_ptr(), region.len());
+    }
+
+    /// Counts all subsequent accesses within the `len` bytes at `start`, replacing the
+    /// previously tracked range.
+    ///
+    /// The range is replaced atomically, so concurrent accesses are either counted against the
+    /// previous or the new range, but never against a mix of both.
+    pub fn track_range(&'static self, start: *const u8, len: usize) {
+        self.range.store(start.addr(), len, 0);
+        if self.registered.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let this = ptr::from_ref(self).cast_mut();
+        let mut head = HEAD.load(Ordering::Acquire);
+        loop {
+            self.next.store(head, Ordering::Relaxed);
+            match HEAD.compare_exchange_weak(head, this, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Returns the current values of the counters.
+    pub fn counts(&self) -> Counts {
+        Counts {
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets all counters to zero.
+    pub fn reset(&self) {
+        self.reads.store(0, Ordering::Relaxed);
+        self.writes.store(0, Ordering::Relaxed);
+        self.bytes_read.store(0, Ordering::Relaxed);
+        self.bytes_written.store(0, Ordering::Relaxed);
+    }
+
+    fn contains(&self, addr: usize) -> bool {
+        let (start, len, _) = self.range.load();
+        addr.wrapping_sub(start) < len
+    }
+}
+
+/// Calls `f` for all statistics that track a range.
+pub fn for_each(mut f: impl FnMut(&'static AccessStats)) {
+    let mut current = HEAD.load(Ordering::Acquire);
+    // SAFETY: Only `'static` statistics are added to the list.
+    while let Some(stats) = unsafe { current.as_ref() } {
+        f(stats);
+        current = stats.next.load(Ordering::Relaxed);
+    }
+}
+
+/// Counts an access of `size` bytes at `ptr` for all statistics that track it.
+pub(crate) fn record(ptr: *const u8, size: usize, direction: Direction) {
+    let addr = ptr.addr();
+    for_each(|stats| {
+        if !stats.contains(addr) {
+            return;
+        }
+        let (count, bytes) = match direction {
+            Direction::Read => (&stats.reads, &stats.bytes_read),
+            Direction::Write => (&stats.writes, &stats.bytes_written),
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+        bytes.fetch_add(size, Ordering::Relaxed);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        map_field,
+        stats::{self, AccessStats, Counts},
+        VolatilePtr,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_access_stats() {
+        static STATS: AccessStats = AccessStats::new("test_access_stats");
+
+        let mut array = [0_u16; 4];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array)) };
+        STATS.track_range(volatile.as_raw_ptr().as_ptr().cast(), 4);
+
+        map_field!(volatile[0]).update(|v| v + 1);
+        map_field!(volatile[1]).write(2);
+        map_field!(volatile[2]).read();
+        volatile.read();
+        assert_eq!(
+            STATS.counts(),
+            Counts {
+                reads: 2,
+                writes: 2,
+                bytes_read: 10,
+                bytes_written: 4
+            }
+        );
+
+        let mut found = false;
+        stats::for_each(|stats| found |= stats.name() == "test_access_stats");
+        assert!(found);
+
+        STATS.reset();
+        assert_eq!(STATS.counts(), Counts::default());
+    }
+
+    #[test]
+    fn test_access_stats_bulk() {
+        static STATS: AccessStats = AccessStats::new("test_access_stats_bulk");
+
+        let mut array = [0_u32; 16];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+        STATS.track_range(volatile.as_raw_ptr().as_ptr().cast(), 64);
+
+        volatile.copy_from_slice(&[1; 16]);
+        volatile.copy_into_slice(&mut [0; 16]);
+        volatile.copy_within(..8, 8);
+        assert_eq!(
+            STATS.counts(),
+            Counts {
+                reads: 2,
+                writes: 2,
+                bytes_read: 96,
+                bytes_written: 96
+            }
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Arrays of registers with a fixed byte stride.
+//!
+//! Hardware often repeats a register for each channel, port, or queue at a fixed distance
+//! that is larger than the register itself, e.g., GPIO banks or per-queue doorbells. A
+//! [`VolatileStrided`] describes such a layout and hands out a [`VolatilePtr`] per element.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::ptr::NonNull;
+//! use volatile::strided::VolatileStrided;
+//!
+//! // Four queue doorbells, each at the start of a 16-byte block.
+//! let mut blocks = [0_u32; 16];
+//! let doorbells =
+//!     unsafe { VolatileStrided::new(NonNull::from(&mut blocks).cast::<u32>(), 4, 16) };
+//!
+//! doorbells.index(2).write(7);
+//! assert_eq!(blocks[8], 7);
+//!
+//! for (queue, doorbell) in doorbells.iter().enumerate() {
+//!     doorbell.write(queue as u32);
+//! }
+//! assert_eq!([blocks[0], blocks[4], blocks[8], blocks[12]], [0, 1, 2, 3]);
+//! ```
+
+use core::{fmt, iter::FusedIterator, marker::PhantomData, mem, ptr::NonNull};
+
+use crate::{
+    access::{Access, ReadOnly, ReadWrite, RestrictAccess, WriteOnly},
+    padding::NoPadding,
+    region::VolatileRegion,
+    VolatileError, VolatilePtr,
+};
+
+/// A sequence of values of type `T` that are `stride` bytes apart.
+///
+/// Like [`VolatilePtr`], this type restricts the allowed operations through the access
+/// parameter `A`, which is passed on to all handed out pointers.
+pub struct VolatileStrided<'a, T, A = ReadWrite> {
+    base: NonNull<T>,
+    len: usize,
+    stride: usize,
+    reference: PhantomData<&'a [T]>,
+    access: PhantomData<A>,
+}
+
+impl<T, A> Copy for VolatileStrided<'_, T, A> {}
+
+impl<T, A> Clone for VolatileStrided<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> fmt::Debug for VolatileStrided<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileStrided")
+            .field("base", &self.base)
+            .field("len", &self.len)
+            .field("stride", &self.stride)
+            .finish()
+    }
+}
+
+/// Constructor functions.
+impl<'a, T> VolatileStrided<'a, T> {
+    /// Creates a view of `len` values of type `T` that start `stride` bytes apart.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `stride` is smaller than the size of `T` or not a multiple of its alignment.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`VolatilePtr::new`] apply to each of the values.
+    pub const unsafe fn new(
+        base: NonNull<T>,
+        len: usize,
+        stride: usize,
+    ) -> VolatileStrided<'a, T, ReadWrite> {
+        unsafe { Self::new_restricted(ReadWrite, base, len, stride) }
+    }
+
+    /// Creates a view with restricted access.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `stride` is smaller than the size of `T` or not a multiple of its alignment.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`Self::new`] apply to this function too.
+    pub const unsafe fn new_restricted<A>(
+        access: A,
+        base: NonNull<T>,
+        len: usize,
+        stride: usize,
+    ) -> VolatileStrided<'a, T, A>
+    where
+        A: Access,
+    {
+        let _ = access;
+        assert!(
+            stride >= mem::size_of::<T>(),
+            "stride must not be smaller than the element"
+        );
+        assert!(
+            stride & (mem::align_of::<T>() - 1) == 0,
+            "stride must be a multiple of the alignment"
+        );
+        VolatileStrided {
+            base,
+            len,
+            stride,
+            reference: PhantomData,
+            access: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, A> VolatileStrided<'a, T, A>
+where
+    A: Access,
+{
+    /// Returns the number of values.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no values.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the distance between two consecutive values in bytes.
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a volatile pointer to the value at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<VolatilePtr<'a, T, A>> {
+        if index >= self.len {
+            return None;
+        }
+        // SAFETY: The value is within the bounds given to `new`.
+        Some(unsafe {
+            VolatilePtr::new_restricted(A::default(), self.base.byte_add(index * self.stride))
+        })
+    }
+
+    /// Returns a volatile pointer to the value at `index`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn index(&self, index: usize) -> VolatilePtr<'a, T, A> {
+        match self.get(index) {
+            Some(pointer) => pointer,
+            None => panic!(
+                "index {index} is out of bounds for length {len}",
+                len = self.len
+            ),
+        }
+    }
+
+    /// Returns an iterator over volatile pointers to all values.
+    pub fn iter(&self) -> Iter<'a, T, A> {
+        Iter {
+            strided: *self,
+            range: 0..self.len,
+        }
+    }
+
+    /// Restricts access permissions to `To`.
+    pub fn restrict<To>(self) -> VolatileStrided<'a, T, A::Restricted>
+    where
+        A: RestrictAccess<To>,
+    {
+        VolatileStrided {
+            base: self.base,
+            len: self.len,
+            stride: self.stride,
+            reference: PhantomData,
+            access: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> VolatileStrided<'a, T, ReadWrite> {
+    /// Restricts access permissions to read-only.
+    pub fn read_only(self) -> VolatileStrided<'a, T, ReadOnly> {
+        self.restrict()
+    }
+
+    /// Restricts access permissions to write-only.
+    pub fn write_only(self) -> VolatileStrided<'a, T, WriteOnly> {
+        self.restrict()
+    }
+}
+
+impl<'a, T, A> IntoIterator for VolatileStrided<'a, T, A>
+where
+    A: Access,
+{
+    type Item = VolatilePtr<'a, T, A>;
+    type IntoIter = Iter<'a, T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`VolatileStrided`].
+pub struct Iter<'a, T, A> {
+    strided: VolatileStrided<'a, T, A>,
+    range: core::ops::Range<usize>,
+}
+
+impl<T, A> fmt::Debug for Iter<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("strided", &self.strided)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<'a, T, A> Iterator for Iter<'a, T, A>
+where
+    A: Access,
+{
+    type Item = VolatilePtr<'a, T, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.strided.index(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T, A> DoubleEndedIterator for Iter<'_, T, A>
+where
+    A: Access,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range
+            .next_back()
+            .map(|index| self.strided.index(index))
+    }
+}
+
+impl<T, A> ExactSizeIterator for Iter<'_, T, A> where A: Access {}
+
+impl<T, A> FusedIterator for Iter<'_, T, A> where A: Access {}
+
+/// Methods for strided views of regions.
+impl<'a, A> VolatileRegion<'a, A>
+where
+    A: Access,
+{
+    /// Returns a view of `len` values of type `T` that start at byte offset `offset` and are
+    /// `stride` bytes apart.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `stride` is smaller than the size of `T` or not a multiple of its alignment.
+    pub fn strided<T>(
+        &self,
+        offset: usize,
+        len: usize,
+        stride: usize,
+    ) -> Result<VolatileStrided<'a, T, A>, VolatileError>
+    where
+        T: NoPadding,
+    {
+        let end = match len {
+            0 => offset,
+            len => (len - 1)
+                .checked_mul(stride)
+                .and_then(|last| last.checked_add(offset))
+                .and_then(|last| last.checked_add(mem::size_of::<T>()))
+                .unwrap_or(usize::MAX),
+        };
+        let bytes = self.subregion(offset..end)?;
+        let base = match len {
+            0 => NonNull::dangling(),
+            _ => bytes.at::<T>(0)?.as_raw_ptr(),
+        };
+        // SAFETY: All values are within the region and properly aligned.
+        Ok(unsafe { VolatileStrided::new_restricted(A::default(), base, len, stride) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{region::VolatileRegion, VolatileError};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_strided() {
+        let mut banks = [0_u32; 12];
+        let region = unsafe { VolatileRegion::new(NonNull::from(&mut banks).cast(), 48) };
+
+        let outputs = region.strided::<u32>(4, 3, 16).unwrap();
+        assert_eq!(outputs.len(), 3);
+        outputs.index(0).write(1);
+        outputs.iter().next_back().unwrap().write(3);
+        assert!(outputs.get(3).is_none());
+        assert_eq!(outputs.read_only().iter().map(|p| p.read()).sum::<u32>(), 4);
+        assert_eq!(
+            region.strided::<u32>(4, 4, 16).unwrap_err(),
+            VolatileError::OutOfBounds { index: 56, len: 48 }
+        );
+        assert!(region.strided::<u32>(48, 0, 16).unwrap().is_empty());
+        assert_eq!(banks, [0, 1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Recording of volatile accesses for debugging.
+//!
+//! [`VolatilePtr::traced`] wraps a pointer in a [`Traced`] pointer that reports every read
+//! and write to a [`TraceSink`]. The reported offsets are relative to the traced pointer,
+//! which makes it easy to capture the register traffic of a device during bring-up.
+//!
+//! Since [`Traced`] provides `as_raw_ptr` and `map`, [`map_field!`](crate::map_field) works
+//! on traced pointers as well.
+//!
+//! ## Example
+//!
+//! ```
+//! use core::cell::Cell;
+//! use volatile::{map_field, trace::TraceEvent, VolatilePtr};
+//!
+//! #[repr(C)]
+//! #[derive(Clone, Copy)]
+//! struct Uart {
+//!     data: u32,
+//!     status: u32,
+//! }
+//!
+//! let mut uart = Uart { data: 0, status: 1 };
+//! let volatile = unsafe { VolatilePtr::new((&mut uart).into()) };
+//!
+//! let last_offset = Cell::new(None);
+//! let sink = |event: &TraceEvent<'_>| last_offset.set(Some(event.offset));
+//! let traced = volatile.traced(&sink);
+//!
+//! assert_eq!(map_field!(traced.status).read(), 1);
+//! assert_eq!(last_offset.get(), Some(4));
+//! ```
+
+use core::{fmt, ptr::NonNull};
+
+use crate::{
+    access::{Access, Readable, Writable},
+    backend::Direction,
+    VolatilePtr,
+};
+
+/// A recorded volatile access.
+#[derive(Clone, Copy)]
+pub struct TraceEvent<'a> {
+    /// Whether the access was a read or a write.
+    pub direction: Direction,
+    /// The offset of the access in bytes from the start of the traced pointer.
+    pub offset: usize,
+    /// The width of the access in bytes.
+    pub width: usize,
+    /// The value that was read or written.
+    pub value: &'a dyn fmt::Debug,
+}
+
+impl fmt::Debug for TraceEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceEvent")
+            .field("direction", &self.direction)
+            .field("offset", &self.offset)
+            .field("width", &self.width)
+            .field("value", self.value)
+            .finish()
+    }
+}
+
+/// A destination for [`TraceEvent`]s.
+///
+/// This is implemented for closures, which also allows using a sink by reference, e.g., a
+/// ring buffer, through `|event| ring.record(event)`.
+pub trait TraceSink {
+    /// Records a volatile access.
+    fn record(&self, event: &TraceEvent<'_>);
+}
+
+impl<F> TraceSink for F
+where
+    F: Fn(&TraceEvent<'_>),
+{
+    fn record(&self, event: &TraceEvent<'_>) {
+        self(event);
+    }
+}
+
+/// A [`VolatilePtr`] that reports all accesses to a [`TraceSink`].
+///
+/// Created by [`VolatilePtr::traced`].
+pub struct Traced<'a, T, A, S>
+where
+    T: ?Sized,
+{
+    pointer: VolatilePtr<'a, T, A>,
+    base: usize,
+    sink: S,
+}
+
+impl<T, A, S> Copy for Traced<'_, T, A, S>
+where
+    T: ?Sized,
+    S: Copy,
+{
+}
+
+impl<T, A, S> Clone for Traced<'_, T, A, S>
+where
+    T: ?Sized,
+    S: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A, S> fmt::Debug for Traced<'_, T, A, S>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Traced")
+            .field("pointer", &self.pointer)
+            .field("base", &(self.base as *const u8))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, A> VolatilePtr<'a, T, A>
+where
+    T: ?Sized,
+{
+    /// Reports all accesses through the returned pointer to `sink`.
+    ///
+    /// Offsets are reported relative to `self`.
+    pub fn traced<S>(self, sink: S) -> Traced<'a, T, A, S>
+    where
+        S: TraceSink,
+    {
+        Traced {
+            pointer: self,
+            base: self.as_raw_ptr().as_ptr().cast::<u8>() as usize,
+            sink,
+        }
+    }
+}
+
+impl<'a, T, A, S> Traced<'a, T, A, S>
+where
+    T: ?Sized,
+    S: TraceSink,
+{
+    /// Performs a volatile read of the contained value and records it.
+    ///
+    /// See [`VolatilePtr::read`].
+    pub fn read(&self) -> T
+    where
+        T: Copy + fmt::Debug,
+        A: Readable,
+    {
+        let value = self.pointer.read();
+        self.record(Direction::Read, &value);
+        value
+    }
+
+    /// Performs a volatile write of `value` and records it.
+    ///
+    /// See [`VolatilePtr::write`].
+    pub fn write(&self, value: T)
+    where
+        T: Copy + fmt::Debug,
+        A: Writable,
+    {
+        self.record(Direction::Write, &value);
+        self.pointer.write(value);
+    }
+
+    /// Updates the contained value using the given closure and records both accesses.
+    ///
+    /// See [`VolatilePtr::update`].
+    pub fn update<F>(&self, f: F)
+    where
+        T: Copy + fmt::Debug,
+        A: Readable + Writable,
+        F: FnOnce(T) -> T,
+    {
+        self.write(f(self.read()));
+    }
+
+    /// Returns the offset of this pointer from the start of the traced pointer.
+    pub fn offset(&self) -> usize {
+        self.pointer.as_raw_ptr().as_ptr().cast::<u8>() as usize - self.base
+    }
+
+    /// Returns the underlying pointer, which does not record accesses.
+    pub fn into_inner(self) -> VolatilePtr<'a, T, A> {
+        self.pointer
+    }
+
+    /// Extracts the wrapped raw pointer.
+    pub fn as_raw_ptr(&self) -> NonNull<T> {
+        self.pointer.as_raw_ptr()
+    }
+
+    /// Constructs a new traced pointer by mapping the wrapped pointer, keeping the sink.
+    ///
+    /// ## Safety
+    ///
+    /// See [`VolatilePtr::map`].
+    pub unsafe fn map<F, U>(self, f: F) -> Traced<'a, U, A, S>
+    where
+        A: Access,
+        F: FnOnce(NonNull<T>) -> NonNull<U>,
+        U: ?Sized,
+    {
+        Traced {
+            pointer: unsafe { self.pointer.map(f) },
+            base: self.base,
+            sink: self.sink,
+        }
+    }
+
+    fn record(&self, direction: Direction, value: &T)
+    where
+        T: Sized + fmt::Debug,
+    {
+        self.sink.record(&TraceEvent {
+            direction,
+            offset: self.offset(),
+            width: core::mem::size_of::<T>(),
+            value,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{backend::Direction, map_field, trace::TraceEvent, VolatilePtr};
+    use core::{cell::RefCell, ptr::NonNull};
+
+    #[test]
+    fn test_traced() {
+        #[repr(C)]
+        struct S {
+            a: u32,
+            b: u16,
+        }
+
+        let mut val = S { a: 1, b: 2 };
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+
+        let events = RefCell::new([(Direction::Read, 0, 0); 3]);
+        let count = core::cell::Cell::new(0);
+        let sink = |event: &TraceEvent<'_>| {
+            events.borrow_mut()[count.get()] = (event.direction, event.offset, event.width);
+            count.set(count.get() + 1);
+        };
+        let traced = volatile.traced(&sink);
+
+        assert_eq!(map_field!(traced.a).read(), 1);
+        map_field!(traced.b).update(|b| b + 1);
+        assert_eq!(val.b, 3);
+        assert_eq!(count.get(), 3);
+        assert_eq!(
+            events.into_inner(),
+            [
+                (Direction::Read, 0, 4),
+                (Direction::Read, 4, 2),
+                (Direction::Write, 4, 2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Address ranges that can be replaced while other threads check accesses against them.
+
+use core::{
+    hint,
+    sync::atomic::{fence, AtomicU8, AtomicUsize, Ordering},
+};
+
+/// An address range with a tag, e.g., the accesses that it applies to.
+///
+/// This is used for the ranges of the access statistics and the watchpoints.
+///
+/// The range is updated like a seqlock: the sequence number is odd while [`store`] runs, and
+/// [`load`] retries until it read all fields under the same even sequence number. This
+/// ensures that readers never observe a start address of one range with the length of
+/// another.
+///
+/// [`store`]: Self::store
+/// [`load`]: Self::load
+#[derive(Debug)]
+pub(crate) struct TrackedRange {
+    sequence: AtomicUsize,
+    start: AtomicUsize,
+    len: AtomicUsize,
+    tag: AtomicU8,
+}
+
+impl TrackedRange {
+    /// Creates an empty range with the given tag.
+    pub(crate) const fn new(tag: u8) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            start: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            tag: AtomicU8::new(tag),
+        }
+    }
+
+    /// Replaces the range and its tag.
+    pub(crate) fn store(&self, start: usize, len: usize, tag: u8) {
+        let mut sequence = self.sequence.load(Ordering::Relaxed);
+        loop {
+            if sequence % 2 == 1 {
+                // Another thread is updating the range.
+                hint::spin_loop();
+                sequence = self.sequence.load(Ordering::Relaxed);
+                continue;
+            }
+            match self.sequence.compare_exchange_weak(
+                sequence,
+                sequence.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => sequence = current,
+            }
+        }
+        fence(Ordering::Release);
+        self.start.store(start, Ordering::Relaxed);
+        self.len.store(len, Ordering::Relaxed);
+        self.tag.store(tag, Ordering::Relaxed);
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Returns the start, length, and tag of a consistent snapshot of the range.
+    pub(crate) fn load(&self) -> (usize, usize, u8) {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+            if sequence % 2 == 1 {
+                hint::spin_loop();
+                continue;
+            }
+            let range = (
+                self.start.load(Ordering::Relaxed),
+                self.len.load(Ordering::Relaxed),
+                self.tag.load(Ordering::Relaxed),
+            );
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == sequence {
+                return range;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::tracked::TrackedRange;
+
+    #[test]
+    fn test_tracked_range_threads() {
+        const COUNT: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+        let range = TrackedRange::new(0);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 1..=COUNT {
+                    range.store(i, i, i as u8);
+                }
+            });
+            loop {
+                let (start, len, tag) = range.load();
+                assert_eq!(start, len);
+                assert_eq!(tag, start as u8);
+                if start == COUNT {
+                    break;
+                }
+            }
+        });
+    }
+}
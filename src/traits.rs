@@ -0,0 +1,169 @@
+//! Traits for code that is generic over the volatile wrapper types.
+//!
+//! Drivers often only need to read or write a register and do not care whether it is behind a
+//! [`VolatilePtr`] or a [`VolatileRef`]. Functions that accept `impl VolatileReadable<T>` or
+//! `impl VolatileWritable<T>` instead of a concrete wrapper type work with both. Fields can be
+//! projected from the pointers returned by [`VolatileReadable::as_read_ptr`] and
+//! [`VolatileWritable::as_write_ptr`] as usual.
+//!
+//! The traits are not object safe, since some of their methods are generic.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{
+//!     map_field,
+//!     traits::{VolatileReadable, VolatileWritable},
+//!     VolatilePtr, VolatileRef,
+//! };
+//!
+//! #[repr(C)]
+//! #[derive(Clone, Copy)]
+//! struct Uart {
+//!     data: u8,
+//!     status: u8,
+//! }
+//!
+//! fn send(mut uart: impl VolatileWritable<Uart> + VolatileReadable<Uart>, byte: u8) {
+//!     let status = uart.as_read_ptr();
+//!     if map_field!(status.status).read() & 1 == 0 {
+//!         let registers = uart.as_write_ptr();
+//!         map_field!(registers.data).write(byte);
+//!     }
+//! }
+//!
+//! let mut registers = Uart { data: 0, status: 0 };
+//! send(VolatileRef::from_mut_ref(&mut registers), b'a');
+//! send(unsafe { VolatilePtr::new((&mut registers).into()) }, b'b');
+//! assert_eq!(registers.data, b'b');
+//! ```
+
+use crate::{
+    access::{Access, ReadOnly, Readable, RestrictAccess, Writable},
+    VolatilePtr, VolatileRef,
+};
+
+/// Volatile wrappers that allow reading the referenced value.
+pub trait VolatileReadable<T>
+where
+    T: ?Sized,
+{
+    /// Borrows the wrapper as a read-only [`VolatilePtr`].
+    fn as_read_ptr(&self) -> VolatilePtr<'_, T, ReadOnly>;
+
+    /// Performs a volatile read of the referenced value.
+    fn read(&self) -> T
+    where
+        T: Copy,
+    {
+        self.as_read_ptr().read()
+    }
+}
+
+/// Volatile wrappers that allow writing the referenced value.
+pub trait VolatileWritable<T>
+where
+    T: ?Sized,
+{
+    /// The access permissions of the pointers returned by [`as_write_ptr`](Self::as_write_ptr).
+    type Access: Writable;
+
+    /// Borrows the wrapper as a writable [`VolatilePtr`].
+    fn as_write_ptr(&mut self) -> VolatilePtr<'_, T, Self::Access>;
+
+    /// Performs a volatile write, setting the referenced value to `value`.
+    fn write(&mut self, value: T)
+    where
+        T: Copy,
+    {
+        self.as_write_ptr().write(value);
+    }
+
+    /// Updates the referenced value using the given closure and volatile instructions.
+    fn update<F>(&mut self, f: F)
+    where
+        T: Copy,
+        Self::Access: Readable,
+        F: FnOnce(T) -> T,
+    {
+        self.as_write_ptr().update(f);
+    }
+}
+
+impl<T, A> VolatileReadable<T> for VolatilePtr<'_, T, A>
+where
+    T: ?Sized,
+    A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+{
+    fn as_read_ptr(&self) -> VolatilePtr<'_, T, ReadOnly> {
+        self.restrict()
+    }
+}
+
+impl<T, A> VolatileWritable<T> for VolatilePtr<'_, T, A>
+where
+    T: ?Sized,
+    A: Writable,
+{
+    type Access = A;
+
+    fn as_write_ptr(&mut self) -> VolatilePtr<'_, T, A> {
+        *self
+    }
+}
+
+impl<T, A> VolatileReadable<T> for VolatileRef<'_, T, A>
+where
+    T: ?Sized,
+    A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+{
+    fn as_read_ptr(&self) -> VolatilePtr<'_, T, ReadOnly> {
+        self.as_ptr()
+    }
+}
+
+impl<T, A> VolatileWritable<T> for VolatileRef<'_, T, A>
+where
+    T: ?Sized,
+    A: Access + Writable,
+{
+    type Access = A;
+
+    fn as_write_ptr(&mut self) -> VolatilePtr<'_, T, A> {
+        self.as_mut_ptr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        access::{ReadOnly, ReadWrite},
+        traits::{VolatileReadable, VolatileWritable},
+        VolatilePtr, VolatileRef,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_volatile_traits() {
+        fn double(
+            mut register: impl VolatileReadable<u32> + VolatileWritable<u32, Access = ReadWrite>,
+        ) -> u32 {
+            register.update(|value| value * 2);
+            register.read()
+        }
+
+        fn read_twice(register: &impl VolatileReadable<u32>) -> u32 {
+            register.read() + register.as_read_ptr().read()
+        }
+
+        let mut val = 21_u32;
+        assert_eq!(double(VolatileRef::from_mut_ref(&mut val)), 42);
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+        assert_eq!(double(volatile), 84);
+        let mut volatile = volatile;
+        VolatileWritable::write(&mut volatile, 1);
+        assert_eq!(read_twice(&volatile), 2);
+        let read_only: VolatilePtr<'_, u32, ReadOnly> = volatile.read_only();
+        assert_eq!(read_twice(&read_only), 2);
+    }
+}
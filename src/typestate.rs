@@ -0,0 +1,225 @@
+//! Compile-time enforcement of device initialization protocols.
+//!
+//! Many devices require certain registers to be written before others may be accessed, e.g.,
+//! a controller that has to be configured before its data registers are valid. A [`Device`]
+//! wraps a pointer to the register block of type `T` together with a state type `S`. The
+//! register block is only accessible in states for which `T` implements [`Accessible`], and
+//! the state only changes through the transitions that `T` implements as [`Transition`]. Since
+//! transitions consume the device, a handle in an outdated state cannot be used anymore.
+//!
+//! This module provides the [`Unconfigured`] and [`Ready`] states for the common case, but
+//! drivers can define their own state types as well. [`Device::new`] always starts in the
+//! [`Unconfigured`] state. Devices in other states, e.g., if the firmware already configured
+//! the device, are created through [`Device::new_in_state`], which skips the transitions and
+//! should therefore be used sparingly.
+//!
+//! The states only protect accesses through the [`Device`]. Since [`VolatilePtr`] is `Copy`,
+//! the caller can keep using the pointer that was passed to [`Device::new`] to access the
+//! register block in any state. Drivers that rely on the protocol should therefore not keep
+//! other pointers to the register block around.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{
+//!     map_field,
+//!     typestate::{Accessible, Device, Ready, Transition, Unconfigured},
+//!     VolatilePtr,
+//! };
+//!
+//! #[repr(C)]
+//! struct Uart {
+//!     baud_divisor: u32,
+//!     data: u32,
+//! }
+//!
+//! impl Transition<Unconfigured, Ready> for Uart {
+//!     type Input = u32;
+//!
+//!     fn transition(uart: VolatilePtr<'_, Self>, baud_divisor: u32) {
+//!         map_field!(uart.baud_divisor).write(baud_divisor);
+//!     }
+//! }
+//!
+//! impl Accessible<Ready> for Uart {}
+//!
+//! let mut registers = Uart { baud_divisor: 0, data: 0 };
+//! let uart: Device<'_, Uart, Unconfigured> =
+//!     Device::new(unsafe { VolatilePtr::new((&mut registers).into()) });
+//!
+//! let uart = uart.transition::<Ready>(115_200 / 9600);
+//! let pointer = uart.as_ptr();
+//! map_field!(pointer.data).write(u32::from(b'!'));
+//! assert_eq!(registers.baud_divisor, 12);
+//! ```
+//!
+//! Accessing the registers before the transition does not compile:
+//!
+//! ```compile_fail
+//! # use volatile::{typestate::{Accessible, Device, Ready, Unconfigured}, VolatilePtr};
+//! # struct Uart { data: u32 }
+//! # impl Accessible<Ready> for Uart {}
+//! # let mut registers = Uart { data: 0 };
+//! let uart: Device<'_, Uart, Unconfigured> =
+//!     Device::new(unsafe { VolatilePtr::new((&mut registers).into()) });
+//! uart.as_ptr();
+//! ```
+//!
+//! Neither does creating the device in the `Ready` state directly:
+//!
+//! ```compile_fail
+//! # use volatile::{typestate::{Device, Ready}, VolatilePtr};
+//! # struct Uart { data: u32 }
+//! # let mut registers = Uart { data: 0 };
+//! let uart: Device<'_, Uart, Ready> =
+//!     Device::new(unsafe { VolatilePtr::new((&mut registers).into()) });
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use crate::VolatilePtr;
+
+/// The state of a device that has not been configured yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unconfigured {}
+
+/// The state of a device that is ready for use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ready {}
+
+/// A state change of a register block from state `From` to state `To`.
+pub trait Transition<From, To> {
+    /// The data that is required for the transition, e.g., configuration values.
+    type Input;
+
+    /// Performs the register accesses that move the device from `From` to `To`.
+    fn transition(registers: VolatilePtr<'_, Self>, input: Self::Input);
+}
+
+/// Marks states in which the register block may be accessed directly.
+pub trait Accessible<S> {}
+
+/// A register block of type `T` in state `S`.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Device<'a, T, S> {
+    registers: VolatilePtr<'a, T>,
+    state: PhantomData<S>,
+}
+
+impl<T, S> fmt::Debug for Device<'_, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Device")
+            .field("registers", &self.registers)
+            .field("state", &core::any::type_name::<S>())
+            .finish()
+    }
+}
+
+impl<'a, T> Device<'a, T, Unconfigured> {
+    /// Creates a handle for the unconfigured register block behind `registers`.
+    ///
+    /// To start in another state, e.g., if the firmware already configured the device, use
+    /// [`Device::new_in_state`].
+    ///
+    /// The caller can still access the register block through copies of `registers`, see the
+    /// [module-level documentation](self).
+    pub const fn new(registers: VolatilePtr<'a, T>) -> Self {
+        Self::new_in_state(registers)
+    }
+}
+
+impl<'a, T, S> Device<'a, T, S> {
+    /// Creates a handle for the register block behind `registers`, which is in state `S`.
+    ///
+    /// The register block should actually be in state `S`, i.e., all transitions that lead to
+    /// `S` should have been performed. Otherwise, the register block might be accessed before
+    /// the device is ready. This is a logic error, but not a memory safety issue, since the
+    /// register block could be accessed through `registers` directly as well.
+    pub const fn new_in_state(registers: VolatilePtr<'a, T>) -> Self {
+        Self {
+            registers,
+            state: PhantomData,
+        }
+    }
+
+    /// Moves the device to state `To` by performing the corresponding [`Transition`].
+    pub fn transition<To>(self, input: T::Input) -> Device<'a, T, To>
+    where
+        T: Transition<S, To>,
+    {
+        T::transition(self.registers, input);
+        Device::new_in_state(self.registers)
+    }
+
+    /// Returns a pointer to the register block.
+    pub fn as_ptr(&self) -> VolatilePtr<'a, T>
+    where
+        T: Accessible<S>,
+    {
+        self.registers
+    }
+
+    /// Returns a pointer to the register block and discards the state.
+    pub fn into_inner(self) -> VolatilePtr<'a, T>
+    where
+        T: Accessible<S>,
+    {
+        self.registers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        map_field,
+        typestate::{Accessible, Device, Ready, Transition, Unconfigured},
+        VolatilePtr,
+    };
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_typestate() {
+        enum Reset {}
+
+        struct Controller {
+            control: u32,
+            data: u32,
+        }
+
+        impl Transition<Unconfigured, Reset> for Controller {
+            type Input = ();
+
+            fn transition(registers: VolatilePtr<'_, Self>, (): ()) {
+                map_field!(registers.control).write(1);
+            }
+        }
+
+        impl Transition<Reset, Ready> for Controller {
+            type Input = u32;
+
+            fn transition(registers: VolatilePtr<'_, Self>, mode: u32) {
+                map_field!(registers.control).update(|control| control | mode << 4);
+            }
+        }
+
+        impl Accessible<Ready> for Controller {}
+
+        let mut controller = Controller {
+            control: 0,
+            data: 0,
+        };
+        let device: Device<'_, Controller, Unconfigured> =
+            Device::new(unsafe { VolatilePtr::new(NonNull::from(&mut controller)) });
+        let device = device.transition::<Reset>(()).transition::<Ready>(2);
+        let registers = device.into_inner();
+        map_field!(registers.data).write(5);
+        assert_eq!(controller.control, 0x21);
+        assert_eq!(controller.data, 5);
+
+        let device: Device<'_, Controller, Ready> =
+            Device::new_in_state(unsafe { VolatilePtr::new(NonNull::from(&mut controller)) });
+        map_field!(device.as_ptr().data).write(6);
+        assert_eq!(controller.data, 6);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+
+macro_rules! impl_arith {
+    ($($ty:ty),*) => {
+        $(
+            /// Methods for counters in volatile memory.
+            ///
+            /// These methods perform a volatile read followed by a volatile write. They are
+            /// **not atomic**: concurrent modifications by other cores, interrupt handlers, or the
+            /// device between the two accesses are lost. Use the atomic types of `core` if such
+            /// modifications are possible.
+            impl<A> VolatilePtr<'_, $ty, A>
+            where
+                A: Readable + Writable,
+            {
+                /// Adds `by` to the value, wrapping around at the boundary of the type, and
+                /// returns the previous value.
+                ///
+                /// ## Example
+                ///
+                /// ```
+                /// use volatile::VolatilePtr;
+                ///
+                #[doc = concat!("let mut counter: ", stringify!($ty), " = 1;")]
+                /// let volatile = unsafe { VolatilePtr::new((&mut counter).into()) };
+                /// assert_eq!(volatile.increment(2), 1);
+                /// assert_eq!(counter, 3);
+                /// ```
+                #[inline(always)]
+                #[track_caller]
+                pub fn increment(self, by: $ty) -> $ty {
+                    let value = self.read();
+                    self.write(value.wrapping_add(by));
+                    value
+                }
+
+                /// Subtracts `by` from the value, wrapping around at the boundary of the type,
+                /// and returns the previous value.
+                #[inline(always)]
+                #[track_caller]
+                pub fn decrement(self, by: $ty) -> $ty {
+                    let value = self.read();
+                    self.write(value.wrapping_sub(by));
+                    value
+                }
+            }
+        )*
+    };
+}
+
+impl_arith!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
@@ -0,0 +1,62 @@
+use crate::VolatilePtr;
+
+/// Methods for volatile arrays.
+impl<'a, T, A, const N: usize> VolatilePtr<'a, [T; N], A> {
+    /// Returns a pointer to the element at index `I`, which is checked at compile time.
+    ///
+    /// Unlike [`map_field!`](crate::map_field) with a runtime index, this method does not contain
+    /// any bounds checks or panic paths, which is useful for fixed register banks that are
+    /// accessed from interrupt handlers.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut bank = [0_u32; 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut bank).into()) };
+    /// volatile.index_const::<3>().write(7);
+    /// assert_eq!(bank, [0, 0, 0, 7]);
+    /// ```
+    ///
+    /// Out-of-bounds indices do not compile:
+    ///
+    /// ```compile_fail
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut bank = [0_u32; 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut bank).into()) };
+    /// volatile.index_const::<4>().write(7);
+    /// ```
+    pub const fn index_const<const I: usize>(self) -> VolatilePtr<'a, T, A> {
+        const { assert!(I < N, "index out of bounds") }
+        // SAFETY: The element is within the array.
+        unsafe { VolatilePtr::new_generic(self.pointer.cast::<T>().add(I)) }
+    }
+
+    /// Returns a pointer to the `L` elements starting at index `S`, which are checked at compile
+    /// time.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut bank = [1_u32, 2, 3, 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut bank).into()) };
+    /// assert_eq!(volatile.range_const::<1, 2>().read(), [2, 3]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut bank = [1_u32, 2, 3, 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut bank).into()) };
+    /// volatile.range_const::<3, 2>().read();
+    /// ```
+    pub const fn range_const<const S: usize, const L: usize>(self) -> VolatilePtr<'a, [T; L], A> {
+        const { assert!(S <= N && L <= N - S, "range out of bounds") }
+        // SAFETY: The elements are within the array.
+        unsafe { VolatilePtr::new_generic(self.pointer.cast::<T>().add(S).cast()) }
+    }
+}
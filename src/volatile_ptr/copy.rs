@@ -0,0 +1,791 @@
+use core::{
+    mem,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use crate::{
+    access::{Readable, Writable},
+    backend::{self, Direction},
+    VolatilePtr,
+};
+
+/// Methods for copying volatile slices.
+///
+/// With the `simd` feature, large copies use vector accesses as described in [`crate::simd`].
+/// Otherwise, with the `unstable` feature, these methods use the volatile memory intrinsics of
+/// the compiler. Without either feature, they copy word-wide where the alignment of both slices
+/// allows it. Everywhere else, they use accesses as wide as the alignment of `T`, up to a word,
+/// so that, e.g., a slice of `u32` registers is never accessed with 8-bit or 16-bit accesses.
+impl<T, A> VolatilePtr<'_, [T], A> {
+    /// Copies all elements from `self` into `dst`, using a volatile memcpy.
+    ///
+    /// The length of `dst` must be the same as `self`.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if the two slices have different lengths.
+    ///
+    /// ## Examples
+    ///
+    /// Copying two elements from a volatile slice:
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let src = [1, 2];
+    /// // the `Volatile` type does not work with arrays, so convert `src` to a slice
+    /// let slice = &src[..];
+    /// let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(slice)) };
+    /// let mut dst = [5, 0, 0];
+    ///
+    /// // Because the slices have to be the same length,
+    /// // we slice the destination slice from three elements
+    /// // to two. It will panic if we don't do this.
+    /// volatile.copy_into_slice(&mut dst[1..]);
+    ///
+    /// assert_eq!(src, [1, 2]);
+    /// assert_eq!(dst, [5, 1, 2]);
+    /// ```
+    #[track_caller]
+    pub fn copy_into_slice(self, dst: &mut [T])
+    where
+        T: Copy,
+        A: Readable,
+    {
+        let len = self.pointer.len();
+        assert_eq!(
+            len,
+            dst.len(),
+            "destination and source slices have different lengths"
+        );
+        unsafe { copy_from_volatile(dst.as_mut_ptr(), self.pointer.as_ptr().cast::<T>(), len) };
+    }
+
+    /// Copies all elements from `src` into `self`, using a volatile memcpy.
+    ///
+    /// The length of `src` must be the same as `self`.
+    ///
+    /// This method is similar to the `slice::copy_from_slice` method of the standard library. The
+    /// difference is that this method performs a volatile copy.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if the two slices have different lengths.
+    ///
+    /// ## Examples
+    ///
+    /// Copying two elements from a slice into a volatile slice:
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let src = [1, 2, 3, 4];
+    /// let mut dst = [0, 0];
+    /// // the `Volatile` type does not work with arrays, so convert `dst` to a slice
+    /// let slice = &mut dst[..];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(slice)) };
+    /// // Because the slices have to be the same length,
+    /// // we slice the source slice from four elements
+    /// // to two. It will panic if we don't do this.
+    /// volatile.copy_from_slice(&src[2..]);
+    ///
+    /// assert_eq!(src, [1, 2, 3, 4]);
+    /// assert_eq!(dst, [3, 4]);
+    /// ```
+    #[track_caller]
+    pub fn copy_from_slice(self, src: &[T])
+    where
+        T: Copy,
+        A: Writable,
+    {
+        let len = self.pointer.len();
+        assert_eq!(
+            len,
+            src.len(),
+            "destination and source slices have different lengths"
+        );
+        unsafe { copy_to_volatile(self.pointer.as_ptr().cast::<T>(), src.as_ptr(), len) };
+    }
+
+    /// Copies all elements from `self` into the volatile slice `dst`, using a volatile
+    /// `memmove`.
+    ///
+    /// The two slices may overlap, e.g., when compacting a ring buffer. This method is similar to
+    /// [`ptr::copy`](core::ptr::copy). If the slices are known not to overlap,
+    /// [`copy_to_nonoverlapping`](Self::copy_to_nonoverlapping) can be faster.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if the two slices have different lengths.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut buffer = [1, 2, 3, 4, 5];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut buffer[..])) };
+    /// let (src, dst) = unsafe {
+    ///     (
+    ///         volatile.map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<i32>().add(2), 3)),
+    ///         volatile.map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<i32>(), 3)),
+    ///     )
+    /// };
+    /// src.copy_to(dst);
+    ///
+    /// assert_eq!(buffer, [3, 4, 5, 4, 5]);
+    /// ```
+    #[track_caller]
+    pub fn copy_to<B>(self, dst: VolatilePtr<'_, [T], B>)
+    where
+        T: Copy,
+        A: Readable,
+        B: Writable,
+    {
+        let len = self.pointer.len();
+        assert_eq!(
+            len,
+            dst.pointer.len(),
+            "destination and source slices have different lengths"
+        );
+        unsafe {
+            copy_volatile(
+                dst.pointer.as_ptr().cast::<T>(),
+                self.pointer.as_ptr().cast::<T>(),
+                len,
+                true,
+            );
+        }
+    }
+
+    /// Copies all elements from `self` into the volatile slice `dst`, using a volatile
+    /// `memcpy`.
+    ///
+    /// This method is similar to [`ptr::copy_nonoverlapping`](core::ptr::copy_nonoverlapping).
+    /// Use [`copy_to`](Self::copy_to) if the slices might overlap.
+    ///
+    /// ## Safety
+    ///
+    /// The two slices must not overlap.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if the two slices have different lengths.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut staging = [1_u8, 2, 3, 4];
+    /// let mut device = [0_u8; 4];
+    /// let src = unsafe { VolatilePtr::new(NonNull::from(&mut staging[..])) };
+    /// let dst = unsafe { VolatilePtr::new(NonNull::from(&mut device[..])) };
+    /// unsafe { src.copy_to_nonoverlapping(dst) };
+    ///
+    /// assert_eq!(device, [1, 2, 3, 4]);
+    /// ```
+    #[track_caller]
+    pub unsafe fn copy_to_nonoverlapping<B>(self, dst: VolatilePtr<'_, [T], B>)
+    where
+        T: Copy,
+        A: Readable,
+        B: Writable,
+    {
+        let len = self.pointer.len();
+        assert_eq!(
+            len,
+            dst.pointer.len(),
+            "destination and source slices have different lengths"
+        );
+        // SAFETY: The caller guarantees that the slices do not overlap.
+        unsafe {
+            copy_volatile(
+                dst.pointer.as_ptr().cast::<T>(),
+                self.pointer.as_ptr().cast::<T>(),
+                len,
+                false,
+            );
+        }
+    }
+
+    /// Copies elements from one part of the slice to another part of itself, using a
+    /// volatile `memmove`.
+    ///
+    /// `src` is the range within `self` to copy from. `dest` is the starting index of the
+    /// range within `self` to copy to, which will have the same length as `src`. The two ranges
+    /// may overlap. The ends of the two ranges must be less than or equal to `self.len()`.
+    ///
+    /// This method is similar to the `slice::copy_within` method of the standard library. The
+    /// difference is that this method performs a volatile copy.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if either range exceeds the end of the slice, or if the end
+    /// of `src` is before the start.
+    ///
+    /// ## Examples
+    ///
+    /// Copying four bytes within a slice:
+    ///
+    /// ```
+    /// extern crate core;
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut byte_array = *b"Hello, World!";
+    /// let slice: &mut [u8] = &mut byte_array[..];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(slice)) };
+    /// volatile.copy_within(1..5, 8);
+    ///
+    /// assert_eq!(&byte_array, b"Hello, Wello!");
+    #[track_caller]
+    pub fn copy_within(self, src: impl RangeBounds<usize>, dest: usize)
+    where
+        T: Copy,
+        A: Readable + Writable,
+    {
+        let len = self.pointer.len();
+        // implementation taken from https://github.com/rust-lang/rust/blob/683d1bcd405727fcc9209f64845bd3b9104878b8/library/core/src/slice/mod.rs#L2726-L2738
+        let Range {
+            start: src_start,
+            end: src_end,
+        } = range(src, len);
+        let count = src_end - src_start;
+        assert!(dest <= len - count, "dest is out of bounds");
+        // SAFETY: the conditions for `volatile_copy_memory` have all been checked above,
+        // as have those for `ptr::add`.
+        unsafe {
+            copy_volatile(
+                self.pointer.as_ptr().cast::<T>().add(dest),
+                self.pointer.as_ptr().cast::<T>().add(src_start),
+                count,
+                true,
+            );
+        }
+    }
+}
+
+/// Methods for filling, checksumming, and searching volatile byte slices.
+impl<A> VolatilePtr<'_, [u8], A> {
+    /// Sets all bytes of the slice to `value`, using a volatile `memset`.
+    ///
+    /// With the `unstable` feature, this method uses the volatile memory intrinsic of the
+    /// compiler. Otherwise, it writes the word-aligned middle part of the slice with word-wide
+    /// volatile stores and the unaligned head and tail byte-wide.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut shared = [0xaa_u8; 100];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut shared[..])) };
+    /// volatile.fill_bytes(0);
+    /// assert_eq!(shared, [0; 100]);
+    /// ```
+    #[track_caller]
+    pub fn fill_bytes(self, value: u8)
+    where
+        A: Writable,
+    {
+        let dst = self.pointer.as_ptr().cast::<u8>();
+        let len = self.pointer.len();
+        let _observation = backend::observe_bulk(dst, len, Direction::Write);
+        // SAFETY: The slice is valid for writes of `len` bytes.
+        let routed = unsafe {
+            copy_through_backend(
+                len,
+                false,
+                |_| value,
+                |i, byte| backend::write_unobserved(dst.add(i), byte),
+            )
+        };
+        if !routed {
+            unsafe { volatile_set_memory(dst, value, len) };
+        }
+    }
+
+    /// Returns the wrapping sum of all bytes of the slice.
+    ///
+    /// This is the checksum of ACPI and SMBIOS tables, which are valid if the sum over the
+    /// whole table is zero. The bytes are read in chunks with the same volatile copies as
+    /// [`copy_into_slice`](Self::copy_into_slice), so tables can be validated in place without
+    /// copying them out of their mapping first.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let table = [0x10_u8, 0x20, 0xd0];
+    /// let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(&table[..])) };
+    /// assert_eq!(volatile.sum_bytes_wrapping(), 0);
+    /// ```
+    #[track_caller]
+    pub fn sum_bytes_wrapping(self) -> u8
+    where
+        A: Readable,
+    {
+        let mut buf = [0; 64];
+        let mut sum = 0_u8;
+        let mut offset = 0;
+        let len = self.pointer.len();
+        let base = self.pointer.as_ptr().cast::<u8>();
+        let _observation = backend::observe_bulk(base, len, Direction::Read);
+        while offset < len {
+            let chunk = &mut buf[..usize::min(64, len - offset)];
+            unsafe {
+                copy_from_volatile_unobserved(chunk.as_mut_ptr(), base.add(offset), chunk.len());
+            }
+            sum = chunk.iter().fold(sum, |sum, &byte| sum.wrapping_add(byte));
+            offset += chunk.len();
+        }
+        sum
+    }
+
+    /// Returns the offset of the first occurrence of `needle` in the slice.
+    ///
+    /// The slice is scanned with byte-wide volatile reads, and each candidate is only read
+    /// up to its first mismatch. This allows locating signatures in large firmware regions
+    /// without copying them into memory first. An empty `needle` is found at offset 0.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let firmware = *b"....._SM_......";
+    /// let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(&firmware[..])) };
+    /// assert_eq!(volatile.find(b"_SM_"), Some(5));
+    /// assert_eq!(volatile.find(b"_DMI_"), None);
+    /// ```
+    pub fn find(self, needle: &[u8]) -> Option<usize>
+    where
+        A: Readable,
+    {
+        self.find_aligned(needle, 1)
+    }
+
+    /// Returns the offset of the first occurrence of `needle` that starts at a multiple of
+    /// `stride`.
+    ///
+    /// Offsets are relative to the start of the slice. This is useful for signatures that are
+    /// only valid at aligned locations, e.g., the ACPI RSDP on a 16-byte boundary, and skips
+    /// all other offsets without reading them.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `stride` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut bios = [0_u8; 64];
+    /// bios[4..12].copy_from_slice(b"RSD PTR ");
+    /// bios[32..40].copy_from_slice(b"RSD PTR ");
+    /// let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(&bios[..])) };
+    /// assert_eq!(volatile.find_aligned(b"RSD PTR ", 16), Some(32));
+    /// ```
+    pub fn find_aligned(self, needle: &[u8], stride: usize) -> Option<usize>
+    where
+        A: Readable,
+    {
+        assert_ne!(stride, 0, "stride must not be zero");
+        let len = self.pointer.len();
+        let last = len.checked_sub(needle.len())?;
+        let base = self.pointer.as_ptr().cast::<u8>();
+        (0..=last).step_by(stride).find(|&offset| {
+            needle.iter().enumerate().all(|(i, &expected)| {
+                // SAFETY: `offset + i` is within the slice.
+                let byte = unsafe { backend::read(base.add(offset + i)) };
+                byte == expected
+            })
+        })
+    }
+}
+
+/// Methods for copying volatile arrays.
+///
+/// Unlike the slice copies, these methods access each element with a separate volatile access
+/// of type `T`. Since the length is known at compile time, they do not perform any length checks,
+/// and the compiler fully unrolls the copies for small arrays.
+impl<T, A, const N: usize> VolatilePtr<'_, [T; N], A> {
+    /// Copies all elements from `src` into `self`, element by element.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut descriptor = [0_u32; 4];
+    /// let volatile = unsafe { VolatilePtr::new((&mut descriptor).into()) };
+    /// volatile.copy_from_array(&[0x1000, 0, 64, 1]);
+    /// assert_eq!(descriptor, [0x1000, 0, 64, 1]);
+    /// ```
+    #[inline(always)]
+    pub fn copy_from_array(self, src: &[T; N])
+    where
+        T: Copy,
+        A: Writable,
+    {
+        let dst = self.pointer.as_ptr().cast::<T>();
+        for (i, &value) in src.iter().enumerate() {
+            // SAFETY: `i` is within the array.
+            unsafe { backend::write(dst.add(i), value) };
+        }
+    }
+
+    /// Copies all elements from `self` into `dst`, element by element.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let descriptor = [0x1000_u32, 0, 64, 1];
+    /// let volatile = unsafe { VolatilePtr::new_read_only((&descriptor).into()) };
+    /// let mut dst = [0; 4];
+    /// volatile.copy_into_array(&mut dst);
+    /// assert_eq!(dst, [0x1000, 0, 64, 1]);
+    /// ```
+    #[inline(always)]
+    pub fn copy_into_array(self, dst: &mut [T; N])
+    where
+        T: Copy,
+        A: Readable,
+    {
+        let src = self.pointer.as_ptr().cast::<T>();
+        for (i, value) in dst.iter_mut().enumerate() {
+            // SAFETY: `i` is within the array.
+            *value = unsafe { backend::read(src.add(i)) };
+        }
+    }
+}
+
+/// Converts `range` to a range within a slice of length `len`.
+///
+/// Panics like slice indexing if the range is not within the slice.
+fn range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "slice index starts at {start} but ends at {end}"
+    );
+    assert!(
+        end <= len,
+        "range end index {end} out of range for slice of length {len}"
+    );
+    start..end
+}
+
+/// The width in bytes of the volatile accesses that copy elements of type `T`.
+///
+/// This is the alignment of `T`, but at most a word, so that, e.g., `u32` elements are never
+/// copied with accesses narrower than 32 bits.
+pub(crate) const fn unit<T>() -> usize {
+    let align = mem::align_of::<T>();
+    if align < WORD {
+        align
+    } else {
+        WORD
+    }
+}
+
+/// Evaluates `$body` with `$ty` defined as the unsigned integer type of `$unit` bytes.
+macro_rules! with_unit {
+    ($unit:expr, |$ty:ident| $body:expr) => {
+        match $unit {
+            1 => {
+                type $ty = u8;
+                $body
+            }
+            2 => {
+                type $ty = u16;
+                $body
+            }
+            4 => {
+                type $ty = u32;
+                $body
+            }
+            8 => {
+                type $ty = u64;
+                $body
+            }
+            _ => unreachable!(),
+        }
+    };
+}
+
+pub(crate) use with_unit;
+
+/// Copies `len` bytes in chunks of type `U` if a backend is installed and returns whether it
+/// did so.
+///
+/// `read` and `write` access the chunk at the given byte offset of the source and the
+/// destination, so that only the volatile sides of a copy are reported to the backend. If
+/// `backward` is set, the chunks are copied in descending order.
+#[inline(always)]
+unsafe fn copy_through_backend<U>(
+    len: usize,
+    backward: bool,
+    read: impl Fn(usize) -> U,
+    write: impl Fn(usize, U),
+) -> bool {
+    if !backend::is_installed() {
+        return false;
+    }
+    let copy = |i| write(i, read(i));
+    let offsets = (0..len).step_by(mem::size_of::<U>());
+    if backward {
+        offsets.rev().for_each(copy);
+    } else {
+        offsets.for_each(copy);
+    }
+    true
+}
+
+/// Copies `count` elements from the volatile slice at `src` to the non-overlapping plain memory
+/// at `dst`, either through the installed backend or directly.
+///
+/// The read of the volatile slice is reported to the hooks as a single access.
+#[track_caller]
+unsafe fn copy_from_volatile<T>(dst: *mut T, src: *const T, count: usize) {
+    let len = count * mem::size_of::<T>();
+    let _observation = backend::observe_bulk(src.cast(), len, Direction::Read);
+    unsafe { copy_from_volatile_unobserved(dst, src, count) };
+}
+
+/// Like [`copy_from_volatile`], but without calling any hooks.
+unsafe fn copy_from_volatile_unobserved<T>(dst: *mut T, src: *const T, count: usize) {
+    let (dst_bytes, src_bytes) = (dst.cast::<u8>(), src.cast::<u8>());
+    let routed = with_unit!(unit::<T>(), |U| unsafe {
+        copy_through_backend(
+            count * mem::size_of::<T>(),
+            false,
+            |i| backend::read_unobserved(src_bytes.add(i).cast::<U>()),
+            |i, chunk| dst_bytes.add(i).cast::<U>().write(chunk),
+        )
+    });
+    if !routed {
+        unsafe { volatile_copy_nonoverlapping_memory(dst, src, count) };
+    }
+}
+
+/// Copies `count` elements from the plain memory at `src` to the non-overlapping volatile
+/// slice at `dst`, either through the installed backend or directly.
+///
+/// The write of the volatile slice is reported to the hooks as a single access.
+#[track_caller]
+unsafe fn copy_to_volatile<T>(dst: *mut T, src: *const T, count: usize) {
+    let len = count * mem::size_of::<T>();
+    let _observation = backend::observe_bulk(dst.cast(), len, Direction::Write);
+    let (dst_bytes, src_bytes) = (dst.cast::<u8>(), src.cast::<u8>());
+    let routed = with_unit!(unit::<T>(), |U| unsafe {
+        copy_through_backend(
+            len,
+            false,
+            |i| src_bytes.add(i).cast::<U>().read(),
+            |i, chunk| backend::write_unobserved(dst_bytes.add(i).cast::<U>(), chunk),
+        )
+    });
+    if !routed {
+        unsafe { volatile_copy_nonoverlapping_memory(dst, src, count) };
+    }
+}
+
+/// Copies `count` elements between two volatile slices, either through the installed backend
+/// or directly.
+///
+/// The memory regions may only overlap if `overlapping` is set. The read of `src` and the write
+/// of `dst` are each reported to the hooks as a single access.
+#[track_caller]
+unsafe fn copy_volatile<T>(dst: *mut T, src: *const T, count: usize, overlapping: bool) {
+    let len = count * mem::size_of::<T>();
+    let _read = backend::observe_bulk(src.cast(), len, Direction::Read);
+    let _write = backend::observe_bulk(dst.cast(), len, Direction::Write);
+    let (dst_bytes, src_bytes) = (dst.cast::<u8>(), src.cast::<u8>());
+    let routed = with_unit!(unit::<T>(), |U| unsafe {
+        copy_through_backend(
+            len,
+            dst_bytes.cast_const() > src_bytes,
+            |i| backend::read_unobserved(src_bytes.add(i).cast::<U>()),
+            |i, chunk| backend::write_unobserved(dst_bytes.add(i).cast::<U>(), chunk),
+        )
+    });
+    match (routed, overlapping) {
+        (true, _) => {}
+        (false, true) => unsafe { volatile_copy_memory(dst, src, count) },
+        (false, false) => unsafe { volatile_copy_nonoverlapping_memory(dst, src, count) },
+    }
+}
+
+/// Copies `count` elements from `src` to `dst` using volatile accesses.
+///
+/// The memory regions must not overlap.
+unsafe fn volatile_copy_nonoverlapping_memory<T>(dst: *mut T, src: *const T, count: usize) {
+    #[cfg(all(feature = "unstable", not(feature = "simd"), not(miri)))]
+    unsafe {
+        core::intrinsics::volatile_copy_nonoverlapping_memory(dst, src, count);
+    }
+    #[cfg(not(all(feature = "unstable", not(feature = "simd"), not(miri))))]
+    {
+        let len = count * mem::size_of::<T>();
+        with_unit!(unit::<T>(), |U| unsafe {
+            copy_chunks_forward::<U>(dst.cast(), src.cast(), len)
+        });
+    }
+}
+
+/// Copies `count` elements from `src` to `dst` using volatile accesses.
+///
+/// The memory regions may overlap.
+unsafe fn volatile_copy_memory<T>(dst: *mut T, src: *const T, count: usize) {
+    #[cfg(all(feature = "unstable", not(feature = "simd"), not(miri)))]
+    unsafe {
+        core::intrinsics::volatile_copy_memory(dst, src, count);
+    }
+    #[cfg(not(all(feature = "unstable", not(feature = "simd"), not(miri))))]
+    {
+        let len = count * mem::size_of::<T>();
+        // Copy backwards if the destination overlaps the end of the source.
+        if dst.cast_const() > src {
+            with_unit!(unit::<T>(), |U| unsafe {
+                copy_chunks_backward::<U>(dst.cast(), src.cast(), len)
+            });
+        } else {
+            with_unit!(unit::<T>(), |U| unsafe {
+                copy_chunks_forward::<U>(dst.cast(), src.cast(), len)
+            });
+        }
+    }
+}
+
+/// Sets `len` bytes at `dst` to `value` using volatile accesses.
+unsafe fn volatile_set_memory(dst: *mut u8, value: u8, len: usize) {
+    #[cfg(all(feature = "unstable", not(miri)))]
+    unsafe {
+        core::intrinsics::volatile_set_memory(dst, value, len);
+    }
+    #[cfg(not(all(feature = "unstable", not(miri))))]
+    {
+        let mut i = 0;
+        let head = dst.align_offset(WORD).min(len);
+        while i < head {
+            unsafe { dst.add(i).write_volatile(value) };
+            i += 1;
+        }
+        let word = usize::from_ne_bytes([value; WORD]);
+        while len - i >= WORD {
+            unsafe { dst.add(i).cast::<usize>().write_volatile(word) };
+            i += WORD;
+        }
+        while i < len {
+            unsafe { dst.add(i).write_volatile(value) };
+            i += 1;
+        }
+    }
+}
+
+const WORD: usize = mem::size_of::<usize>();
+
+/// Copies `len` bytes from `src` to `dst` in ascending order.
+///
+/// `len` and both pointers must be multiples of the size of `U`, which is used for all
+/// accesses except for the word-wide ones. If both pointers have the same alignment modulo the
+/// word size, the bytes between the unaligned head and tail are copied with word-wide volatile
+/// accesses. Otherwise, all bytes are copied with `U`-wide volatile accesses. With the `simd`
+/// feature, large aligned middle parts are copied with vector accesses instead.
+#[cfg_attr(
+    all(feature = "unstable", not(feature = "simd"), not(miri)),
+    allow(dead_code)
+)]
+unsafe fn copy_chunks_forward<U>(dst: *mut u8, src: *const u8, len: usize) {
+    let unit = mem::size_of::<U>();
+    let copy = |i: usize| unsafe {
+        let chunk = src.add(i).cast::<U>().read_volatile();
+        dst.add(i).cast::<U>().write_volatile(chunk);
+    };
+    let mut i = 0;
+    if (dst.addr() ^ src.addr()) & (WORD - 1) == 0 {
+        let head = dst.align_offset(WORD).min(len);
+        while i < head {
+            copy(i);
+            i += unit;
+        }
+        #[cfg(feature = "simd")]
+        {
+            i += unsafe { crate::simd::copy_forward(dst.add(i), src.add(i), len - i) };
+        }
+        while len - i >= WORD {
+            unsafe {
+                let word = src.add(i).cast::<usize>().read_volatile();
+                dst.add(i).cast::<usize>().write_volatile(word);
+            }
+            i += WORD;
+        }
+    }
+    while i < len {
+        copy(i);
+        i += unit;
+    }
+}
+
+/// Copies `len` bytes from `src` to `dst` in descending order.
+///
+/// Uses word-wide and `U`-wide volatile accesses under the same conditions as
+/// [`copy_chunks_forward`].
+#[cfg_attr(
+    all(feature = "unstable", not(feature = "simd"), not(miri)),
+    allow(dead_code)
+)]
+unsafe fn copy_chunks_backward<U>(dst: *mut u8, src: *const u8, len: usize) {
+    let unit = mem::size_of::<U>();
+    let copy = |i: usize| unsafe {
+        let chunk = src.add(i).cast::<U>().read_volatile();
+        dst.add(i).cast::<U>().write_volatile(chunk);
+    };
+    let mut i = len;
+    if (dst.addr() ^ src.addr()) & (WORD - 1) == 0 {
+        let tail = dst.wrapping_add(len).addr() & (WORD - 1);
+        let tail = tail.min(len);
+        while i > len - tail {
+            i -= unit;
+            copy(i);
+        }
+        while i >= WORD {
+            i -= WORD;
+            unsafe {
+                let word = src.add(i).cast::<usize>().read_volatile();
+                dst.add(i).cast::<usize>().write_volatile(word);
+            }
+        }
+    }
+    while i > 0 {
+        i -= unit;
+        copy(i);
+    }
+}
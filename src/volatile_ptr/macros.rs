@@ -1,5 +1,10 @@
 /// Provides safe field projection for volatile pointers referencing structs.
 ///
+/// The base pointer can be any expression, e.g., a method call, and is evaluated exactly once.
+/// It is the innermost expression that is neither a field access nor an index. With the `derive`
+/// feature, this macro is replaced by a procedural macro that accepts the same syntax and
+/// reports errors more precisely.
+///
 /// ## Examples
 ///
 /// Accessing a struct field:
@@ -16,7 +21,133 @@
 /// assert_eq!(field_2.read(), 255);
 /// ```
 ///
-/// Creating `VolatilePtr`s to unaligned field in packed structs is not allowed:
+/// Accessing a nested field in a single invocation:
+///
+/// ```
+/// use volatile::{VolatilePtr, map_field};
+///
+/// #[repr(C)]
+/// struct Queue { desc_addr: u64, size: u16, }
+/// #[repr(C)]
+/// struct Device { status: u32, queue: Queue, }
+/// let mut value = Device { status: 0, queue: Queue { desc_addr: 0x1000, size: 256 } };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// // same as `let queue = map_field!(volatile.queue);` followed by `map_field!(queue.desc_addr)`
+/// let desc_addr = map_field!(volatile.queue.desc_addr);
+/// assert_eq!(desc_addr.read(), 0x1000);
+/// ```
+///
+/// Accessing fields of tuple structs, e.g., newtype register wrappers:
+///
+/// ```
+/// use volatile::{VolatilePtr, map_field};
+///
+/// #[repr(transparent)]
+/// struct Status(u32);
+/// struct Example { status: Status, pair: (u8, (u16, u32)), }
+/// let mut value = Example { status: Status(1), pair: (2, (3, 4)) };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// assert_eq!(map_field!(volatile.status.0).read(), 1);
+/// assert_eq!(map_field!(volatile.pair.1.0).read(), 3);
+///
+/// let status = map_field!(volatile.status);
+/// assert_eq!(map_field!(status.0).read(), 1);
+/// ```
+///
+/// Indexing into arrays:
+///
+/// ```
+/// use volatile::{VolatilePtr, map_field};
+///
+/// #[repr(C)]
+/// struct Queue { desc_addr: u64, size: u16, }
+/// #[repr(C)]
+/// struct Device { status: u32, queues: [Queue; 2], }
+/// let mut value = Device {
+///     status: 0,
+///     queues: [Queue { desc_addr: 0x1000, size: 256 }, Queue { desc_addr: 0x2000, size: 128 }],
+/// };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// let i = 1;
+/// assert_eq!(map_field!(volatile.queues[i].desc_addr).read(), 0x2000);
+/// assert_eq!(map_field!(volatile.queues[0].size).read(), 256);
+/// ```
+///
+/// Projecting from an arbitrary base expression, or from an element of a pointer to an array:
+///
+/// ```
+/// use volatile::{VolatilePtr, map_field};
+///
+/// #[repr(C)]
+/// struct Queue { desc_addr: u64, size: u16, }
+///
+/// struct Driver<'a> { queues: VolatilePtr<'a, [Queue; 2]>, }
+///
+/// impl<'a> Driver<'a> {
+///     fn queues(&self) -> VolatilePtr<'a, [Queue; 2]> {
+///         self.queues
+///     }
+/// }
+///
+/// let mut queues = [Queue { desc_addr: 0x1000, size: 256 }, Queue { desc_addr: 0x2000, size: 128 }];
+/// let driver = Driver { queues: unsafe { VolatilePtr::new((&mut queues).into()) } };
+///
+/// assert_eq!(map_field!(driver.queues()[1].desc_addr).read(), 0x2000);
+/// assert_eq!(map_field!((driver.queues)[0].size).read(), 256);
+/// let queues = driver.queues();
+/// assert_eq!(map_field!(queues[1].size).read(), 128);
+/// ```
+///
+/// Indices are bounds checked at runtime, and constant indices are also checked at compile time:
+///
+/// ```should_panic
+/// use volatile::{VolatilePtr, map_field};
+///
+/// struct Example { field: [u32; 4], }
+/// let mut value = Example { field: [0; 4] };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// let i = 4;
+/// map_field!(volatile.field[i]).read(); // panics
+/// ```
+///
+/// ```compile_fail
+/// use volatile::{VolatilePtr, map_field};
+///
+/// struct Example { field: [u32; 4], }
+/// let mut value = Example { field: [0; 4] };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// map_field!(volatile.field[4]).read(); // index out of bounds
+/// ```
+///
+/// Only arrays and slices can be indexed, since other types go through [`Index`](core::ops::Index),
+/// which requires creating a reference.
+///
+/// ## Packed structs
+///
+/// Volatile reads and writes require aligned pointers, so the macro only projects fields that are
+/// guaranteed to be aligned. This includes all fields of `repr(packed(N))` structs whose alignment
+/// does not exceed `N`:
+///
+/// ```
+/// use volatile::{VolatilePtr, map_field};
+///
+/// #[repr(C, packed(2))]
+/// struct Example { field_1: u8, field_2: u16, field_3: [u8; 3], }
+/// let mut value = Example { field_1: 15, field_2: 255, field_3: [1, 2, 3] };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// assert_eq!(map_field!(volatile.field_2).read(), 255);
+/// assert_eq!(map_field!(volatile.field_3[2]).read(), 3);
+/// ```
+///
+/// Creating `VolatilePtr`s to potentially unaligned fields in packed structs is rejected at
+/// compile time (error `E0793`) instead of producing a misaligned pointer:
+///
 /// ```compile_fail
 /// use volatile::{VolatilePtr, map_field};
 ///
@@ -28,20 +159,133 @@
 /// // Constructing a volatile reference to an unaligned field doesn't compile.
 /// let field_2 = map_field!(volatile.field_2);
 /// ```
+///
+/// This also holds for fields of nested structs and array elements:
+///
+/// ```compile_fail
+/// use volatile::{VolatilePtr, map_field};
+///
+/// struct Inner { field: u32, }
+/// #[repr(packed)]
+/// struct Example { field_1: u8, inner: Inner, array: [u32; 2], }
+/// let mut value = Example { field_1: 15, inner: Inner { field: 255 }, array: [1, 2] };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// let field = map_field!(volatile.inner.field);
+/// let element = map_field!(volatile.array[1]);
+/// ```
+///
+/// To access such fields, read or write the whole struct instead, e.g.,
+/// `volatile.read().field_2`.
+#[cfg(not(feature = "derive"))]
 #[macro_export]
 macro_rules! map_field {
-    ($volatile:ident.$($place:ident).+) => {{
+    // Splits the input into the base expression and the projections that are applied to it.
+    // The base is the innermost expression that is neither a field access nor an index, so
+    // all tokens up to the last token that is not part of a projection, e.g., the arguments of
+    // a method call, belong to it.
+    (@split [] [] $first:tt $($rest:tt)*) => {
+        $crate::map_field!(@split [$first] [] $($rest)*)
+    };
+    (@split [$($base:tt)*] [$($projections:tt)*] . $field:tt $($rest:tt)*) => {
+        $crate::map_field!(@split [$($base)*] [$($projections)* . $field] $($rest)*)
+    };
+    (@split [$($base:tt)*] [$($projections:tt)*] [$($index:tt)*] $($rest:tt)*) => {
+        $crate::map_field!(@split [$($base)*] [$($projections)* [$($index)*]] $($rest)*)
+    };
+    (@split [$($base:tt)*] [$($projections:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::map_field!(@split [$($base)* $($projections)* $next] [] $($rest)*)
+    };
+    (@split [$($base:tt)*] []) => {
+        ::core::compile_error!(
+            "expected a place such as `volatile.field` or `volatile.array[index]`"
+        )
+    };
+    (@split [$($base:tt)*] [$($projections:tt)+]) => {{
+        // The base may be parenthesized, e.g., `(*self)`.
+        #[allow(unused_parens)]
+        let volatile = $($base)*;
+
         // Simulate creating a reference to the field. This is done to make
         // sure that the field is not potentially unaligned. The body of the
         // if statement will never be executed, so it can never cause any UB.
         if false {
-            let _ref_to_field = &(unsafe { &*$volatile.as_raw_ptr().as_ptr() }).$($place).+;
+            let value = unsafe { &*volatile.as_raw_ptr().as_ptr() };
+            let _ref_to_field = &value $($projections)+;
+
+            // Only allow indexing arrays and slices, which does not create references, and check
+            // constant indices at compile time.
+            $crate::map_field!(@check_indices value [] $($projections)+);
         }
 
         unsafe {
-            $volatile.map(|ptr| {
-                core::ptr::NonNull::new(core::ptr::addr_of_mut!((*ptr.as_ptr()).$($place).+)).unwrap()
+            volatile.map(|ptr| {
+                ::core::ptr::NonNull::new(
+                    ::core::ptr::addr_of_mut!((*ptr.as_ptr()) $($projections)+)
+                ).unwrap()
             })
         }
     }};
+    // Checks all indices in the place. `$prefix` is the part of the place before the current
+    // token.
+    (@check_indices $value:ident [$($prefix:tt)*]) => {};
+    (@check_indices $value:ident [$($prefix:tt)*] [$index:literal] $($rest:tt)*) => {
+        $crate::__MapFieldIndex::check_const_index::<$index>(&(*$value) $($prefix)*);
+        $crate::map_field!(@check_indices $value [$($prefix)* [$index]] $($rest)*);
+    };
+    (@check_indices $value:ident [$($prefix:tt)*] [$index:expr] $($rest:tt)*) => {
+        $crate::__MapFieldIndex::check_index(&(*$value) $($prefix)*);
+        $crate::map_field!(@check_indices $value [$($prefix)* [$index]] $($rest)*);
+    };
+    (@check_indices $value:ident [$($prefix:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::map_field!(@check_indices $value [$($prefix)* $next] $($rest)*);
+    };
+    ($($place:tt)+) => {
+        $crate::map_field!(@split [] [] $($place)+)
+    };
 }
+
+/// Projects a volatile pointer to several fields at once.
+///
+/// `map_fields!(volatile => a, b, c)` evaluates `volatile` once and returns the tuple
+/// `(map_field!(volatile.a), map_field!(volatile.b), map_field!(volatile.c))`. Fields can be
+/// named fields or tuple indices.
+///
+/// ## Examples
+///
+/// ```
+/// use volatile::{VolatilePtr, map_fields};
+///
+/// #[repr(C)]
+/// struct Ring { head: u16, tail: u16, entries: [u32; 4], }
+/// let mut value = Ring { head: 1, tail: 3, entries: [0; 4] };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// let (head, tail, entries) = map_fields!(volatile => head, tail, entries);
+/// head.write(tail.read());
+/// assert_eq!(head.read(), 3);
+/// assert_eq!(entries.read(), [0; 4]);
+/// ```
+#[macro_export]
+macro_rules! map_fields {
+    ($volatile:expr => $($field:tt),+ $(,)?) => {{
+        let volatile = $volatile;
+        ($($crate::map_field!(volatile.$field),)+)
+    }};
+}
+
+/// Implementation detail of [`map_field`] for checking indices.
+#[doc(hidden)]
+pub trait __MapFieldIndex {
+    fn check_index(&self) {}
+
+    fn check_const_index<const I: usize>(&self) {}
+}
+
+impl<T, const N: usize> __MapFieldIndex for [T; N] {
+    fn check_const_index<const I: usize>(&self) {
+        const { assert!(I < N, "index out of bounds") }
+    }
+}
+
+impl<T> __MapFieldIndex for [T] {}
@@ -1,9 +1,18 @@
 use core::{cmp::Ordering, fmt, hash, marker::PhantomData, ptr::NonNull};
 
-use crate::access::ReadWrite;
+use crate::access::{ReadOnly, ReadWrite};
 
+mod arith;
+mod array;
+mod copy;
 mod macros;
 mod operations;
+#[cfg(feature = "alloc")]
+mod owned;
+
+pub(crate) use copy::{unit, with_unit};
+#[doc(hidden)]
+pub use macros::__MapFieldIndex;
 
 #[cfg(test)]
 mod tests;
@@ -24,6 +33,14 @@ mod very_unstable;
 /// to `ReadWrite`, which allows all operations.
 ///
 /// The size of this struct is the same as the size of the contained reference.
+///
+/// ## Zero-sized types
+///
+/// Reads and writes of zero-sized types, such as `()` or `[u32; 0]`, are no-ops: they do not
+/// access memory and do not act as fences, so they are not observable by a device. Copies
+/// between slices of zero-sized types copy no bytes, but still panic if the lengths differ.
+/// Generic code that relies on an access being performed has to require a non-zero size
+/// itself.
 #[repr(transparent)]
 pub struct VolatilePtr<'a, T, A = ReadWrite>
 where
@@ -63,6 +80,16 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<T, A> defmt::Format for VolatilePtr<'_, T, A>
+where
+    T: ?Sized,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=usize:#x}", self.pointer.as_ptr().cast::<u8>().addr());
+    }
+}
+
 impl<T, A> PartialEq for VolatilePtr<'_, T, A>
 where
     T: ?Sized,
@@ -101,3 +128,16 @@ where
         self.pointer.as_ptr().hash(state);
     }
 }
+
+/// Creates a read-only `VolatilePtr` from a shared reference.
+///
+/// **Note:** Like [`VolatileRef::from_ref`](crate::VolatileRef::from_ref), this is only intended
+/// for testing with in-memory storage, not for accessing real volatile data.
+impl<'a, T> From<&'a T> for VolatilePtr<'a, T, ReadOnly>
+where
+    T: ?Sized,
+{
+    fn from(reference: &'a T) -> Self {
+        unsafe { VolatilePtr::new_read_only(reference.into()) }
+    }
+}
@@ -1,11 +1,12 @@
 use core::{
     marker::PhantomData,
+    num::NonZeroUsize,
     ptr::{self, NonNull},
 };
 
 use crate::{
     access::{Access, ReadOnly, ReadWrite, Readable, RestrictAccess, Writable, WriteOnly},
-    VolatilePtr,
+    backend, Mismatch, VolatilePtr,
 };
 
 /// Constructor functions.
@@ -24,10 +25,82 @@ where
     /// - The given pointer must be valid.
     /// - No other thread must have access to the given pointer. This must remain true
     ///   for the whole lifetime of the `VolatilePtr`.
-    pub unsafe fn new(pointer: NonNull<T>) -> VolatilePtr<'a, T, ReadWrite> {
+    pub const unsafe fn new(pointer: NonNull<T>) -> VolatilePtr<'a, T, ReadWrite> {
         unsafe { VolatilePtr::new_restricted(ReadWrite, pointer) }
     }
 
+    /// Creates a `VolatilePtr` from the given address, e.g., of a memory-mapped register.
+    ///
+    /// Like the other constructors, this function can be used in `const` initializers. The
+    /// address is converted with an integer-to-pointer cast, which has the same semantics as
+    /// [`Self::from_exposed_addr`]. Use [`Self::with_addr`] to derive a pointer with strict
+    /// provenance instead.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `addr` is zero.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{access::ReadOnly, VolatilePtr};
+    ///
+    /// const UART: VolatilePtr<'static, [u32; 8]> = unsafe { VolatilePtr::from_addr(0x1000_0000) };
+    /// const STATUS: VolatilePtr<'static, u32, ReadOnly> =
+    ///     unsafe { UART.map_offset(0x14).read_only() };
+    /// assert_eq!(STATUS.as_raw_ptr().as_ptr() as usize, 0x1000_0014);
+    /// ```
+    pub const unsafe fn from_addr(addr: usize) -> VolatilePtr<'a, T, ReadWrite>
+    where
+        T: Sized,
+    {
+        match NonNull::new(addr as *mut T) {
+            Some(pointer) => unsafe { VolatilePtr::new(pointer) },
+            None => panic!("address must not be zero"),
+        }
+    }
+
+    /// Creates a `VolatilePtr` from the given address, picking up any provenance that was
+    /// previously exposed at this address.
+    ///
+    /// This is the counterpart of [`Self::expose_addr`] and states explicitly that the pointer
+    /// uses the exposed provenance model, e.g., for addresses that are provided by firmware.
+    /// Pointers derived from a pointer with known provenance should use [`Self::with_addr`]
+    /// instead.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `addr` is zero.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut value = 42_u32;
+    /// let addr = unsafe { VolatilePtr::new((&mut value).into()) }.expose_addr();
+    ///
+    /// let volatile = unsafe { VolatilePtr::<u32>::from_exposed_addr(addr) };
+    /// assert_eq!(volatile.read(), 42);
+    /// ```
+    pub unsafe fn from_exposed_addr(addr: usize) -> VolatilePtr<'a, T, ReadWrite>
+    where
+        T: Sized,
+    {
+        match NonNull::new(ptr::with_exposed_provenance_mut(addr)) {
+            Some(pointer) => unsafe { VolatilePtr::new(pointer) },
+            None => panic!("address must not be zero"),
+        }
+    }
+
     /// Creates a new read-only volatile pointer from the given raw pointer.
     ///
     /// ## Safety
@@ -50,7 +123,7 @@ where
         unsafe { Self::new_generic(pointer) }
     }
 
-    pub(super) const unsafe fn new_generic<A>(pointer: NonNull<T>) -> VolatilePtr<'a, T, A> {
+    pub(crate) const unsafe fn new_generic<A>(pointer: NonNull<T>) -> VolatilePtr<'a, T, A> {
         VolatilePtr {
             pointer,
             reference: PhantomData,
@@ -82,12 +155,14 @@ where
     /// };
     /// assert_eq!(pointer.read(), 42);
     /// ```
+    #[inline(always)]
+    #[track_caller]
     pub fn read(self) -> T
     where
         T: Copy,
         A: Readable,
     {
-        unsafe { ptr::read_volatile(self.pointer.as_ptr()) }
+        unsafe { backend::read(self.pointer.as_ptr()) }
     }
 
     /// Performs a volatile write, setting the contained value to the given `value`.
@@ -107,12 +182,14 @@ where
     ///
     /// assert_eq!(volatile.read(), 50);
     /// ```
+    #[inline(always)]
+    #[track_caller]
     pub fn write(self, value: T)
     where
         T: Copy,
         A: Writable,
     {
-        unsafe { ptr::write_volatile(self.pointer.as_ptr(), value) };
+        unsafe { backend::write(self.pointer.as_ptr(), value) };
     }
 
     /// Updates the contained value using the given closure and volatile instructions.
@@ -130,6 +207,8 @@ where
     ///
     /// assert_eq!(volatile.read(), 43);
     /// ```
+    #[inline(always)]
+    #[track_caller]
     pub fn update<F>(self, f: F)
     where
         T: Copy,
@@ -140,6 +219,38 @@ where
         self.write(new);
     }
 
+    /// Writes the given value and verifies it by reading it back.
+    ///
+    /// This is useful for registers that drivers are required to verify after configuration
+    /// writes, e.g., registers backed by flash or EEPROM. The written value stays in place if the
+    /// verification fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{Mismatch, VolatilePtr};
+    ///
+    /// let mut value = 0_u32;
+    /// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+    /// if let Err(Mismatch { wrote, read }) = volatile.write_verified(42) {
+    ///     panic!("wrote {wrote:#x} but read {read:#x}");
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn write_verified(self, value: T) -> Result<(), Mismatch<T>>
+    where
+        T: Copy + PartialEq,
+        A: Readable + Writable,
+    {
+        self.write(value);
+        let read = self.read();
+        if read == value {
+            Ok(())
+        } else {
+            Err(Mismatch { wrote: value, read })
+        }
+    }
+
     /// Extracts the wrapped raw pointer.
     ///
     /// ## Example
@@ -154,10 +265,50 @@ where
     ///
     /// assert_eq!(unsafe { *unwrapped }, 50); // non volatile access, be careful!
     /// ```
-    pub fn as_raw_ptr(self) -> NonNull<T> {
+    pub const fn as_raw_ptr(self) -> NonNull<T> {
         self.pointer
     }
 
+    /// Returns the address of the wrapped pointer without exposing its provenance.
+    pub fn addr(self) -> NonZeroUsize {
+        self.pointer.addr()
+    }
+
+    /// Returns the address of the wrapped pointer and exposes its provenance.
+    ///
+    /// The address can be turned back into a pointer using [`Self::from_exposed_addr`].
+    pub fn expose_addr(self) -> usize {
+        self.pointer.as_ptr().expose_provenance()
+    }
+
+    /// Creates a `VolatilePtr` with the given address and the provenance of `self`.
+    ///
+    /// This follows the strict provenance model and is preferred over
+    /// [`Self::from_exposed_addr`] whenever the new address is derived from an existing
+    /// pointer, e.g., a register within a mapped MMIO window.
+    ///
+    /// ## Safety
+    ///
+    /// The resulting pointer must satisfy the requirements of [`Self::new`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut registers = [1_u32, 2];
+    /// let first = unsafe { VolatilePtr::new((&mut registers).into()).map_offset::<u32>(0) };
+    ///
+    /// let second = unsafe { first.with_addr(first.addr().checked_add(4).unwrap()) };
+    /// assert_eq!(second.read(), 2);
+    /// ```
+    pub unsafe fn with_addr(self, addr: NonZeroUsize) -> Self
+    where
+        A: Access,
+    {
+        unsafe { self.map(|ptr| ptr.with_addr(addr)) }
+    }
+
     /// Constructs a new `VolatilePtr` by mapping the wrapped pointer.
     ///
     /// This method is useful for accessing only a part of a volatile value, e.g. a subslice or
@@ -208,6 +359,84 @@ where
     {
         unsafe { VolatilePtr::new_restricted(A::default(), f(self.pointer)) }
     }
+
+    /// Constructs a new `VolatilePtr` to the value of type `U` at byte offset `offset`.
+    ///
+    /// Unlike [`Self::map`], this method can be used in `const` initializers, e.g., for
+    /// register maps that are built at compile time.
+    ///
+    /// ## Safety
+    ///
+    /// The resulting pointer must satisfy the requirements of [`Self::new`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut registers = [1_u32, 2, 3];
+    /// let volatile = unsafe { VolatilePtr::new((&mut registers).into()) };
+    ///
+    /// let third = unsafe { volatile.map_offset::<u32>(8) };
+    /// assert_eq!(third.read(), 3);
+    /// ```
+    pub const unsafe fn map_offset<U>(self, offset: usize) -> VolatilePtr<'a, U, A> {
+        // Use wrapping arithmetic since addresses created by `from_addr` have no provenance
+        // during const evaluation.
+        let pointer = self.pointer.as_ptr().cast::<u8>().wrapping_add(offset);
+        unsafe { VolatilePtr::new_generic(NonNull::new_unchecked(pointer).cast()) }
+    }
+}
+
+/// Methods for reborrowing.
+impl<T, A> VolatilePtr<'_, T, A>
+where
+    T: ?Sized,
+{
+    /// Reborrows this `VolatilePtr` with a lifetime that is tied to `&self`.
+    ///
+    /// This is useful for lending the pointer to a callback or storing it in a short-lived
+    /// struct without giving away the original lifetime `'a`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// fn with_register(f: impl FnOnce(VolatilePtr<'_, u32>)) {
+    ///     let mut value = 0;
+    ///     let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+    ///     f(volatile.as_ptr());
+    ///     assert_eq!(volatile.read(), 1);
+    /// }
+    ///
+    /// with_register(|register| register.write(1));
+    /// ```
+    pub const fn as_ptr(&self) -> VolatilePtr<'_, T, A> {
+        unsafe { VolatilePtr::new_generic(self.pointer) }
+    }
+
+    /// Reborrows this `VolatilePtr` with a lifetime that is tied to `&self` and access
+    /// permissions restricted to `To`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::access::ReadOnly;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut value = 42_u32;
+    /// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+    /// let status = volatile.as_restricted_ptr::<ReadOnly>();
+    /// assert_eq!(status.read(), 42);
+    /// // status.write(1); // compile-time error
+    /// ```
+    pub const fn as_restricted_ptr<To>(&self) -> VolatilePtr<'_, T, A::Restricted>
+    where
+        A: RestrictAccess<To>,
+    {
+        unsafe { VolatilePtr::new_generic(self.pointer) }
+    }
 }
 
 /// Methods for restricting access.
@@ -234,21 +463,17 @@ where
     /// // no_access.read(); // compile-time error
     /// // no_access.write(10); // compile-time error
     /// ```
-    pub fn restrict<To>(self) -> VolatilePtr<'a, T, A::Restricted>
+    pub const fn restrict<To>(self) -> VolatilePtr<'a, T, A::Restricted>
     where
         A: RestrictAccess<To>,
     {
-        unsafe { VolatilePtr::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatilePtr::new_generic(self.pointer) }
     }
-}
 
-/// Methods for restricting access.
-impl<'a, T> VolatilePtr<'a, T, ReadWrite>
-where
-    T: ?Sized,
-{
     /// Restricts access permissions to read-only.
     ///
+    /// This is a shorthand for `restrict::<ReadOnly>()` and requires read access.
+    ///
     /// ## Example
     ///
     /// ```
@@ -261,12 +486,17 @@ where
     /// assert_eq!(read_only.read(), -4);
     /// // read_only.write(10); // compile-time error
     /// ```
-    pub fn read_only(self) -> VolatilePtr<'a, T, ReadOnly> {
+    pub const fn read_only(self) -> VolatilePtr<'a, T, ReadOnly>
+    where
+        A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+    {
         self.restrict()
     }
 
     /// Restricts access permissions to write-only.
     ///
+    /// This is a shorthand for `restrict::<WriteOnly>()` and requires write access.
+    ///
     /// ## Example
     ///
     /// Creating a write-only pointer to a struct field:
@@ -283,7 +513,10 @@ where
     /// field_2.write(14);
     /// // field_2.read(); // compile-time error
     /// ```
-    pub fn write_only(self) -> VolatilePtr<'a, T, WriteOnly> {
+    pub const fn write_only(self) -> VolatilePtr<'a, T, WriteOnly>
+    where
+        A: RestrictAccess<WriteOnly, Restricted = WriteOnly>,
+    {
         self.restrict()
     }
 }
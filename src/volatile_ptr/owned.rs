@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+
+use crate::{
+    access::{Readable, Writable},
+    backend, VolatilePtr,
+};
+
+/// Methods for copying volatile slices from and to owned buffers.
+///
+/// These methods are only available with the `alloc` feature enabled.
+impl<T, A> VolatilePtr<'_, [T], A>
+where
+    T: Copy,
+{
+    /// Copies all elements of `self` into a new `Vec` using volatile reads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::ptr::NonNull;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut array = [1, 2, 3];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    /// assert_eq!(volatile.to_vec(), vec![1, 2, 3]);
+    /// ```
+    pub fn to_vec(self) -> Vec<T>
+    where
+        A: Readable,
+    {
+        let mut vec = Vec::new();
+        self.extend_vec(&mut vec);
+        vec
+    }
+
+    /// Appends all elements of `self` to `vec` using volatile reads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::ptr::NonNull;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut array = [3, 4];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    ///
+    /// let mut vec = vec![1, 2];
+    /// volatile.extend_vec(&mut vec);
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    pub fn extend_vec(self, vec: &mut Vec<T>)
+    where
+        A: Readable,
+    {
+        let len = self.pointer.len();
+        vec.reserve(len);
+        let base = self.pointer.as_ptr().cast::<T>();
+        for i in 0..len {
+            // SAFETY: The element is within the slice.
+            vec.push(unsafe { backend::read(base.add(i)) });
+        }
+    }
+
+    /// Copies all elements from `src` into `self` using volatile writes.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `src` and `self` differ in length.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use core::ptr::NonNull;
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut array = [0; 3];
+    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    /// volatile.copy_from_vec(vec![1, 2, 3]);
+    /// assert_eq!(array, [1, 2, 3]);
+    /// ```
+    pub fn copy_from_vec(self, src: Vec<T>)
+    where
+        A: Writable,
+    {
+        let len = self.pointer.len();
+        assert_eq!(
+            len,
+            src.len(),
+            "destination and source slices have different lengths"
+        );
+        let base = self.pointer.as_ptr().cast::<T>();
+        for (i, value) in src.into_iter().enumerate() {
+            // SAFETY: The element is within the slice.
+            unsafe { backend::write(base.add(i), value) };
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     access::{ReadOnly, ReadWrite, WriteOnly},
-    map_field, VolatilePtr,
+    map_field, map_fields, VolatilePtr,
 };
 use core::ptr::NonNull;
 
@@ -110,6 +110,119 @@ fn test_struct_macro() {
     );
 }
 
+#[test]
+fn test_nested_struct_macro() {
+    #[derive(Debug, PartialEq)]
+    struct Inner {
+        field_1: u16,
+        field_2: u64,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Outer {
+        field_1: u32,
+        inner: Inner,
+    }
+
+    let mut val = Outer {
+        field_1: 60,
+        inner: Inner {
+            field_1: 1,
+            field_2: 2,
+        },
+    };
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    map_field!(volatile.inner.field_2).update(|v| v + 1);
+    assert_eq!(map_field!(volatile.inner.field_1).read(), 1);
+    assert_eq!(
+        val,
+        Outer {
+            field_1: 60,
+            inner: Inner {
+                field_1: 1,
+                field_2: 3
+            }
+        }
+    );
+}
+
+#[test]
+fn test_tuple_struct_macro() {
+    #[derive(Debug, PartialEq)]
+    struct Newtype(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct S(u8, Newtype, (u16, u64));
+
+    let mut val = S(1, Newtype(2), (3, 4));
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    let newtype = map_field!(volatile.1);
+    map_field!(newtype.0).update(|v| v + 1);
+    map_field!(volatile.2 .1).write(5);
+    assert_eq!(map_field!(volatile.0).read(), 1);
+    assert_eq!(map_field!(volatile.1 .0).read(), 3);
+    assert_eq!(val, S(1, Newtype(3), (3, 5)));
+}
+
+#[test]
+fn test_map_fields_macro() {
+    struct S {
+        field_1: u32,
+        field_2: bool,
+        field_3: (u8, u16),
+    }
+
+    let mut val = S {
+        field_1: 60,
+        field_2: true,
+        field_3: (1, 2),
+    };
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    let (field_1, field_3) = map_fields!(volatile => field_1, field_3);
+    let (a, b) = map_fields!(field_3 => 0, 1,);
+    field_1.update(|v| v + 1);
+    b.write(u16::from(a.read()) + 1);
+    let (field_2,) = map_fields!(volatile => field_2);
+    assert!(field_2.read());
+    assert_eq!(val.field_1, 61);
+    assert_eq!(val.field_3, (1, 2));
+}
+
+#[test]
+fn test_array_index_macro() {
+    struct S {
+        field_1: u32,
+        field_2: [[u16; 3]; 2],
+    }
+
+    let mut val = S {
+        field_1: 60,
+        field_2: [[1, 2, 3], [4, 5, 6]],
+    };
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    for i in 0..2 {
+        for j in 0..3 {
+            map_field!(volatile.field_2[i][j]).update(|v| v * 10);
+        }
+    }
+    assert_eq!(map_field!(volatile.field_2[1][0]).read(), 40);
+    assert_eq!(map_field!(volatile.field_1).read(), 60);
+    assert_eq!(val.field_2, [[10, 20, 30], [40, 50, 60]]);
+}
+
+#[test]
+#[should_panic]
+fn test_array_index_macro_out_of_bounds() {
+    struct S {
+        field: [u16; 3],
+    }
+
+    let mut val = S { field: [1, 2, 3] };
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    let index = 3;
+    map_field!(volatile.field[index]).read();
+}
+
 #[cfg(feature = "unstable")]
 #[test]
 fn test_slice() {
@@ -177,3 +290,420 @@ fn test_chunks() {
     assert_eq!(chunks.index(0).read(), [1, 2, 3]);
     assert_eq!(chunks.index(1).read(), [10, 11, 12]);
 }
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_slice_copy_within_and_fill() {
+    let val: &mut [u16] = &mut [1, 2, 3, 4, 5];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut *val)) };
+    volatile.copy_within(0..3, 2);
+    assert_eq!(val, [1, 2, 1, 2, 3]);
+    volatile.copy_within(2..5, 1);
+    assert_eq!(val, [1, 1, 2, 3, 3]);
+    volatile.copy_from_slice(&[5, 4, 3, 2, 1]);
+    assert_eq!(val, [5, 4, 3, 2, 1]);
+
+    let bytes: &mut [u8] = &mut [1, 2, 3];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut *bytes)) };
+    volatile.index(1..).fill(0xff);
+    assert_eq!(bytes, [1, 0xff, 0xff]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_to_vec() {
+    let mut array = [1_u32, 2, 3];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    assert_eq!(volatile.to_vec(), [1, 2, 3]);
+    volatile.copy_from_vec(alloc::vec![4, 5, 6]);
+    let mut vec = alloc::vec![0];
+    volatile.read_only().extend_vec(&mut vec);
+    assert_eq!(vec, [0, 4, 5, 6]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn test_copy_from_vec_length_mismatch() {
+    let mut array = [1_u32, 2, 3];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array[..])) };
+    volatile.copy_from_vec(alloc::vec![4, 5]);
+}
+
+#[test]
+fn test_const_register_map() {
+    use crate::{
+        access::{ReadOnly, WriteOnly},
+        register::RegisterAt,
+        VolatileRef,
+    };
+
+    struct Uart {
+        data: VolatileRef<'static, u32, WriteOnly>,
+        status: VolatileRef<'static, u32, ReadOnly>,
+    }
+
+    static UART: Uart = unsafe {
+        Uart {
+            data: VolatileRef::from_addr(0x1000).write_only(),
+            status: VolatileRef::from_addr(0x1004).read_only(),
+        }
+    };
+    const BASE: VolatilePtr<'static, [u32; 4]> = unsafe { VolatilePtr::from_addr(0x2000) };
+    const CONTROL: VolatilePtr<'static, u32, WriteOnly> =
+        RegisterAt::<u32, 8, WriteOnly>::new().resolve(BASE);
+
+    assert_eq!(UART.data.as_ptr().as_raw_ptr().as_ptr().addr(), 0x1000);
+    assert_eq!(UART.status.as_ptr().as_raw_ptr().as_ptr().addr(), 0x1004);
+    assert_eq!(CONTROL.as_raw_ptr().as_ptr().addr(), 0x2008);
+}
+
+#[test]
+fn test_copy_word_wide() {
+    let mut buf = [0_u8; 67];
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let expected = buf;
+
+    for offset in 0..9 {
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut buf[offset..offset + 58])) };
+        let mut dst = [0_u8; 58];
+        volatile.copy_into_slice(&mut dst);
+        assert_eq!(dst[..], expected[offset..offset + 58]);
+    }
+
+    let mut dst = [0_u8; 67];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst[3..])) };
+    volatile.copy_from_slice(&expected[..64]);
+    assert_eq!(dst[3..], expected[..64]);
+
+    let mut within = expected;
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut within[..])) };
+    volatile.copy_within(1..60, 5);
+    let mut reference = expected;
+    reference.copy_within(1..60, 5);
+    assert_eq!(within, reference);
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut within[..])) };
+    volatile.copy_within(9.., 1);
+    reference.copy_within(9.., 1);
+    assert_eq!(within, reference);
+
+    let words: [u32; 9] = core::array::from_fn(|i| i as u32 * 0x0101_0101);
+    let mut dst = [0_u32; 9];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst[1..])) };
+    volatile.copy_from_slice(&words[..8]);
+    assert_eq!(dst[1..], words[..8]);
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst[..])) };
+    volatile.copy_within(1.., 0);
+    assert_eq!(dst[..8], words[..8]);
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_copy_element_width() {
+    use crate::backend::{mock::MockBackend, with_backend};
+
+    #[repr(C, align(8))]
+    struct Aligned([u32; 9]);
+
+    let src = Aligned(core::array::from_fn(|i| i as u32));
+    let mut dst = Aligned([0; 9]);
+    let mock = MockBackend::new();
+
+    // The source starts at 4 modulo 8, so the copy cannot use word-wide accesses.
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst.0[..8])) };
+    unsafe { with_backend(&mock, || volatile.copy_from_slice(&src.0[1..])) };
+    let accesses = mock.take_accesses();
+    assert_eq!(accesses.len(), 8);
+    assert!(accesses.iter().all(|access| access.data.len() == 4));
+    assert_eq!(dst.0[..8], src.0[1..]);
+
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst.0[..])) };
+    unsafe { with_backend(&mock, || volatile.copy_within(..8, 1)) };
+    let accesses = mock.take_accesses();
+    assert_eq!(accesses.len(), 16);
+    assert!(accesses.iter().all(|access| access.data.len() == 4));
+    assert_eq!(dst.0, [1, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let mut bytes = [0_u8; 4];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut bytes[..])) };
+    unsafe { with_backend(&mock, || volatile.copy_from_slice(&[1, 2, 3, 4])) };
+    assert!(mock
+        .take_accesses()
+        .iter()
+        .all(|access| access.data.len() == 1));
+}
+
+#[test]
+fn test_fill_bytes() {
+    let mut buf = [0_u8; 67];
+    for offset in 0..9 {
+        buf.fill(0);
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut buf[offset..offset + 50])) };
+        volatile.fill_bytes(0x5a);
+        assert!(buf[..offset].iter().all(|&byte| byte == 0));
+        assert!(buf[offset..offset + 50].iter().all(|&byte| byte == 0x5a));
+        assert!(buf[offset + 50..].iter().all(|&byte| byte == 0));
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic = "misaligned volatile access"]
+fn test_misaligned_access() {
+    let mut buf = [0_u32; 2];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut buf)) };
+    let misaligned = unsafe { volatile.map(|ptr| ptr.cast::<u32>().byte_add(1)) };
+    misaligned.read();
+}
+
+#[test]
+fn test_zero_sized() {
+    let volatile = unsafe { VolatilePtr::new(NonNull::<()>::dangling()) };
+    volatile.write(());
+    volatile.read();
+
+    let mut empty: [[u32; 0]; 4] = [[]; 4];
+    let slice = unsafe { VolatilePtr::new(NonNull::from(&mut empty[..])) };
+    slice.copy_from_slice(&[[]; 4]);
+    let mut dst = [[]; 4];
+    slice.copy_into_slice(&mut dst);
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_zero_sized_backend() {
+    use crate::backend::{mock::MockBackend, with_backend};
+
+    let volatile = unsafe { VolatilePtr::new(NonNull::<()>::dangling()) };
+    let mock = MockBackend::new();
+    unsafe {
+        with_backend(&mock, || {
+            volatile.write(());
+            volatile.read();
+        })
+    };
+    assert!(mock.take_accesses().is_empty());
+}
+
+#[test]
+fn test_provenance() {
+    let mut array = [1_u32, 2, 3];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut array)).map_offset::<u32>(0) };
+
+    let third = unsafe { volatile.with_addr(volatile.addr().checked_add(8).unwrap()) };
+    assert_eq!(third.read(), 3);
+
+    let exposed = unsafe { VolatilePtr::<u32>::from_exposed_addr(third.expose_addr()) };
+    exposed.write(4);
+    assert_eq!(array, [1, 2, 4]);
+}
+
+#[test]
+fn test_sum_bytes_wrapping() {
+    let mut table = [0_u8; 200];
+    for (i, byte) in table.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let expected = table.iter().fold(0_u8, |sum, &byte| sum.wrapping_add(byte));
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut table[..])) };
+    assert_eq!(volatile.sum_bytes_wrapping(), expected);
+
+    let first = unsafe { volatile.map(|ptr| ptr.cast::<u8>()) };
+    first.write(0_u8.wrapping_sub(expected));
+    assert_eq!(volatile.sum_bytes_wrapping(), 0);
+}
+
+#[test]
+fn test_find() {
+    let mut region = [0_u8; 100];
+    region[10..14].copy_from_slice(b"_MP_");
+    region[48..52].copy_from_slice(b"_MP_");
+    region[96..].copy_from_slice(b"_MP_");
+    let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(&region[..])) };
+
+    assert_eq!(volatile.find(b"_MP_"), Some(10));
+    assert_eq!(
+        volatile.find(b"_MP_\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"),
+        Some(10)
+    );
+    assert_eq!(volatile.find(b""), Some(0));
+    assert_eq!(volatile.find(&[1; 101]), None);
+    assert_eq!(volatile.find_aligned(b"_MP_", 16), Some(48));
+    assert_eq!(volatile.find_aligned(b"_MP_", 32), Some(96));
+    assert_eq!(volatile.find_aligned(b"_MP_", 64), None);
+}
+
+#[test]
+fn test_increment_decrement() {
+    let mut counter = 254_u8;
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut counter)) };
+    assert_eq!(volatile.increment(1), 254);
+    assert_eq!(volatile.increment(3), 255);
+    assert_eq!(volatile.decrement(5), 2);
+    assert_eq!(counter, 253);
+
+    let mut signed = i32::MIN;
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut signed)) };
+    assert_eq!(volatile.decrement(1), i32::MIN);
+    assert_eq!(signed, i32::MAX);
+}
+
+#[test]
+fn test_from_references() {
+    use crate::{access::ReadOnly, VolatileRef};
+
+    fn increment<'a>(register: impl Into<VolatileRef<'a, u32>>) {
+        register.into().as_mut_ptr().update(|value| value + 1);
+    }
+
+    let mut val = 1_u32;
+    increment(&mut val);
+    assert_eq!(val, 2);
+
+    let volatile: VolatilePtr<'_, u32, ReadOnly> = (&val).into();
+    assert_eq!(volatile.read(), 2);
+    let volatile: VolatileRef<'_, [u32], ReadOnly> = VolatileRef::from(&[1, 2][..]);
+    assert_eq!(volatile.as_ptr().as_raw_ptr().len(), 2);
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_write_verified() {
+    use crate::{
+        backend::{mock::MockBackend, with_backend},
+        Mismatch,
+    };
+    use std::string::ToString;
+
+    let mut val = 0_u16;
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    assert_eq!(volatile.write_verified(0x1234), Ok(()));
+    assert_eq!(volatile.read(), 0x1234);
+
+    let mock = MockBackend::new();
+    mock.push_read(volatile.as_raw_ptr().as_ptr(), &0x00ff_u16.to_ne_bytes());
+    let result = unsafe { with_backend(&mock, || volatile.write_verified(0xffff)) };
+    assert_eq!(
+        result,
+        Err(Mismatch {
+            wrote: 0xffff,
+            read: 0x00ff
+        })
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "wrote 65535 but read back 255"
+    );
+}
+
+#[test]
+fn test_narrowing() {
+    use crate::{
+        access::{ReadOnly, WriteOnly},
+        VolatileRef,
+    };
+
+    let mut val = 1_u32;
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+    let status: VolatilePtr<'_, u32, ReadOnly> = volatile.read_only().read_only();
+    let command: VolatilePtr<'_, u32, WriteOnly> = volatile.write_only().write_only();
+    command.write(2);
+    assert_eq!(status.read(), 2);
+
+    let volatile = VolatileRef::from_mut_ref(&mut val);
+    let status: VolatileRef<'_, u32, ReadOnly> = volatile.read_only().read_only();
+    assert_eq!(status.as_ptr().read(), 2);
+}
+
+#[test]
+fn test_reborrow() {
+    use crate::access::{NoAccess, ReadOnly, WriteOnly};
+
+    struct Lender<'a> {
+        register: VolatilePtr<'a, u32>,
+    }
+
+    impl Lender<'_> {
+        fn lend<R>(&self, f: impl FnOnce(VolatilePtr<'_, u32>) -> R) -> R {
+            f(self.register.as_ptr())
+        }
+    }
+
+    let mut val = 0_u32;
+    let lender = Lender {
+        register: unsafe { VolatilePtr::new(NonNull::from(&mut val)) },
+    };
+    lender.lend(|register| register.write(3));
+    assert_eq!(lender.lend(|register| register.read()), 3);
+
+    let read_only: VolatilePtr<'_, u32, ReadOnly> = lender.register.as_restricted_ptr::<ReadOnly>();
+    assert_eq!(read_only.read(), 3);
+    let _: VolatilePtr<'_, u32, NoAccess> = read_only.as_restricted_ptr::<WriteOnly>();
+}
+
+#[test]
+fn test_copy_to() {
+    fn sub<'a>(
+        volatile: VolatilePtr<'a, [u16]>,
+        start: usize,
+        len: usize,
+    ) -> VolatilePtr<'a, [u16]> {
+        assert!(start + len <= volatile.as_raw_ptr().len());
+        unsafe {
+            volatile.map(|ptr| NonNull::slice_from_raw_parts(ptr.cast::<u16>().add(start), len))
+        }
+    }
+
+    let mut val: [u16; 64] = core::array::from_fn(|i| i as u16);
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val[..])) };
+
+    // destination after the overlapping source
+    sub(volatile, 0, 40).copy_to(sub(volatile, 10, 40));
+    let mut expected: [u16; 64] = core::array::from_fn(|i| i as u16);
+    expected.copy_within(0..40, 10);
+    let mut actual = [0; 64];
+    volatile.copy_into_slice(&mut actual);
+    assert_eq!(actual, expected);
+
+    // destination before the overlapping source
+    sub(volatile, 20, 30).copy_to(sub(volatile, 3, 30));
+    expected.copy_within(20..50, 3);
+    volatile.copy_into_slice(&mut actual);
+    assert_eq!(actual, expected);
+
+    let mut other = [0_u16; 16];
+    let dst = unsafe { VolatilePtr::new(NonNull::from(&mut other[..])) };
+    unsafe { sub(volatile, 48, 16).copy_to_nonoverlapping(dst.write_only()) };
+    assert_eq!(other[..], expected[48..]);
+}
+
+#[test]
+#[should_panic(expected = "destination and source slices have different lengths")]
+fn test_copy_to_length_mismatch() {
+    let mut src = [0_u8; 4];
+    let mut dst = [0_u8; 5];
+    let src = unsafe { VolatilePtr::new_read_only(NonNull::from(&mut src[..])) };
+    let dst = unsafe { VolatilePtr::new(NonNull::from(&mut dst[..])) };
+    src.copy_to(dst);
+}
+
+#[test]
+fn test_index_const() {
+    let mut val = [[1_u8, 2], [3, 4], [5, 6]];
+    let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut val)) };
+
+    const fn second<'a>(bank: VolatilePtr<'a, [[u8; 2]; 3]>) -> VolatilePtr<'a, u8> {
+        bank.index_const::<1>().index_const::<1>()
+    }
+
+    second(volatile).write(40);
+    assert_eq!(volatile.index_const::<2>().read(), [5, 6]);
+    volatile.range_const::<1, 2>().write([[7, 8], [9, 10]]);
+    assert_eq!(
+        volatile.range_const::<0, 3>().read(),
+        [[1, 2], [7, 8], [9, 10]]
+    );
+    assert_eq!(volatile.range_const::<3, 0>().read(), [[0_u8; 2]; 0]);
+}
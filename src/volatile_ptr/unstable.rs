@@ -1,12 +1,10 @@
 use core::{
-    intrinsics,
-    ops::{Range, RangeBounds},
     ptr::{self, NonNull},
-    slice::{range, SliceIndex},
+    slice::SliceIndex,
 };
 
 use crate::{
-    access::{Access, Readable, Writable},
+    access::{Access, Writable},
     VolatilePtr,
 };
 
@@ -76,171 +74,6 @@ impl<'a, T, A> VolatilePtr<'a, [T], A> {
             .map(move |i| unsafe { VolatilePtr::new_generic(NonNull::new_unchecked(ptr.add(i))) })
     }
 
-    /// Copies all elements from `self` into `dst`, using a volatile memcpy.
-    ///
-    /// The length of `dst` must be the same as `self`.
-    ///
-    /// The method is only available with the `unstable` feature enabled (requires a nightly
-    /// Rust compiler).
-    ///
-    /// ## Panics
-    ///
-    /// This function will panic if the two slices have different lengths.
-    ///
-    /// ## Examples
-    ///
-    /// Copying two elements from a volatile slice:
-    ///
-    /// ```
-    /// use volatile::VolatilePtr;
-    /// use core::ptr::NonNull;
-    ///
-    /// let src = [1, 2];
-    /// // the `Volatile` type does not work with arrays, so convert `src` to a slice
-    /// let slice = &src[..];
-    /// let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(slice)) };
-    /// let mut dst = [5, 0, 0];
-    ///
-    /// // Because the slices have to be the same length,
-    /// // we slice the destination slice from three elements
-    /// // to two. It will panic if we don't do this.
-    /// volatile.copy_into_slice(&mut dst[1..]);
-    ///
-    /// assert_eq!(src, [1, 2]);
-    /// assert_eq!(dst, [5, 1, 2]);
-    /// ```
-    pub fn copy_into_slice(self, dst: &mut [T])
-    where
-        T: Copy,
-        A: Readable,
-    {
-        let len = self.pointer.len();
-        assert_eq!(
-            len,
-            dst.len(),
-            "destination and source slices have different lengths"
-        );
-        unsafe {
-            intrinsics::volatile_copy_nonoverlapping_memory(
-                dst.as_mut_ptr(),
-                self.pointer.as_mut_ptr(),
-                len,
-            );
-        }
-    }
-
-    /// Copies all elements from `src` into `self`, using a volatile memcpy.
-    ///
-    /// The length of `src` must be the same as `self`.
-    ///
-    /// This method is similar to the `slice::copy_from_slice` method of the standard library. The
-    /// difference is that this method performs a volatile copy.
-    ///
-    /// The method is only available with the `unstable` feature enabled (requires a nightly
-    /// Rust compiler).
-    ///
-    /// ## Panics
-    ///
-    /// This function will panic if the two slices have different lengths.
-    ///
-    /// ## Examples
-    ///
-    /// Copying two elements from a slice into a volatile slice:
-    ///
-    /// ```
-    /// use volatile::VolatilePtr;
-    /// use core::ptr::NonNull;
-    ///
-    /// let src = [1, 2, 3, 4];
-    /// let mut dst = [0, 0];
-    /// // the `Volatile` type does not work with arrays, so convert `dst` to a slice
-    /// let slice = &mut dst[..];
-    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(slice)) };
-    /// // Because the slices have to be the same length,
-    /// // we slice the source slice from four elements
-    /// // to two. It will panic if we don't do this.
-    /// volatile.copy_from_slice(&src[2..]);
-    ///
-    /// assert_eq!(src, [1, 2, 3, 4]);
-    /// assert_eq!(dst, [3, 4]);
-    /// ```
-    pub fn copy_from_slice(self, src: &[T])
-    where
-        T: Copy,
-        A: Writable,
-    {
-        let len = self.pointer.len();
-        assert_eq!(
-            len,
-            src.len(),
-            "destination and source slices have different lengths"
-        );
-        unsafe {
-            intrinsics::volatile_copy_nonoverlapping_memory(
-                self.pointer.as_mut_ptr(),
-                src.as_ptr(),
-                len,
-            );
-        }
-    }
-
-    /// Copies elements from one part of the slice to another part of itself, using a
-    /// volatile `memmove`.
-    ///
-    /// `src` is the range within `self` to copy from. `dest` is the starting index of the
-    /// range within `self` to copy to, which will have the same length as `src`. The two ranges
-    /// may overlap. The ends of the two ranges must be less than or equal to `self.len()`.
-    ///
-    /// This method is similar to the `slice::copy_within` method of the standard library. The
-    /// difference is that this method performs a volatile copy.
-    ///
-    /// This method is only available with the `unstable` feature enabled (requires a nightly
-    /// Rust compiler).
-    ///
-    /// ## Panics
-    ///
-    /// This function will panic if either range exceeds the end of the slice, or if the end
-    /// of `src` is before the start.
-    ///
-    /// ## Examples
-    ///
-    /// Copying four bytes within a slice:
-    ///
-    /// ```
-    /// extern crate core;
-    /// use volatile::VolatilePtr;
-    /// use core::ptr::NonNull;
-    ///
-    /// let mut byte_array = *b"Hello, World!";
-    /// let slice: &mut [u8] = &mut byte_array[..];
-    /// let volatile = unsafe { VolatilePtr::new(NonNull::from(slice)) };
-    /// volatile.copy_within(1..5, 8);
-    ///
-    /// assert_eq!(&byte_array, b"Hello, Wello!");
-    pub fn copy_within(self, src: impl RangeBounds<usize>, dest: usize)
-    where
-        T: Copy,
-        A: Readable + Writable,
-    {
-        let len = self.pointer.len();
-        // implementation taken from https://github.com/rust-lang/rust/blob/683d1bcd405727fcc9209f64845bd3b9104878b8/library/core/src/slice/mod.rs#L2726-L2738
-        let Range {
-            start: src_start,
-            end: src_end,
-        } = range(src, ..len);
-        let count = src_end - src_start;
-        assert!(dest <= len - count, "dest is out of bounds");
-        // SAFETY: the conditions for `volatile_copy_memory` have all been checked above,
-        // as have those for `ptr::add`.
-        unsafe {
-            intrinsics::volatile_copy_memory(
-                self.pointer.as_mut_ptr().add(dest),
-                self.pointer.as_mut_ptr().add(src_start),
-                count,
-            );
-        }
-    }
-
     /// Divides one slice into two at an index.
     ///
     /// The first will contain all indices from `[0, mid)` (excluding
@@ -337,6 +170,8 @@ impl<A> VolatilePtr<'_, [u8], A> {
     /// is that this method is only available for byte slices (not general `&mut [T]` slices)
     /// because there currently isn't a instrinsic function that allows non-`u8` values.
     ///
+    /// This is the same as [`fill_bytes`](Self::fill_bytes), which is also available on stable.
+    ///
     /// This method is only available with the `unstable` feature enabled (requires a nightly
     /// Rust compiler).
     ///
@@ -355,9 +190,7 @@ impl<A> VolatilePtr<'_, [u8], A> {
     where
         A: Writable,
     {
-        unsafe {
-            intrinsics::volatile_set_memory(self.pointer.as_mut_ptr(), value, self.pointer.len());
-        }
+        self.fill_bytes(value);
     }
 }
 
@@ -55,10 +55,56 @@ where
     ///   chosen and does not necessarily reflect the actual lifetime of the data. In particular,
     ///   while this `VolatileRef` exists, the memory the pointer points to must not get accessed
     ///   (_read or written_) through any other pointer.
-    pub unsafe fn new(pointer: NonNull<T>) -> Self {
+    pub const unsafe fn new(pointer: NonNull<T>) -> Self {
         unsafe { VolatileRef::new_restricted(ReadWrite, pointer) }
     }
 
+    /// Creates a `VolatileRef` from the given address, e.g., of a memory-mapped register.
+    ///
+    /// Since `VolatileRef` is [`Sync`] and this function can be used in `const` initializers,
+    /// this allows building register maps as `static` items at compile time.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `addr` is zero.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements for [`Self::new`] apply to this function too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{
+    ///     access::{ReadOnly, WriteOnly},
+    ///     VolatileRef,
+    /// };
+    ///
+    /// struct Uart {
+    ///     data: VolatileRef<'static, u32, WriteOnly>,
+    ///     status: VolatileRef<'static, u32, ReadOnly>,
+    /// }
+    ///
+    /// const UART_BASE: usize = 0x1000_0000;
+    ///
+    /// static UART: Uart = unsafe {
+    ///     Uart {
+    ///         data: VolatileRef::from_addr(UART_BASE).write_only(),
+    ///         status: VolatileRef::from_addr(UART_BASE + 0x14).read_only(),
+    ///     }
+    /// };
+    /// # assert_eq!(UART.status.as_ptr().as_raw_ptr().as_ptr() as usize, 0x1000_0014);
+    /// ```
+    pub const unsafe fn from_addr(addr: usize) -> Self
+    where
+        T: Sized,
+    {
+        match NonNull::new(addr as *mut T) {
+            Some(pointer) => unsafe { VolatileRef::new(pointer) },
+            None => panic!("address must not be zero"),
+        }
+    }
+
     /// Turns the given pointer into a read-only `VolatileRef`.
     ///
     /// ## Safety
@@ -139,11 +185,11 @@ where
     /// This method creates a `VolatileRef` tied to the lifetime of the `&VolatileRef` it is created from.
     /// This is useful for providing a volatile reference without moving the original `VolatileRef`.
     /// In comparison with creating a `&VolatileRef<'a, T>`, this avoids the additional indirection and lifetime.
-    pub fn borrow(&self) -> VolatileRef<'_, T, A::Restricted>
+    pub const fn borrow(&self) -> VolatileRef<'_, T, A::Restricted>
     where
         A: RestrictAccess<ReadOnly>,
     {
-        unsafe { VolatileRef::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatileRef::new_generic(self.pointer) }
     }
 
     /// Mutably borrows from this `VolatileRef`.
@@ -151,31 +197,31 @@ where
     /// This method creates a `VolatileRef` tied to the lifetime of the `&mut VolatileRef` it is created from.
     /// This is useful for providing a volatile reference without moving the original `VolatileRef`.
     /// In comparison with creating a `&mut VolatileRef<'a, T>`, this avoids the additional indirection and lifetime.
-    pub fn borrow_mut(&mut self) -> VolatileRef<'_, T, A>
+    pub const fn borrow_mut(&mut self) -> VolatileRef<'_, T, A>
     where
         A: Access,
     {
-        unsafe { VolatileRef::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatileRef::new_generic(self.pointer) }
     }
 
     /// Borrows this `VolatileRef` as a read-only [`VolatilePtr`].
     ///
     /// Use this method to do (partial) volatile reads of the referenced data.
-    pub fn as_ptr(&self) -> VolatilePtr<'_, T, A::Restricted>
+    pub const fn as_ptr(&self) -> VolatilePtr<'_, T, A::Restricted>
     where
         A: RestrictAccess<ReadOnly>,
     {
-        unsafe { VolatilePtr::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatilePtr::new_generic(self.pointer) }
     }
 
     /// Borrows this `VolatileRef` as a mutable [`VolatilePtr`].
     ///
     /// Use this method to do (partial) volatile reads or writes of the referenced data.
-    pub fn as_mut_ptr(&mut self) -> VolatilePtr<'_, T, A>
+    pub const fn as_mut_ptr(&mut self) -> VolatilePtr<'_, T, A>
     where
         A: Access,
     {
-        unsafe { VolatilePtr::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatilePtr::new_generic(self.pointer) }
     }
 
     /// Converts this `VolatileRef` into a [`VolatilePtr`] with full access without shortening
@@ -185,11 +231,11 @@ where
     /// lifetime `'a`.
     ///
     /// This method consumes the `VolatileRef`.
-    pub fn into_ptr(self) -> VolatilePtr<'a, T, A>
+    pub const fn into_ptr(self) -> VolatilePtr<'a, T, A>
     where
         A: Access,
     {
-        unsafe { VolatilePtr::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatilePtr::new_generic(self.pointer) }
     }
 }
 
@@ -217,21 +263,23 @@ where
     /// // no_access.read(); // compile-time error
     /// // no_access.write(10); // compile-time error
     /// ```
-    pub fn restrict<To>(self) -> VolatileRef<'a, T, A::Restricted>
+    pub const fn restrict<To>(self) -> VolatileRef<'a, T, A::Restricted>
     where
         A: RestrictAccess<To>,
     {
-        unsafe { VolatileRef::new_restricted(Default::default(), self.pointer) }
+        unsafe { VolatileRef::new_generic(self.pointer) }
     }
 }
 
 /// Methods for restricting access.
-impl<'a, T> VolatileRef<'a, T, ReadWrite>
+impl<'a, T, A> VolatileRef<'a, T, A>
 where
     T: ?Sized,
 {
     /// Restricts access permissions to read-only.
     ///
+    /// This is a shorthand for `restrict::<ReadOnly>()` and requires read access.
+    ///
     /// ## Example
     ///
     /// ```
@@ -244,12 +292,17 @@ where
     /// assert_eq!(read_only.as_ptr().read(), -4);
     /// // read_only.as_ptr().write(10); // compile-time error
     /// ```
-    pub fn read_only(self) -> VolatileRef<'a, T, ReadOnly> {
+    pub const fn read_only(self) -> VolatileRef<'a, T, ReadOnly>
+    where
+        A: RestrictAccess<ReadOnly, Restricted = ReadOnly>,
+    {
         self.restrict()
     }
 
     /// Restricts access permissions to write-only.
     ///
+    /// This is a shorthand for `restrict::<WriteOnly>()` and requires write access.
+    ///
     /// ## Example
     ///
     /// Creating a write-only reference to a struct field:
@@ -265,7 +318,10 @@ where
     /// let write_only = volatile.write_only();
     /// // write_only.as_ptr().read(); // compile-time error
     /// ```
-    pub fn write_only(self) -> VolatileRef<'a, T, WriteOnly> {
+    pub const fn write_only(self) -> VolatileRef<'a, T, WriteOnly>
+    where
+        A: RestrictAccess<WriteOnly, Restricted = WriteOnly>,
+    {
         self.restrict()
     }
 }
@@ -308,6 +364,16 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<T, A> defmt::Format for VolatileRef<'_, T, A>
+where
+    T: ?Sized,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=usize:#x}", self.pointer.as_ptr().cast::<u8>().addr());
+    }
+}
+
 impl<T, A> PartialEq for VolatileRef<'_, T, A>
 where
     T: ?Sized,
@@ -346,3 +412,23 @@ where
         self.pointer.as_ptr().hash(state);
     }
 }
+
+/// Creates a `VolatileRef` from a mutable reference, see [`VolatileRef::from_mut_ref`].
+impl<'a, T> From<&'a mut T> for VolatileRef<'a, T, ReadWrite>
+where
+    T: ?Sized,
+{
+    fn from(reference: &'a mut T) -> Self {
+        VolatileRef::from_mut_ref(reference)
+    }
+}
+
+/// Creates a read-only `VolatileRef` from a shared reference, see [`VolatileRef::from_ref`].
+impl<'a, T> From<&'a T> for VolatileRef<'a, T, ReadOnly>
+where
+    T: ?Sized,
+{
+    fn from(reference: &'a T) -> Self {
+        VolatileRef::from_ref(reference)
+    }
+}
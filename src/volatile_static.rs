@@ -0,0 +1,116 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{VolatilePtr, VolatileRef};
+
+/// Owned storage that is only ever accessed through volatile operations.
+///
+/// This is the sound alternative to a `VolatileCell` (see the [crate-level
+/// documentation](crate#why-is-there-no-volatilecell)). The value is stored in an
+/// [`UnsafeCell`], and this type never creates references to it. Instead, it hands out a
+/// [`VolatileRef`] or a [`VolatilePtr`]. This covers use cases such as variables that are
+/// shared with an interrupt handler or a DMA engine.
+///
+/// Use the [`volatile_static!`](crate::volatile_static) macro to declare a `static` of this
+/// type.
+pub struct VolatileStatic<T> {
+    value: UnsafeCell<T>,
+    taken: AtomicBool,
+}
+
+// SAFETY: The value is only accessed through `take`, which hands out exclusive access once, or
+// through the unsafe `as_ptr`, whose callers are responsible for synchronization.
+unsafe impl<T: Send> Sync for VolatileStatic<T> {}
+
+impl<T> VolatileStatic<T> {
+    /// Creates new volatile storage with the given initial value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns exclusive access to the value.
+    ///
+    /// Returns `None` if this function was called before.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// volatile::volatile_static! {
+    ///     static COUNTER: u32 = 0;
+    /// }
+    ///
+    /// let mut counter = COUNTER.take().unwrap();
+    /// counter.as_mut_ptr().update(|c| c + 1);
+    /// assert_eq!(counter.as_ptr().read(), 1);
+    ///
+    /// assert!(COUNTER.take().is_none());
+    /// ```
+    pub fn take(&self) -> Option<VolatileRef<'_, T>> {
+        if self.taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        // SAFETY: The pointer is valid, and this is the only access to the value.
+        Some(unsafe { VolatileRef::new(self.as_raw_ptr()) })
+    }
+
+    /// Returns a volatile pointer to the value.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`VolatilePtr::new`] apply. In particular, the value must not be
+    /// accessed concurrently from multiple threads, and not through a [`VolatileRef`] returned
+    /// from [`take`](Self::take) while the returned pointer is in use.
+    pub unsafe fn as_ptr(&self) -> VolatilePtr<'_, T> {
+        unsafe { VolatilePtr::new(self.as_raw_ptr()) }
+    }
+
+    /// Returns a raw pointer to the value.
+    pub const fn as_raw_ptr(&self) -> NonNull<T> {
+        // SAFETY: The pointer of an `UnsafeCell` is never null.
+        unsafe { NonNull::new_unchecked(self.value.get()) }
+    }
+}
+
+impl<T> fmt::Debug for VolatileStatic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VolatileStatic")
+            .field("value", &self.value.get())
+            .field("taken", &self.taken.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/// Declares `static` items that are only accessed through volatile operations.
+///
+/// Each item is declared as a [`VolatileStatic`].
+///
+/// ## Example
+///
+/// ```
+/// use volatile::volatile_static;
+///
+/// volatile_static! {
+///     /// Buffer that is written by a DMA engine.
+///     pub static RX_BUFFER: [u8; 64] = [0; 64];
+///     static TICKS: u64 = 0;
+/// }
+///
+/// let rx_buffer = RX_BUFFER.take().unwrap();
+/// assert_eq!(rx_buffer.as_ptr().read(), [0; 64]);
+/// ```
+#[macro_export]
+macro_rules! volatile_static {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $value:expr;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::VolatileStatic<$ty> = $crate::VolatileStatic::new($value);
+        )*
+    };
+}
@@ -0,0 +1,237 @@
+//! Software watchpoints for volatile accesses.
+//!
+//! During bring-up, it is often unclear which code keeps accessing a register. With the
+//! `watch` feature, a [`Watchpoint`] calls a function whenever
+//! [`VolatilePtr::read`](crate::VolatilePtr::read),
+//! [`VolatilePtr::write`](crate::VolatilePtr::write), or any other volatile access overlaps
+//! its address range. The function receives a [`Hit`] including the source location of the
+//! access, so it can log the access or panic using [`panic_on_hit`].
+//!
+//! Watchpoints are checked by the dispatch of the [`instrument`](crate::instrument) hooks, so
+//! the `watch` feature enables the `instrument` feature.
+//!
+//! Bulk operations on slices, such as
+//! [`copy_from_slice`](crate::VolatilePtr::copy_from_slice), trigger a watchpoint once if
+//! their volatile range overlaps the watched range.
+//!
+//! ## Example
+//!
+//! ```should_panic
+//! use volatile::{
+//!     watch::{panic_on_hit, Trigger, Watchpoint},
+//!     VolatilePtr,
+//! };
+//!
+//! static CONTROL: Watchpoint = Watchpoint::new(panic_on_hit);
+//!
+//! let mut control = 0_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut control).into()) };
+//! CONTROL.watch_range(volatile.as_raw_ptr().as_ptr().cast(), 4, Trigger::Write);
+//!
+//! volatile.read();
+//! // Panics with the location of this write.
+//! volatile.write(1);
+//! ```
+
+use core::{
+    panic::Location,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{
+    access::Access, backend::Direction, instrument::Event, region::VolatileRegion,
+    tracked::TrackedRange,
+};
+
+/// The accesses that trigger a [`Watchpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trigger {
+    /// Reads trigger the watchpoint.
+    Read,
+    /// Writes trigger the watchpoint.
+    Write,
+    /// Reads and writes trigger the watchpoint.
+    Access,
+}
+
+impl Trigger {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Trigger::Read,
+            1 => Trigger::Write,
+            _ => Trigger::Access,
+        }
+    }
+
+    fn matches(self, direction: Direction) -> bool {
+        matches!(
+            (self, direction),
+            (Trigger::Access, _)
+                | (Trigger::Read, Direction::Read)
+                | (Trigger::Write, Direction::Write)
+        )
+    }
+}
+
+/// An access that triggered a [`Watchpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hit {
+    /// The address of the access.
+    pub addr: usize,
+    /// The size of the access in bytes, or the length of the range for bulk operations.
+    pub size: usize,
+    /// Whether the access is a read or a write.
+    pub direction: Direction,
+    /// The source location of the access.
+    pub location: &'static Location<'static>,
+}
+
+/// Panics with a description of `hit`.
+///
+/// This can be passed to [`Watchpoint::new`] to stop at the first matching access.
+pub fn panic_on_hit(hit: &Hit) {
+    panic!(
+        "watched {:?} of {} bytes at {:#x} from {}",
+        hit.direction, hit.size, hit.addr, hit.location
+    );
+}
+
+/// A watched address range and the function to call on matching accesses.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Debug)]
+pub struct Watchpoint {
+    on_hit: fn(&Hit),
+    range: TrackedRange,
+    hits: AtomicUsize,
+    registered: AtomicBool,
+    next: AtomicPtr<Watchpoint>,
+}
+
+static HEAD: AtomicPtr<Watchpoint> = AtomicPtr::new(ptr::null_mut());
+
+impl Watchpoint {
+    /// Creates a watchpoint that calls `on_hit` and does not watch any range yet.
+    pub const fn new(on_hit: fn(&Hit)) -> Self {
+        Self {
+            on_hit,
+            range: TrackedRange::new(Trigger::Access as u8),
+            hits: AtomicUsize::new(0),
+            registered: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Watches all subsequent accesses within `region`, replacing the previously watched range.
+    pub fn watch<A>(&'static self, region: VolatileRegion<'_, A>, trigger: Trigger)
+    where
+        A: Access,
+    {
+        self.watch_range(region.as_raw_ptr().as_ptr(), region.len(), trigger);
+    }
+
+    /// Watches all subsequent accesses within the `len` bytes at `start`, replacing the
+    /// previously watched range.
+    ///
+    /// The range and trigger are replaced atomically, so concurrent accesses are either
+    /// checked against the previous or the new range, but never against a mix of both.
+    pub fn watch_range(&'static self, start: *const u8, len: usize, trigger: Trigger) {
+        self.range.store(start.addr(), len, trigger as u8);
+        if self.registered.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let this = ptr::from_ref(self).cast_mut();
+        let mut head = HEAD.load(Ordering::Acquire);
+        loop {
+            self.next.store(head, Ordering::Relaxed);
+            match HEAD.compare_exchange_weak(head, this, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Stops watching accesses.
+    pub fn disable(&self) {
+        self.range.store(0, 0, Trigger::Access as u8);
+    }
+
+    /// Returns how often the watchpoint was hit.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        let (start, len, trigger) = self.range.load();
+        len != 0
+            && event.addr < start.saturating_add(len)
+            && start < event.addr.saturating_add(event.size)
+            && Trigger::from_u8(trigger).matches(event.direction)
+    }
+}
+
+/// Calls all watchpoints that watch the access described by `event`.
+///
+/// This is called by the dispatch of the [`instrument`](crate::instrument) hooks before the
+/// registered hooks.
+pub(crate) fn check(event: &Event) {
+    let mut current = HEAD.load(Ordering::Acquire);
+    // SAFETY: Only `'static` watchpoints are added to the list.
+    while let Some(watchpoint) = unsafe { current.as_ref() } {
+        if watchpoint.matches(event) {
+            watchpoint.hits.fetch_add(1, Ordering::Relaxed);
+            (watchpoint.on_hit)(&Hit {
+                addr: event.addr,
+                size: event.size,
+                direction: event.direction,
+                location: event.location,
+            });
+        }
+        current = watchpoint.next.load(Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::Direction,
+        watch::{Hit, Trigger, Watchpoint},
+        VolatilePtr,
+    };
+    use core::{
+        ptr::NonNull,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    #[test]
+    fn test_watchpoint() {
+        static LAST_LINE: AtomicU32 = AtomicU32::new(0);
+
+        fn record(hit: &Hit) {
+            assert_eq!(hit.direction, Direction::Write);
+            assert_eq!(hit.size, 2);
+            LAST_LINE.store(hit.location.line(), Ordering::Relaxed);
+        }
+
+        static WATCHPOINT: Watchpoint = Watchpoint::new(record);
+
+        let mut registers = [0_u16; 4];
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut registers)) };
+        let second = unsafe { volatile.map(|ptr| ptr.cast::<u16>().add(1)) };
+        let third = unsafe { volatile.map(|ptr| ptr.cast::<u16>().add(2)) };
+        WATCHPOINT.watch_range(second.as_raw_ptr().as_ptr().cast(), 2, Trigger::Write);
+
+        third.write(1);
+        second.read();
+        let line = line!() + 1;
+        second.update(|value| value + 1);
+        assert_eq!(WATCHPOINT.hits(), 1);
+        assert_eq!(LAST_LINE.load(Ordering::Relaxed), line);
+
+        WATCHPOINT.disable();
+        second.write(2);
+        assert_eq!(WATCHPOINT.hits(), 1);
+    }
+}
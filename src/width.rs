@@ -0,0 +1,571 @@
+//! Accesses at a width mandated by the device.
+//!
+//! Many devices only support accesses of a specific width, e.g., 32-bit reads of a register
+//! that is logically a byte. A [`WidePtr`] performs all accesses to a narrow value as accesses
+//! to the naturally aligned word of type `W` that contains it and shifts and masks the logical
+//! value accordingly. Writes are read-modify-writes of the containing word, unless they
+//! explicitly overwrite the other bytes using [`WidePtr::write_isolated`].
+//!
+//! With `#[derive(VolatileFieldAccess)]`, a field can be annotated with
+//! `#[volatile(width = 32)]` to make its accessor return a `WidePtr`.
+//!
+//! Conversely, 64-bit registers on 32-bit targets usually have to be accessed as two 32-bit
+//! halves in a specific order, since a single `u64` access may tear or fault on device memory.
+//! [`VolatilePtr::read_split`] and [`VolatilePtr::write_split`] perform these accesses in the
+//! given [`SplitOrder`], and [`VolatilePtr::read_split_counter`] reads free-running counters
+//! consistently.
+//!
+//! No target accesses a `u128` with a single transaction, and neither does a `u64` on 32-bit
+//! targets, so [`VolatilePtr::read`] may split them in an unspecified way. Instead,
+//! [`VolatilePtr::read_as_chunks`] and [`VolatilePtr::write_as_chunks`] split these integers
+//! into accesses of a given width in a documented order. See also
+//! [`single_access`](crate::single_access), which rejects such types at compile time.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::VolatilePtr;
+//!
+//! let mut register = 0x1234_5678_u32;
+//! let volatile = unsafe { VolatilePtr::new((&mut register).into()) };
+//!
+//! // Access the byte at offset 1 with 32-bit accesses only.
+//! let byte = unsafe { volatile.map(|ptr| ptr.cast::<u8>().add(1)).with_width::<u32>() };
+//! byte.write(0xab);
+//! assert_eq!(byte.read(), 0xab);
+//! assert_eq!(register.to_ne_bytes()[1], 0xab);
+//! ```
+
+use core::{fmt, marker::PhantomData, mem, ptr::NonNull};
+
+use crate::{
+    access::{Access, ReadWrite, Readable, Writable},
+    VolatilePtr,
+};
+
+/// Unsigned integers that can be accessed as a whole.
+///
+/// This trait is sealed and implemented for [`u8`], [`u16`], [`u32`], and [`u64`].
+pub trait AccessWidth: Copy + private::Sealed {}
+
+mod private {
+    pub trait Sealed {
+        fn to_u64(self) -> u64;
+        fn from_u64(value: u64) -> Self;
+    }
+}
+
+macro_rules! impl_access_width {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {
+                fn to_u64(self) -> u64 {
+                    self.into()
+                }
+
+                fn from_u64(value: u64) -> Self {
+                    value as $ty
+                }
+            }
+
+            impl AccessWidth for $ty {}
+        )*
+    };
+}
+
+impl_access_width!(u8, u16, u32, u64);
+
+/// A pointer to a value of type `T` that is accessed as the aligned `W` that contains it.
+///
+/// See the [module-level documentation](self) for details.
+pub struct WidePtr<'a, T, W, A = ReadWrite> {
+    pointer: VolatilePtr<'a, T, A>,
+    width: PhantomData<W>,
+}
+
+impl<T, W, A> Copy for WidePtr<'_, T, W, A> {}
+
+impl<T, W, A> Clone for WidePtr<'_, T, W, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, W, A> fmt::Debug for WidePtr<'_, T, W, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WidePtr")
+            .field("pointer", &self.pointer.as_raw_ptr())
+            .field("width", &mem::size_of::<W>())
+            .finish()
+    }
+}
+
+impl<'a, T, W, A> WidePtr<'a, T, W, A>
+where
+    T: AccessWidth,
+    W: AccessWidth,
+    A: Access,
+{
+    /// Creates a pointer that accesses the value behind `pointer` as the aligned `W` that
+    /// contains it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the value is not contained in a single naturally aligned `W`.
+    ///
+    /// ## Safety
+    ///
+    /// The naturally aligned `W` that contains the value must be valid for volatile reads and,
+    /// if `A` allows writes, for volatile writes.
+    pub unsafe fn new(pointer: VolatilePtr<'a, T, A>) -> Self {
+        let offset = pointer.as_raw_ptr().as_ptr().addr() & (mem::size_of::<W>() - 1);
+        assert!(
+            offset + mem::size_of::<T>() <= mem::size_of::<W>(),
+            "value is not contained in a single aligned word"
+        );
+        Self {
+            pointer,
+            width: PhantomData,
+        }
+    }
+
+    /// Returns the narrow pointer to the value.
+    ///
+    /// Accesses through the returned pointer are performed at the width of `T`.
+    pub fn as_ptr(self) -> VolatilePtr<'a, T, A> {
+        self.pointer
+    }
+
+    /// Reads the containing word and extracts the value.
+    pub fn read(self) -> T
+    where
+        A: Readable,
+    {
+        let (word, shift) = self.word();
+        T::from_u64(word.read().to_u64() >> shift)
+    }
+
+    /// Replaces the value in the containing word through a volatile read-modify-write.
+    pub fn write(self, value: T)
+    where
+        A: Readable + Writable,
+    {
+        let (word, shift) = self.word();
+        let mask = Self::mask() << shift;
+        let other = word.read().to_u64() & !mask;
+        word.write(W::from_u64(other | (value.to_u64() << shift)));
+    }
+
+    /// Writes the containing word with the value in place and all other bits cleared.
+    ///
+    /// Unlike [`write`](Self::write), this does not read the word first, which is required for
+    /// write-only registers.
+    pub fn write_isolated(self, value: T)
+    where
+        A: Writable,
+    {
+        let (word, shift) = self.word();
+        word.write(W::from_u64(value.to_u64() << shift));
+    }
+
+    /// Updates the value in the containing word through a volatile read-modify-write.
+    pub fn update<F>(self, f: F)
+    where
+        A: Readable + Writable,
+        F: FnOnce(T) -> T,
+    {
+        self.write(f(self.read()));
+    }
+
+    /// Returns a pointer to the containing word and the shift of the value within it.
+    fn word(self) -> (VolatilePtr<'a, W, A>, usize) {
+        let offset = self.pointer.as_raw_ptr().as_ptr().addr() & (mem::size_of::<W>() - 1);
+        let shift = if cfg!(target_endian = "little") {
+            offset * 8
+        } else {
+            (mem::size_of::<W>() - mem::size_of::<T>() - offset) * 8
+        };
+        // SAFETY: The containing word is valid for accesses according to `new`.
+        let word = unsafe {
+            self.pointer.map(|ptr| {
+                let byte = ptr.as_ptr().cast::<u8>();
+                NonNull::new_unchecked(byte.sub(offset).cast())
+            })
+        };
+        (word, shift)
+    }
+
+    fn mask() -> u64 {
+        u64::MAX >> (64 - mem::size_of::<T>() * 8)
+    }
+}
+
+/// Methods for accesses at a mandated width.
+impl<'a, T, A> VolatilePtr<'a, T, A>
+where
+    T: AccessWidth,
+    A: Access,
+{
+    /// Returns a pointer that accesses the value as the naturally aligned `W` that contains it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the value is not contained in a single naturally aligned `W`.
+    ///
+    /// ## Safety
+    ///
+    /// The requirements of [`WidePtr::new`] apply.
+    pub unsafe fn with_width<W>(self) -> WidePtr<'a, T, W, A>
+    where
+        W: AccessWidth,
+    {
+        unsafe { WidePtr::new(self) }
+    }
+}
+
+/// Unsigned integers that can be accessed as several narrower chunks.
+///
+/// This trait is sealed and implemented for [`u64`] and [`u128`].
+pub trait Chunked: Copy + chunked::Sealed {}
+
+mod chunked {
+    pub trait Sealed {
+        fn to_u128(self) -> u128;
+        fn from_u128(value: u128) -> Self;
+    }
+}
+
+macro_rules! impl_chunked {
+    ($($ty:ty),*) => {
+        $(
+            impl chunked::Sealed for $ty {
+                fn to_u128(self) -> u128 {
+                    self.into()
+                }
+
+                fn from_u128(value: u128) -> Self {
+                    value as $ty
+                }
+            }
+
+            impl Chunked for $ty {}
+        )*
+    };
+}
+
+impl_chunked!(u64, u128);
+
+/// Methods for splitting wide integers into chunks.
+impl<'a, T, A> VolatilePtr<'a, T, A>
+where
+    T: Chunked,
+    A: Access,
+{
+    /// Reads the value as chunks of type `C` in the given order of significance.
+    ///
+    /// With [`SplitOrder::LowFirst`], the least significant chunk is read first.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `C` is not narrower than `T`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{width::SplitOrder, VolatilePtr};
+    ///
+    /// let mut register = 0x0011_2233_4455_6677_8899_aabb_ccdd_eeff_u128;
+    /// let volatile = unsafe { VolatilePtr::new((&mut register).into()) };
+    /// assert_eq!(
+    ///     volatile.read_as_chunks::<u64>(SplitOrder::HighFirst),
+    ///     0x0011_2233_4455_6677_8899_aabb_ccdd_eeff
+    /// );
+    /// ```
+    pub fn read_as_chunks<C>(self, order: SplitOrder) -> T
+    where
+        C: AccessWidth,
+        A: Readable,
+    {
+        let mut value = 0;
+        for (index, shift) in chunks::<T, C>(order) {
+            // SAFETY: The chunk is within the value.
+            let chunk = unsafe { self.map(|ptr| ptr.cast::<C>().add(index)) };
+            value |= u128::from(chunk.read().to_u64()) << shift;
+        }
+        T::from_u128(value)
+    }
+
+    /// Writes the value as chunks of type `C` in the given order of significance.
+    ///
+    /// With [`SplitOrder::LowFirst`], the least significant chunk is written first.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `C` is not narrower than `T`.
+    pub fn write_as_chunks<C>(self, value: T, order: SplitOrder)
+    where
+        C: AccessWidth,
+        A: Writable,
+    {
+        let value = value.to_u128();
+        for (index, shift) in chunks::<T, C>(order) {
+            // SAFETY: The chunk is within the value.
+            let chunk = unsafe { self.map(|ptr| ptr.cast::<C>().add(index)) };
+            chunk.write(C::from_u64((value >> shift) as u64));
+        }
+    }
+}
+
+/// Returns the indices of the `C` chunks of a `T` in the given order together with their shifts.
+fn chunks<T, C>(order: SplitOrder) -> impl Iterator<Item = (usize, usize)> {
+    let size = mem::size_of::<C>();
+    let count = mem::size_of::<T>() / size;
+    assert!(count > 1, "chunks must be narrower than the value");
+    (0..count).map(move |i| {
+        let significance = match order {
+            SplitOrder::LowFirst => i,
+            SplitOrder::HighFirst => count - 1 - i,
+        };
+        let index = if cfg!(target_endian = "little") {
+            significance
+        } else {
+            count - 1 - significance
+        };
+        (index, significance * size * 8)
+    })
+}
+
+/// The order of the two halves of a split access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SplitOrder {
+    /// Access the low half first.
+    LowFirst,
+    /// Access the high half first.
+    HighFirst,
+}
+
+/// Methods for splitting 64-bit accesses into two 32-bit accesses.
+impl<'a, A> VolatilePtr<'a, u64, A>
+where
+    A: Access,
+{
+    /// Reads the value as two 32-bit halves in the given order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::{width::SplitOrder, VolatilePtr};
+    ///
+    /// let mut register = 0x1122_3344_5566_7788_u64;
+    /// let volatile = unsafe { VolatilePtr::new((&mut register).into()) };
+    /// assert_eq!(volatile.read_split(SplitOrder::LowFirst), 0x1122_3344_5566_7788);
+    /// ```
+    pub fn read_split(self, order: SplitOrder) -> u64
+    where
+        A: Readable,
+    {
+        let (low, high) = self.halves();
+        let (low, high) = match order {
+            SplitOrder::LowFirst => {
+                let low = low.read();
+                (low, high.read())
+            }
+            SplitOrder::HighFirst => {
+                let high = high.read();
+                (low.read(), high)
+            }
+        };
+        (u64::from(high) << 32) | u64::from(low)
+    }
+
+    /// Writes the value as two 32-bit halves in the given order.
+    ///
+    /// Many devices latch the value of a 64-bit register on the write of one of its halves,
+    /// which then has to be written last.
+    pub fn write_split(self, value: u64, order: SplitOrder)
+    where
+        A: Writable,
+    {
+        let (low, high) = self.halves();
+        match order {
+            SplitOrder::LowFirst => {
+                low.write(value as u32);
+                high.write((value >> 32) as u32);
+            }
+            SplitOrder::HighFirst => {
+                high.write((value >> 32) as u32);
+                low.write(value as u32);
+            }
+        }
+    }
+
+    /// Reads a consistent value of a 64-bit counter as two 32-bit halves.
+    ///
+    /// See [`read_split_counter`](crate::consistent::read_split_counter) for details.
+    pub fn read_split_counter(self, order: SplitOrder) -> u64
+    where
+        A: Readable,
+    {
+        let (low, high) = self.halves();
+        crate::consistent::read_split_counter(high, low, order)
+    }
+
+    /// Returns pointers to the low and the high half of the value.
+    fn halves(self) -> (VolatilePtr<'a, u32, A>, VolatilePtr<'a, u32, A>) {
+        let (low, high) = if cfg!(target_endian = "little") {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+        // SAFETY: Both halves are within the value.
+        unsafe {
+            (
+                self.map(|ptr| ptr.cast::<u32>().add(low)),
+                self.map(|ptr| ptr.cast::<u32>().add(high)),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-util")]
+    use crate::backend::{mock::MockBackend, with_backend, Direction};
+    use crate::{width::SplitOrder, VolatilePtr};
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_with_width() {
+        let mut register = 0x1122_3344_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+
+        for offset in 0..4 {
+            let byte = unsafe { volatile.map(|ptr| ptr.cast::<u8>().add(offset)) };
+            let wide = unsafe { byte.with_width::<u32>() };
+            assert_eq!(wide.read(), byte.read());
+        }
+
+        let half = unsafe {
+            volatile
+                .map(|ptr| ptr.cast::<u16>().add(1))
+                .with_width::<u32>()
+        };
+        half.update(|value| value ^ 0xffff);
+        let mut expected = 0x1122_3344_u32.to_ne_bytes();
+        expected[2] ^= 0xff;
+        expected[3] ^= 0xff;
+        assert_eq!(register.to_ne_bytes(), expected);
+
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+        let byte = unsafe {
+            volatile
+                .map(|ptr| ptr.cast::<u8>().add(3))
+                .with_width::<u32>()
+        };
+        byte.write_isolated(0xaa);
+        assert_eq!(register.to_ne_bytes(), [0, 0, 0, 0xaa]);
+    }
+
+    #[test]
+    #[should_panic = "value is not contained in a single aligned word"]
+    fn test_with_width_straddling() {
+        let mut register = 0_u32;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+        let _ = unsafe {
+            volatile
+                .map(|ptr| ptr.cast::<u16>().byte_add(1))
+                .with_width::<u16>()
+        };
+    }
+
+    #[test]
+    fn test_split_u64() {
+        let mut register = 0_u64;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+        for order in [SplitOrder::LowFirst, SplitOrder::HighFirst] {
+            volatile.write_split(0x0123_4567_89ab_cdef, order);
+            assert_eq!(volatile.read(), 0x0123_4567_89ab_cdef);
+            assert_eq!(volatile.read_split(order), 0x0123_4567_89ab_cdef);
+            volatile.write(0);
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_read_split_counter() {
+        let mut counter = 0_u64;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut counter)) };
+        let (low, high) = unsafe {
+            let words = volatile.map(|ptr| ptr.cast::<u32>());
+            if cfg!(target_endian = "little") {
+                (words, words.map(|ptr| ptr.add(1)))
+            } else {
+                (words.map(|ptr| ptr.add(1)), words)
+            }
+        };
+
+        // The low half overflows between the first two reads.
+        let mock = MockBackend::new();
+        for value in [1_u32, 2, 2] {
+            mock.push_read(high.as_raw_ptr().as_ptr(), &value.to_ne_bytes());
+        }
+        for value in [0xffff_ffff_u32, 5] {
+            mock.push_read(low.as_raw_ptr().as_ptr(), &value.to_ne_bytes());
+        }
+        let value =
+            unsafe { with_backend(&mock, || volatile.read_split_counter(SplitOrder::HighFirst)) };
+        assert_eq!(value, 0x2_0000_0005);
+
+        let mock = MockBackend::new();
+        for value in [0xffff_fffe_u32, 3, 4] {
+            mock.push_read(low.as_raw_ptr().as_ptr(), &value.to_ne_bytes());
+        }
+        for value in [1_u32, 2] {
+            mock.push_read(high.as_raw_ptr().as_ptr(), &value.to_ne_bytes());
+        }
+        let value =
+            unsafe { with_backend(&mock, || volatile.read_split_counter(SplitOrder::LowFirst)) };
+        assert_eq!(value, 0x2_0000_0003);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_chunked_u128() {
+        let mut register = 0_u128;
+        let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut register)) };
+        let base = volatile.as_raw_ptr().as_ptr() as usize;
+
+        let mock = MockBackend::new();
+        unsafe {
+            with_backend(&mock, || {
+                volatile.write_as_chunks::<u32>(
+                    0x0f0e_0d0c_0b0a_0908_0706_0504_0302_0100,
+                    SplitOrder::HighFirst,
+                );
+            })
+        };
+        assert_eq!(register, 0x0f0e_0d0c_0b0a_0908_0706_0504_0302_0100);
+        let accesses = mock.take_accesses();
+        assert!(accesses
+            .iter()
+            .all(|access| access.direction == Direction::Write));
+        let order: [usize; 4] = core::array::from_fn(|i| accesses[i].addr - base);
+        if cfg!(target_endian = "little") {
+            assert_eq!(order, [12, 8, 4, 0]);
+        } else {
+            assert_eq!(order, [0, 4, 8, 12]);
+        }
+
+        assert_eq!(
+            volatile.read_as_chunks::<u16>(SplitOrder::LowFirst),
+            0x0f0e_0d0c_0b0a_0908_0706_0504_0302_0100
+        );
+        let volatile = unsafe { volatile.map(|ptr| ptr.cast::<u64>()) };
+        volatile.write_as_chunks::<u8>(0x1122_3344_5566_7788, SplitOrder::LowFirst);
+        assert_eq!(
+            volatile.read_as_chunks::<u32>(SplitOrder::HighFirst),
+            0x1122_3344_5566_7788
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Batched writes to write-combining memory.
+//!
+//! Write-combining (WC) memory, such as framebuffers or doorbell apertures, is most efficient
+//! when written with non-temporal stores, which bypass the cache and are combined into bursts
+//! by the CPU. Non-temporal stores are weakly ordered, so they have to be followed by a fence
+//! before the device may observe them. [`WriteCombiningWriter`] performs non-temporal stores
+//! on x86_64 and issues an `sfence` on [`flush`](WriteCombiningWriter::flush). On other
+//! architectures, it performs normal volatile writes followed by a full fence.
+//!
+//! For multi-megabyte transfers, [`VolatilePtr::copy_from_slice_nontemporal`] and
+//! [`VolatilePtr::copy_into_slice_nontemporal`] copy whole slices with 128-bit streaming stores
+//! and loads, so that the transfer does not evict the rest of the system's working set from the
+//! cache.
+//!
+//! ## Example
+//!
+//! ```
+//! use volatile::{write_combining::WriteCombiningWriter, VolatilePtr};
+//!
+//! let mut framebuffer = [0_u32; 64];
+//! let volatile = unsafe { VolatilePtr::new((&mut framebuffer[..]).into()) };
+//!
+//! let mut writer = WriteCombiningWriter::new(volatile);
+//! writer.write_slice(0, &[0xff00_00ff; 32]);
+//! writer.write(63, 0x00ff_00ff);
+//! writer.flush();
+//!
+//! assert_eq!(framebuffer[31], 0xff00_00ff);
+//! assert_eq!(framebuffer[63], 0x00ff_00ff);
+//! ```
+
+use core::{fmt, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    access::{Readable, Writable},
+    VolatilePtr,
+};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", not(miri)))]
+use crate::{
+    backend::Direction,
+    volatile_ptr::{unit, with_unit},
+};
+
+/// Values that can be written with non-temporal stores.
+///
+/// This trait is sealed and implemented for [`u32`], [`u64`], [`i32`], and [`i64`].
+pub trait NonTemporal: Copy + private::Sealed {}
+
+mod private {
+    pub trait Sealed {
+        /// Stores `value` to `dst`, bypassing the cache if possible.
+        unsafe fn store(dst: *mut Self, value: Self);
+    }
+}
+
+macro_rules! impl_non_temporal {
+    ($($ty:ty => $stream:ident as $int:ty),*) => {
+        $(
+            impl private::Sealed for $ty {
+                #[inline(always)]
+                unsafe fn store(dst: *mut Self, value: Self) {
+                    #[cfg(all(target_arch = "x86_64", not(miri)))]
+                    if !crate::backend::is_installed() {
+                        let _observation = crate::backend::observe(
+                            dst.cast(),
+                            core::mem::size_of::<Self>(),
+                            crate::backend::Direction::Write,
+                        );
+                        unsafe { core::arch::x86_64::$stream(dst.cast::<$int>(), value as $int) };
+                        return;
+                    }
+                    unsafe { crate::backend::write(dst, value) };
+                }
+            }
+
+            impl NonTemporal for $ty {}
+        )*
+    };
+}
+
+impl_non_temporal!(
+    u32 => _mm_stream_si32 as i32,
+    i32 => _mm_stream_si32 as i32,
+    u64 => _mm_stream_si64 as i64,
+    i64 => _mm_stream_si64 as i64
+);
+
+/// Writes to a volatile slice with non-temporal stores until it is flushed.
+///
+/// The writer is flushed when it is dropped.
+///
+/// See the [module-level documentation](self) for details.
+pub struct WriteCombiningWriter<'a, T>
+where
+    T: NonTemporal,
+{
+    pointer: NonNull<[T]>,
+    dirty: bool,
+    reference: PhantomData<&'a [T]>,
+}
+
+impl<'a, T> WriteCombiningWriter<'a, T>
+where
+    T: NonTemporal,
+{
+    /// Creates a writer for the given slice.
+    pub fn new<A>(slice: VolatilePtr<'a, [T], A>) -> Self
+    where
+        A: Writable,
+    {
+        Self {
+            pointer: slice.as_raw_ptr(),
+            dirty: false,
+            reference: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements of the slice.
+    pub fn len(&self) -> usize {
+        self.pointer.len()
+    }
+
+    /// Returns whether the slice is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes `value` to the element at `index` with a non-temporal store.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn write(&mut self, index: usize, value: T) {
+        assert!(index < self.len(), "index out of bounds");
+        // SAFETY: The index was checked above, and the slice is valid for writes.
+        unsafe { T::store(self.pointer.as_ptr().cast::<T>().add(index), value) };
+        self.dirty = true;
+    }
+
+    /// Writes all elements of `src` starting at `offset` with non-temporal stores.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `src` does not fit into the slice at `offset`.
+    pub fn write_slice(&mut self, offset: usize, src: &[T]) {
+        assert!(
+            offset <= self.len() && src.len() <= self.len() - offset,
+            "source slice does not fit"
+        );
+        let dst = self.pointer.as_ptr().cast::<T>();
+        for (i, &value) in src.iter().enumerate() {
+            // SAFETY: The range was checked above, and the slice is valid for writes.
+            unsafe { T::store(dst.add(offset + i), value) };
+        }
+        self.dirty = true;
+    }
+
+    /// Makes all previous writes globally visible.
+    ///
+    /// This issues an `sfence` on x86_64 and a full fence on other architectures.
+    pub fn flush(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        #[cfg(all(target_arch = "x86_64", not(miri)))]
+        // SAFETY: SSE is always available on x86_64.
+        unsafe {
+            core::arch::x86_64::_mm_sfence();
+        }
+        #[cfg(not(all(target_arch = "x86_64", not(miri))))]
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        self.dirty = false;
+    }
+}
+
+impl<T> Drop for WriteCombiningWriter<'_, T>
+where
+    T: NonTemporal,
+{
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl<T> fmt::Debug for WriteCombiningWriter<'_, T>
+where
+    T: NonTemporal,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteCombiningWriter")
+            .field("pointer", &self.pointer.as_ptr())
+            .field("dirty", &self.dirty)
+            .finish()
+    }
+}
+
+/// Methods for non-temporal copies of volatile slices.
+impl<T, A> VolatilePtr<'_, [T], A>
+where
+    T: Copy,
+{
+    /// Copies all elements from `src` into `self` with non-temporal stores, followed by a
+    /// fence.
+    ///
+    /// On x86_64, the 16-byte aligned middle part is written with `movntdq` and the copy is
+    /// followed by an `sfence`. On other architectures, this is a
+    /// [`copy_from_slice`](Self::copy_from_slice) followed by a full fence.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use volatile::VolatilePtr;
+    ///
+    /// let mut framebuffer = [0_u32; 1024];
+    /// let volatile = unsafe { VolatilePtr::new((&mut framebuffer[..]).into()) };
+    /// volatile.copy_from_slice_nontemporal(&[0xff00_00ff; 1024]);
+    /// assert_eq!(framebuffer, [0xff00_00ff; 1024]);
+    /// ```
+    #[track_caller]
+    pub fn copy_from_slice_nontemporal(self, src: &[T])
+    where
+        A: Writable,
+    {
+        let dst = self.as_raw_ptr();
+        assert_eq!(
+            dst.len(),
+            src.len(),
+            "destination and source slices have different lengths"
+        );
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2", not(miri)))]
+        if !crate::backend::is_installed() {
+            // SAFETY: The slices have the same length, and `self` is valid for writes.
+            let len = core::mem::size_of_val(src);
+            let _observation =
+                crate::backend::observe_bulk(dst.as_ptr().cast(), len, Direction::Write);
+            with_unit!(unit::<T>(), |U| unsafe {
+                x86_64::store_nontemporal::<U>(dst.as_ptr().cast(), src.as_ptr().cast(), len)
+            });
+            return;
+        }
+        self.copy_from_slice(src);
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Copies all elements from `self` into `dst` with non-temporal loads, followed by a
+    /// fence.
+    ///
+    /// On x86_64 with SSE4.1, the 16-byte aligned middle part is read with `movntdqa` and the
+    /// copy is followed by an `mfence`. SSE4.1 is detected at runtime with the `std` feature and
+    /// has to be enabled at compile time otherwise. Without SSE4.1 and on other architectures,
+    /// this is a [`copy_into_slice`](Self::copy_into_slice) followed by a full fence.
+    ///
+    /// Non-temporal loads only bypass the cache for write-combining memory.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    #[track_caller]
+    pub fn copy_into_slice_nontemporal(self, dst: &mut [T])
+    where
+        A: Readable,
+    {
+        let src = self.as_raw_ptr();
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "destination and source slices have different lengths"
+        );
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2", not(miri)))]
+        if x86_64::sse41_available() && !crate::backend::is_installed() {
+            // SAFETY: SSE4.1 is available, the slices have the same length, and `self` is
+            // valid for reads.
+            let len = core::mem::size_of_val(dst);
+            let _observation =
+                crate::backend::observe_bulk(src.as_ptr().cast(), len, Direction::Read);
+            with_unit!(unit::<T>(), |U| unsafe {
+                x86_64::load_nontemporal::<U>(dst.as_mut_ptr().cast(), src.as_ptr().cast(), len)
+            });
+            return;
+        }
+        self.copy_into_slice(dst);
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", not(miri)))]
+mod x86_64 {
+    use core::arch::x86_64::{
+        __m128i, _mm_loadu_si128, _mm_mfence, _mm_sfence, _mm_storeu_si128, _mm_stream_load_si128,
+        _mm_stream_si128,
+    };
+
+    /// Copies `len` bytes from `src` to `dst` using streaming stores for the 16-byte aligned
+    /// middle part of `dst` and `U`-wide volatile accesses for the rest.
+    pub(super) unsafe fn store_nontemporal<U>(dst: *mut u8, src: *const u8, len: usize) {
+        let head = dst.align_offset(16).min(len);
+        let body = head + (len - head) / 16 * 16;
+        unsafe {
+            copy_chunks::<U>(dst, src, head);
+            for i in (head..body).step_by(16) {
+                let value = _mm_loadu_si128(src.add(i).cast::<__m128i>());
+                _mm_stream_si128(dst.add(i).cast::<__m128i>(), value);
+            }
+            copy_chunks::<U>(dst.add(body), src.add(body), len - body);
+            _mm_sfence();
+        }
+    }
+
+    /// Copies `len` bytes from `src` to `dst` using streaming loads for the 16-byte aligned
+    /// middle part of `src` and `U`-wide volatile accesses for the rest.
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn load_nontemporal<U>(dst: *mut u8, src: *const u8, len: usize) {
+        let head = src.align_offset(16).min(len);
+        let body = head + (len - head) / 16 * 16;
+        unsafe {
+            copy_chunks::<U>(dst, src, head);
+            for i in (head..body).step_by(16) {
+                let value = _mm_stream_load_si128(src.add(i).cast::<__m128i>());
+                _mm_storeu_si128(dst.add(i).cast::<__m128i>(), value);
+            }
+            copy_chunks::<U>(dst.add(body), src.add(body), len - body);
+            // Streaming loads are weakly ordered with respect to other loads.
+            _mm_mfence();
+        }
+    }
+
+    /// Returns whether `movntdqa` may be used.
+    pub(super) fn sse41_available() -> bool {
+        #[cfg(feature = "std")]
+        {
+            std::is_x86_feature_detected!("sse4.1")
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            cfg!(target_feature = "sse4.1")
+        }
+    }
+
+    unsafe fn copy_chunks<U>(dst: *mut u8, src: *const u8, len: usize) {
+        for i in (0..len).step_by(core::mem::size_of::<U>()) {
+            unsafe {
+                let chunk = src.add(i).cast::<U>().read_volatile();
+                dst.add(i).cast::<U>().write_volatile(chunk);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VolatilePtr;
+    use core::ptr::NonNull;
+
+    #[test]
+    fn test_copy_nontemporal() {
+        let mut src = [0_u8; 301];
+        for (i, byte) in src.iter_mut().enumerate() {
+            *byte = (i * 3) as u8;
+        }
+
+        for offset in [0, 1, 7, 16] {
+            let mut dst = [0_u8; 301];
+            let volatile = unsafe { VolatilePtr::new(NonNull::from(&mut dst[offset..])) };
+            volatile.copy_from_slice_nontemporal(&src[..301 - offset]);
+            assert_eq!(dst[offset..], src[..301 - offset]);
+
+            let mut back = [0_u8; 301];
+            let volatile = unsafe { VolatilePtr::new_read_only(NonNull::from(&src[offset..])) };
+            volatile.copy_into_slice_nontemporal(&mut back[..301 - offset]);
+            assert_eq!(back[..301 - offset], src[offset..]);
+        }
+    }
+}
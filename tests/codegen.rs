@@ -0,0 +1,135 @@
+//! Checks that volatile reads and writes of primitive integers compile to a single load or store
+//! instruction.
+//!
+//! MMIO correctness depends on this, since a split, merged, or repeated access can have side
+//! effects on the device. The accessors are defined in `examples/codegen.rs`, which is compiled
+//! to assembly with the default features and with each feature in [`FEATURES`], since features
+//! are unified across the dependency graph.
+
+use std::{env, fs, path::Path, process::Command};
+
+/// Features that must not add overhead to single accesses.
+///
+/// Features that intercept every access by design, e.g., `test-util` or `stats`, are not
+/// listed.
+const FEATURES: &[&str] = &["std"];
+
+#[test]
+#[cfg_attr(
+    not(all(
+        any(target_arch = "x86_64", target_arch = "aarch64"),
+        any(target_os = "linux", target_os = "macos")
+    )),
+    ignore = "no assembly expectations for this target"
+)]
+fn single_instruction_accesses() {
+    for features in [""].iter().chain(FEATURES) {
+        let asm = emit_asm(features);
+        for ty in ["u8", "u16", "u32", "u64"] {
+            for direction in ["read", "write"] {
+                let name = format!("codegen_{direction}_{ty}");
+                let instructions = function_body(&asm, &name);
+                let (ret, accesses) = instructions
+                    .split_last()
+                    .unwrap_or_else(|| panic!("`{name}` not found in assembly ({features:?})"));
+                assert!(
+                    ret.starts_with("ret"),
+                    "`{name}` does not end with `ret` ({features:?}): {instructions:?}"
+                );
+                assert_eq!(
+                    accesses.len(),
+                    1,
+                    "`{name}` does not compile to a single instruction ({features:?}): \
+                     {instructions:?}"
+                );
+                assert!(
+                    accesses[0].contains('(') || accesses[0].contains('['),
+                    "`{name}` does not access memory ({features:?}): {instructions:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(
+    not(all(
+        any(target_arch = "x86_64", target_arch = "aarch64"),
+        any(target_os = "linux", target_os = "macos")
+    )),
+    ignore = "no assembly expectations for this target"
+)]
+fn unrolled_array_copies() {
+    let asm = emit_asm("");
+    for name in ["codegen_copy_from_array", "codegen_copy_into_array"] {
+        let instructions = function_body(&asm, name);
+        assert!(!instructions.is_empty(), "`{name}` not found in assembly");
+        let branches: Vec<_> = instructions
+            .iter()
+            .filter(|instruction| is_branch(instruction))
+            .collect();
+        assert!(
+            branches.is_empty(),
+            "`{name}` is not unrolled: {instructions:?}"
+        );
+    }
+}
+
+/// Returns whether `instruction` is a conditional or unconditional jump within a function.
+fn is_branch(instruction: &str) -> bool {
+    let mnemonic = instruction.split_whitespace().next().unwrap_or_default();
+    if cfg!(target_arch = "x86_64") {
+        mnemonic.starts_with('j')
+    } else {
+        mnemonic == "b"
+            || mnemonic.starts_with("b.")
+            || mnemonic.starts_with("cb")
+            || mnemonic.starts_with("tb")
+    }
+}
+
+/// Compiles the `codegen` example with optimizations and the given features and returns its
+/// assembly.
+fn emit_asm(features: &str) -> String {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("codegen-{features}"));
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let status = Command::new(cargo)
+        .args([
+            "rustc",
+            "--release",
+            "--example",
+            "codegen",
+            "--features",
+            features,
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "--emit", "asm", "-C", "codegen-units=1"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to compile the `codegen` example");
+
+    let examples = target_dir.join("release").join("examples");
+    let path = fs::read_dir(examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "s"))
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("no assembly output found");
+    fs::read_to_string(path).unwrap()
+}
+
+/// Returns the instructions of the function `name` without labels, directives, and comments.
+fn function_body<'a>(asm: &'a str, name: &str) -> Vec<&'a str> {
+    let labels = [format!("{name}:"), format!("_{name}:")];
+    asm.lines()
+        .map(str::trim)
+        .skip_while(|line| !labels.iter().any(|label| line == label))
+        .skip(1)
+        .take_while(|line| !line.contains("func_end"))
+        .filter(|line| {
+            !line.is_empty() && !line.starts_with(['.', '#', ';', '/']) && !line.ends_with(':')
+        })
+        .collect()
+}
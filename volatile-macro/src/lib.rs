@@ -12,6 +12,8 @@ macro_rules! bail {
     };
 }
 
+mod map_field;
+mod register_block;
 mod volatile;
 
 /// A derive macro for method-based accesses to volatile structures.
@@ -60,6 +62,504 @@ mod volatile;
 /// // volatile_ptr.feature_select().write(42);
 /// ```
 ///
+/// # Default access
+///
+/// Fields without an `#[access(...)]` attribute are `ReadWrite` by default.
+/// For register blocks that are mostly read-only or write-only, the default can be changed with `#[volatile(access = ...)]` on the struct.
+/// Individual fields can still override it.
+///
+/// ```
+/// use volatile::access::{ReadOnly, ReadWrite};
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// #[volatile(access = ReadOnly)]
+/// pub struct Capabilities {
+///     vendor_id: u16,
+///     device_id: u16,
+///     #[access(ReadWrite)]
+///     command: u16,
+/// }
+///
+/// let mut capabilities = Capabilities::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut capabilities);
+/// let volatile_ptr = volatile_ref.as_mut_ptr();
+///
+/// assert_eq!(volatile_ptr.vendor_id().read(), 0);
+/// // volatile_ptr.vendor_id().write(1); // compile-time error
+/// volatile_ptr.command().write(1);
+/// ```
+///
+/// # Snapshots
+///
+/// With `#[volatile(snapshot)]` on the struct, the trait additionally provides a `snapshot` method.
+/// It reads all readable fields one by one and returns an owned copy of the struct.
+/// Fields that cannot be read (e.g., `#[access(WriteOnly)]`) are set to their [`Default`] value.
+/// The same holds for fields marked with `#[volatile(read_side_effects)]`, since reading them would change the device state.
+///
+/// ```
+/// use volatile::access::{ReadOnly, WriteOnly};
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default, Clone, Copy, Debug, PartialEq)]
+/// #[volatile(snapshot)]
+/// pub struct Uart {
+///     #[access(ReadOnly)]
+///     status: u32,
+///     #[access(ReadOnly)]
+///     #[volatile(read_side_effects)]
+///     rx_fifo: u32,
+///     #[access(WriteOnly)]
+///     tx_fifo: u32,
+/// }
+///
+/// let mut uart = Uart { status: 1, rx_fifo: 2, tx_fifo: 3 };
+/// let volatile_ref = VolatileRef::from_mut_ref(&mut uart);
+///
+/// let snapshot = volatile_ref.as_ptr().snapshot();
+/// assert_eq!(snapshot, Uart { status: 1, rx_fifo: 0, tx_fifo: 0 });
+/// ```
+///
+/// # Writing whole structs
+///
+/// With `#[volatile(write_all)]` on the struct, the trait additionally provides `write_all` and `init` methods.
+/// Both take a reference to an owned value and write all writable fields in declaration order.
+/// Fields that cannot be written (e.g., `#[access(ReadOnly)]`) are skipped.
+/// `init` additionally issues a [`fence`](core::sync::atomic::fence) before and after the writes, which is useful for one-shot device initialization.
+///
+/// ```
+/// use volatile::access::ReadOnly;
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// #[volatile(write_all)]
+/// pub struct QueueConfig {
+///     #[access(ReadOnly)]
+///     max_size: u16,
+///     size: u16,
+///     desc_addr: u64,
+/// }
+///
+/// let mut queue_config = QueueConfig { max_size: 256, ..Default::default() };
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut queue_config);
+///
+/// volatile_ref.as_mut_ptr().init(&QueueConfig { max_size: 0, size: 128, desc_addr: 0x1000 });
+/// assert_eq!(volatile_ref.as_ptr().max_size().read(), 256);
+/// assert_eq!(volatile_ref.as_ptr().size().read(), 128);
+/// assert_eq!(volatile_ref.as_ptr().desc_addr().read(), 0x1000);
+/// ```
+///
+/// # Resetting
+///
+/// With `#[volatile(reset)]` on the struct, the trait additionally provides a `reset` method, which puts the register block back into a known state.
+/// It writes the [`Default`] value of each writable field in declaration order.
+/// A different reset value can be given with `#[volatile(reset = ...)]` on the field.
+/// For fixed-endian fields, the reset value is given in native byte order.
+///
+/// ```
+/// use volatile::access::ReadOnly;
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// #[volatile(reset)]
+/// pub struct Timer {
+///     #[volatile(reset = 0x8000)]
+///     control: u32,
+///     counter: u32,
+///     #[access(ReadOnly)]
+///     status: u32,
+/// }
+///
+/// let mut timer = Timer { control: 1, counter: 2, status: 3 };
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut timer);
+///
+/// volatile_ref.as_mut_ptr().reset();
+/// assert_eq!(volatile_ref.as_ptr().control().read(), 0x8000);
+/// assert_eq!(volatile_ref.as_ptr().counter().read(), 0);
+/// assert_eq!(volatile_ref.as_ptr().status().read(), 3);
+/// ```
+///
+/// # Builders
+///
+/// With `#[volatile(builder)]` on the struct, the trait additionally provides a `builder` method.
+/// The returned `{T}VolatileBuilder` has one setter per writable field and a `write` method that writes all fields in declaration order.
+/// `write` is only available once every writable field has been set, so forgetting a mandatory register is a compile-time error.
+///
+/// ```
+/// use volatile::access::{ReadOnly, WriteOnly};
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// #[volatile(builder)]
+/// pub struct Dma {
+///     #[access(WriteOnly)]
+///     src: u64,
+///     #[access(WriteOnly)]
+///     dst: u64,
+///     #[access(WriteOnly)]
+///     len: u32,
+///     #[access(ReadOnly)]
+///     status: u32,
+/// }
+///
+/// let mut dma = Dma::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut dma);
+///
+/// // The setters can be called in any order, the fields are written in declaration order.
+/// volatile_ref.as_mut_ptr().builder().len(64).src(0x1000).dst(0x2000).write();
+/// // volatile_ref.as_mut_ptr().builder().src(0x1000).write(); // compile-time error
+///
+/// assert_eq!(dma.src, 0x1000);
+/// assert_eq!(dma.dst, 0x2000);
+/// assert_eq!(dma.len, 64);
+/// ```
+///
+/// # Bit fields
+///
+/// Integer fields can be split into named bit fields with `#[volatile(bits(...))]`.
+/// A bit field is either a single bit index, which is accessed as `bool`, or a range of bits, which is accessed as the field's integer type.
+/// For each bit field `name`, the trait provides a `name` getter and a `set_name` setter.
+/// The setter performs a volatile read-modify-write of the whole field and leaves the other bits untouched.
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// pub struct Timer {
+///     #[volatile(bits(enable = 0, mode = 1..3, prescaler = 8..=15))]
+///     control: u32,
+/// }
+///
+/// let mut timer = Timer::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut timer);
+/// let volatile_ptr = volatile_ref.as_mut_ptr();
+///
+/// volatile_ptr.set_enable(true);
+/// volatile_ptr.set_mode(0b10);
+/// volatile_ptr.set_prescaler(0xff);
+/// assert!(volatile_ptr.enable());
+/// assert_eq!(volatile_ptr.mode(), 0b10);
+/// assert_eq!(volatile_ptr.control().read(), 0xff05);
+/// ```
+///
+/// Bit fields can also be declared on fixed-endian fields, such as `zerocopy::byteorder::U32<LE>`.
+/// The macro recognizes the wrapper types of `zerocopy::byteorder` as well as types named `Le<T>` or `Be<T>` and then operates on the value in native byte order, using the accessors from `volatile::endian`.
+/// The field accessor itself still returns a pointer to the wrapper type, which provides `read_native` and `write_native`.
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+/// use zerocopy::byteorder::{BE, U32};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// pub struct NetDevice {
+///     #[volatile(bits(link_up = 0, speed = 8..16))]
+///     status: U32<BE>,
+/// }
+///
+/// let mut device = NetDevice { status: U32::new(0) };
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut device);
+/// let volatile_ptr = volatile_ref.as_mut_ptr();
+///
+/// volatile_ptr.set_link_up(true);
+/// volatile_ptr.set_speed(100);
+/// assert_eq!(volatile_ptr.status().read_native(), 0x6401);
+/// assert_eq!(volatile_ptr.status().read().to_bytes(), [0, 0, 0x64, 0x01]);
+/// ```
+///
+/// # Batched updates
+///
+/// With `#[volatile(modify)]` on the struct, the trait additionally provides a `modify` method.
+/// It passes a `{T}VolatileModify` value to a closure, which records the requested updates through one setter per writable field and one setter per bit field.
+/// When the closure returns, each modified field is written exactly once in declaration order, and no other field is accessed.
+/// If only bit fields of a field were set, that field is updated through a single volatile read-modify-write.
+///
+/// ```
+/// use volatile::access::ReadOnly;
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Default)]
+/// #[volatile(modify)]
+/// pub struct Timer {
+///     #[volatile(bits(enable = 0, mode = 1..3))]
+///     control: u32,
+///     reload: u32,
+///     #[access(ReadOnly)]
+///     counter: u32,
+/// }
+///
+/// let mut timer = Timer { control: 0xf0, ..Default::default() };
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut timer);
+///
+/// volatile_ref.as_mut_ptr().modify(|m| m.reload(1000).enable(true).mode(0b10));
+/// assert_eq!(volatile_ref.as_ptr().control().read(), 0xf5);
+/// assert_eq!(volatile_ref.as_ptr().reload().read(), 1000);
+/// ```
+///
+/// # Strict layout
+///
+/// Register blocks usually have to match a layout given by a datasheet exactly.
+/// With `#[volatile(strict_layout)]` on the struct, the macro emits compile-time assertions that each field is [`Copy`] and that the struct does not contain any implicit padding, neither between fields nor at its end.
+/// Violations are reported at the offending field.
+///
+/// ```compile_fail
+/// use volatile::VolatileFieldAccess;
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// #[volatile(strict_layout)]
+/// pub struct DeviceConfig {
+///     status: u8,
+///     // error: implicit padding before field `data`
+///     data: u32,
+/// }
+/// ```
+///
+/// With `#[volatile(no_padding)]` instead, the macro performs the same checks and additionally implements `volatile::padding::NoPadding` for the struct if all field types implement it.
+/// This allows reading and writing the whole struct through `read_no_padding` and `write_no_padding`.
+/// The struct has to implement [`Copy`].
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess, Clone, Copy, Debug, Default, PartialEq)]
+/// #[volatile(no_padding)]
+/// pub struct Descriptor {
+///     address: u64,
+///     len: u32,
+///     flags: u32,
+/// }
+///
+/// let mut descriptor = Descriptor::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut descriptor);
+/// let value = Descriptor { address: 0x1000, len: 64, flags: 1 };
+/// volatile_ref.as_mut_ptr().write_no_padding(value);
+/// assert_eq!(volatile_ref.as_ptr().read_no_padding(), value);
+/// ```
+///
+/// # Field offsets
+///
+/// Fields can be annotated with the offset from the specification through `#[volatile(offset = N)]`.
+/// The macro then asserts at compile time that the field is actually at that offset, which catches accidentally reordered fields or wrong padding.
+/// To let the macro insert the padding instead, see [`volatile_register_block`](macro@volatile_register_block).
+///
+/// ```compile_fail
+/// use volatile::VolatileFieldAccess;
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// pub struct DeviceConfig {
+///     #[volatile(offset = 0x00)]
+///     status: u8,
+///     // error: field `data` is not at offset 0x01
+///     #[volatile(offset = 0x01)]
+///     data: u32,
+/// }
+/// ```
+///
+/// # Access width
+///
+/// Some devices only support accesses of a specific width, e.g., 32-bit accesses to a register that is logically a byte.
+/// With `#[volatile(width = N)]` on an unsigned integer field, the accessor returns a `volatile::width::WidePtr`, which accesses the naturally aligned `N`-bit word that contains the field and shifts and masks the value.
+/// Its `write` method performs a read-modify-write of that word, and snapshots and `defmt` formatting read the field through it as well.
+/// The macro asserts at compile time that the struct is aligned to the access width and that the field does not cross a word boundary.
+/// Such fields are not supported together with `#[volatile(write_all)]`, `#[volatile(reset)]`, `#[volatile(builder)]`, and `#[volatile(modify)]`.
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C, align(4))]
+/// #[derive(VolatileFieldAccess, Default)]
+/// pub struct Uart {
+///     #[volatile(width = 32)]
+///     data: u8,
+///     #[volatile(width = 32)]
+///     line_control: u8,
+/// }
+///
+/// let mut uart = Uart::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut uart);
+/// let volatile_ptr = volatile_ref.as_mut_ptr();
+///
+/// volatile_ptr.line_control().write(0x03);
+/// volatile_ptr.data().write(b'a');
+/// assert_eq!(volatile_ptr.line_control().read(), 0x03);
+/// assert_eq!(volatile_ptr.data().read(), b'a');
+/// ```
+///
+/// # Conditional fields
+///
+/// `#[cfg(...)]` attributes on fields are copied to all methods generated for that field, so register blocks that differ between targets or features can use the derive.
+/// Conditional fields are not supported together with `#[volatile(builder)]`, `#[volatile(modify)]`, `#[volatile(strict_layout)]`, `#[volatile(no_padding)]`, `#[volatile(metadata)]`, and `#[volatile(defmt)]`, since these depend on the exact set of fields.
+///
+/// # Field metadata
+///
+/// With `#[volatile(metadata)]` on the struct, the macro implements `volatile::metadata::VolatileFields` for it.
+/// The resulting `FIELDS` table contains the name, offset, size, and access permissions of every non-reserved field in declaration order, which is useful for register dumps and debug consoles.
+///
+/// ```
+/// use volatile::access::{AccessKind, ReadOnly};
+/// use volatile::metadata::VolatileFields;
+/// use volatile::VolatileFieldAccess;
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// #[volatile(metadata)]
+/// pub struct DeviceConfig {
+///     feature_select: u32,
+///     #[access(ReadOnly)]
+///     feature: u16,
+/// }
+///
+/// let feature = &DeviceConfig::FIELDS[1];
+/// assert_eq!(feature.name, "feature");
+/// assert_eq!(feature.offset, 4);
+/// assert_eq!(feature.size, 2);
+/// assert_eq!(feature.access, AccessKind::ReadOnly);
+/// ```
+///
+/// # `defmt` formatting
+///
+/// With the `defmt` feature of `volatile` and `#[volatile(defmt)]` on the struct, the trait additionally provides a `defmt` method.
+/// It returns a `{T}VolatileDefmt` adapter that implements `defmt::Format` by volatile-reading all readable fields whenever it is formatted.
+/// Like snapshots, it skips fields that cannot be read or are marked with `#[volatile(read_side_effects)]`.
+/// The generated code refers to `::defmt`, so the crate using the derive has to depend on `defmt` itself.
+///
+/// ```
+/// use volatile::access::{ReadOnly, WriteOnly};
+/// use volatile::{VolatileFieldAccess, VolatilePtr};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// #[volatile(defmt)]
+/// pub struct Uart {
+///     #[access(ReadOnly)]
+///     status: u32,
+///     #[access(WriteOnly)]
+///     tx_fifo: u32,
+/// }
+///
+/// fn log_state(uart: VolatilePtr<'_, Uart, ReadOnly>) {
+///     // Prints `Uart { status: 1 }`.
+///     defmt::info!("{}", uart.defmt());
+/// }
+/// ```
+///
+/// # Visibility
+///
+/// The generated trait and helper types have the visibility of the struct by default.
+/// It can be overridden with `#[volatile(vis = "...")]` on the struct, e.g., to keep the accessors of a public struct out of the public API of a driver crate.
+/// With `#[volatile(hidden)]`, the generated items are additionally marked with `#[doc(hidden)]`.
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatilePtr};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// #[volatile(vis = "pub(crate)", hidden)]
+/// pub struct Timer {
+///     counter: u32,
+/// }
+///
+/// let mut timer = Timer { counter: 3 };
+/// let volatile = unsafe { VolatilePtr::new((&mut timer).into()) };
+/// assert_eq!(volatile.counter().read(), 3);
+/// ```
+///
+/// # Accessor modules
+///
+/// The accessor methods are only available if the generated trait is in scope.
+/// With `#[volatile(accessors)]` on the struct, the macro additionally generates a `volatile_accessors` module next to the struct, which re-exports the trait anonymously.
+/// A different module name can be given with `#[volatile(accessors = "...")]`, which is required if several structs in the same module use this option.
+/// Derive macros cannot add items to modules that are defined elsewhere, but a crate can collect these modules in a single module by re-exporting them with glob imports.
+/// Other modules then bring all accessors into scope with a single glob import:
+///
+/// ```
+/// mod uart {
+///     use volatile::VolatileFieldAccess;
+///
+///     #[repr(C)]
+///     #[derive(VolatileFieldAccess)]
+///     #[volatile(accessors)]
+///     pub struct Uart {
+///         pub data: u32,
+///     }
+/// }
+///
+/// mod timer {
+///     use volatile::VolatileFieldAccess;
+///
+///     #[repr(C)]
+///     #[derive(VolatileFieldAccess)]
+///     #[volatile(accessors = "timer_accessors")]
+///     pub struct Timer {
+///         pub counter: u32,
+///     }
+/// }
+///
+/// pub mod volatile_accessors {
+///     pub use crate::timer::timer_accessors::*;
+///     pub use crate::uart::volatile_accessors::*;
+/// }
+///
+/// mod driver {
+///     use crate::volatile_accessors::*;
+///     use volatile::VolatilePtr;
+///
+///     pub fn tick(uart: VolatilePtr<'_, crate::uart::Uart>, timer: VolatilePtr<'_, crate::timer::Timer>) {
+///         uart.data().write(timer.counter().read());
+///     }
+/// }
+/// #
+/// # fn main() {
+/// #     let mut uart = uart::Uart { data: 0 };
+/// #     let mut timer = timer::Timer { counter: 7 };
+/// #     driver::tick(unsafe { volatile::VolatilePtr::new((&mut uart).into()) }, unsafe {
+/// #         volatile::VolatilePtr::new((&mut timer).into())
+/// #     });
+/// #     assert_eq!(uart.data, 7);
+/// # }
+/// ```
+///
+/// # Const generics
+///
+/// Structs with const generic parameters are supported, e.g., for register blocks with a configurable number of queues.
+/// The const parameters are forwarded to the generated trait, which is then implemented for every instantiation.
+/// Type and lifetime parameters are not supported, and neither is `#[volatile(strict_layout)]` for such structs.
+///
+/// ```
+/// use volatile::{VolatileFieldAccess, VolatileRef};
+///
+/// #[repr(C)]
+/// #[derive(VolatileFieldAccess)]
+/// pub struct Config<const N: usize> {
+///     num_queues: u32,
+///     queue_sizes: [u16; N],
+/// }
+///
+/// let mut config = Config { num_queues: 2, queue_sizes: [0; 2] };
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut config);
+/// let volatile_ptr = volatile_ref.as_mut_ptr();
+///
+/// assert_eq!(volatile_ptr.num_queues().read(), 2);
+/// volatile_ptr.queue_sizes().write([128, 256]);
+/// assert_eq!(volatile_ptr.queue_sizes().read(), [128, 256]);
+/// ```
+///
+/// # Reserved fields
+///
+/// Padding arrays and `volatile::padding::Reserved` fields that are only there to move the following fields to their offsets can be marked with `#[volatile(reserved)]`.
+/// Reserved fields don't get accessors, are never written, and are zeroed in snapshots.
+/// [`volatile_register_block`](macro@volatile_register_block) inserts such fields automatically.
+///
 /// # Details
 ///
 /// This macro generates a new trait (`{T}VolatileFieldAccess`) and implements it for `VolatilePtr<'a, T, ReadWrite>`.
@@ -100,7 +600,7 @@ mod volatile;
 ///     }
 /// }
 /// ```
-#[proc_macro_derive(VolatileFieldAccess, attributes(access))]
+#[proc_macro_derive(VolatileFieldAccess, attributes(access, volatile))]
 pub fn derive_volatile(item: TokenStream) -> TokenStream {
     match volatile::derive_volatile(parse_macro_input!(item)) {
         Ok(items) => {
@@ -113,3 +613,155 @@ pub fn derive_volatile(item: TokenStream) -> TokenStream {
         Err(e) => e.to_compile_error().into(),
     }
 }
+
+/// An attribute macro for register blocks with explicit field offsets.
+///
+/// Datasheets usually describe register blocks as a table of offsets.
+/// Instead of transcribing such a table into a manually padded `#[repr(C)]` struct, this macro lets you annotate each field with its `#[volatile(offset = N)]`, the same option that [`VolatileFieldAccess`](derive@VolatileFieldAccess) uses to check offsets.
+/// The macro then
+///
+/// - validates that the offsets are strictly increasing,
+/// - inserts reserved padding fields between the fields,
+/// - adds `#[repr(C)]` if the struct has no `repr` attribute,
+/// - asserts at compile time that each field ends up at its offset, and
+/// - derives [`VolatileFieldAccess`](derive@VolatileFieldAccess), so the struct gets the same accessor trait.
+///
+/// Padding fields have the type `volatile::padding::Reserved<N>`, which implements `Default` for gaps of any size, are marked with `#[volatile(reserved)]`, and don't get accessors.
+/// Passing `size = N` to the attribute pads the struct to a total size of `N` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use volatile::access::{ReadOnly, WriteOnly};
+/// use volatile::{volatile_register_block, VolatileRef};
+///
+/// #[volatile_register_block(size = 0x100)]
+/// #[derive(Default)]
+/// pub struct Uart {
+///     #[volatile(offset = 0x00)]
+///     #[access(WriteOnly)]
+///     data: u8,
+///     #[volatile(offset = 0x04)]
+///     #[access(ReadOnly)]
+///     status: u32,
+///     #[volatile(offset = 0x10)]
+///     control: u16,
+/// }
+///
+/// assert_eq!(core::mem::offset_of!(Uart, control), 0x10);
+/// assert_eq!(core::mem::size_of::<Uart>(), 0x100);
+///
+/// let mut uart = Uart::default();
+/// let mut volatile_ref = VolatileRef::from_mut_ref(&mut uart);
+/// volatile_ref.as_mut_ptr().control().write(3);
+/// assert_eq!(volatile_ref.as_ptr().control().read(), 3);
+/// ```
+///
+/// Overlapping fields are rejected at compile time:
+///
+/// ```compile_fail
+/// use volatile::volatile_register_block;
+///
+/// #[volatile_register_block]
+/// pub struct Uart {
+///     #[volatile(offset = 0x00)]
+///     data: u64,
+///     // error: field `status` at offset 0x04 overlaps the previous field
+///     #[volatile(offset = 0x04)]
+///     status: u32,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn volatile_register_block(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = register_block::Args::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
+
+    match register_block::volatile_register_block(args, parse_macro_input!(item)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Provides safe field projection for volatile pointers referencing structs.
+///
+/// This is the procedural implementation of `map_field!`, which `volatile` uses if the `derive` feature is enabled.
+/// It accepts the same syntax as the `macro_rules!` version, i.e.,
+///
+/// - any expression that evaluates to a `VolatilePtr` as base, e.g., `map_field!(device.regs().status)` or `map_field!((*self).regs.status)`, and
+/// - indexing the base pointer itself, e.g., `map_field!(volatile[1].status)` for a `VolatilePtr<[Regs; N]>`,
+///
+/// but reports errors, such as typos in field names, at the offending token.
+///
+/// The base expression is the innermost expression that is neither a field access nor an index.
+/// It is evaluated exactly once.
+///
+/// # Examples
+///
+/// ```
+/// use volatile::{map_field, VolatilePtr};
+///
+/// #[repr(C)]
+/// struct Queue { desc_addr: u64, size: u16 }
+/// #[repr(C)]
+/// struct Device { status: u32, queues: [Queue; 2] }
+///
+/// struct Driver<'a> { regs: VolatilePtr<'a, Device> }
+///
+/// impl<'a> Driver<'a> {
+///     fn regs(&self) -> VolatilePtr<'a, Device> {
+///         self.regs
+///     }
+/// }
+///
+/// let mut device = Device {
+///     status: 1,
+///     queues: [Queue { desc_addr: 0x1000, size: 256 }, Queue { desc_addr: 0x2000, size: 128 }],
+/// };
+/// let driver = Driver { regs: unsafe { VolatilePtr::new((&mut device).into()) } };
+///
+/// assert_eq!(map_field!(driver.regs().status).read(), 1);
+/// let i = 1;
+/// assert_eq!(map_field!(driver.regs().queues[i].desc_addr).read(), 0x2000);
+///
+/// let queues = map_field!(driver.regs().queues);
+/// assert_eq!(map_field!(queues[0].size).read(), 256);
+/// ```
+///
+/// The last field of a dynamically sized struct can be projected as well:
+///
+/// ```
+/// use volatile::{map_field, VolatilePtr};
+///
+/// #[repr(C)]
+/// struct Packet<T: ?Sized> { len: u16, data: T }
+///
+/// let mut packet = Packet { len: 3, data: [1u8, 2, 3] };
+/// let packet: &mut Packet<[u8]> = &mut packet;
+/// let volatile = unsafe { VolatilePtr::new(packet.into()) };
+///
+/// let data: VolatilePtr<[u8]> = map_field!(volatile.data);
+/// assert_eq!(data.as_raw_ptr().len(), 3);
+/// assert_eq!(map_field!(volatile.data[2]).read(), 3);
+/// ```
+///
+/// Indices are bounds checked at runtime, and constant indices into arrays are also checked at compile time.
+/// Like the `macro_rules!` version, projecting potentially unaligned fields of packed structs does not compile:
+///
+/// ```compile_fail
+/// use volatile::{map_field, VolatilePtr};
+///
+/// #[repr(packed)]
+/// struct Example { field_1: u8, field_2: usize }
+/// let mut value = Example { field_1: 15, field_2: 255 };
+/// let volatile = unsafe { VolatilePtr::new((&mut value).into()) };
+///
+/// let field_2 = map_field!(volatile.field_2);
+/// ```
+#[proc_macro]
+pub fn map_field(item: TokenStream) -> TokenStream {
+    match map_field::map_field(parse_macro_input!(item)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
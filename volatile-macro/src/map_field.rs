@@ -0,0 +1,156 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{Expr, ExprField, ExprIndex, ExprLit, Ident, Lit, Result};
+
+/// A single step of a place expression.
+enum Projection<'a> {
+    Field(&'a ExprField),
+    Index(&'a ExprIndex),
+}
+
+impl ToTokens for Projection<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Projection::Field(field) => {
+                field.dot_token.to_tokens(tokens);
+                field.member.to_tokens(tokens);
+            }
+            Projection::Index(index) => {
+                index.bracket_token.surround(tokens, |tokens| {
+                    index.index.to_tokens(tokens);
+                });
+            }
+        }
+    }
+}
+
+/// Splits `input` into the expression of the volatile pointer and the projections applied to it.
+///
+/// The pointer is the innermost expression that is neither a field access nor an index.
+fn split_place(input: &Expr) -> Result<(&Expr, Vec<Projection<'_>>)> {
+    let mut projections = vec![];
+    let mut expr = input;
+    loop {
+        match expr {
+            Expr::Field(field) => {
+                projections.push(Projection::Field(field));
+                expr = &field.base;
+            }
+            Expr::Index(index) => {
+                projections.push(Projection::Index(index));
+                expr = &index.expr;
+            }
+            _ => break,
+        }
+    }
+
+    if projections.is_empty() {
+        bail!(
+            input,
+            "expected a place such as `volatile.field` or `volatile.array[index]`"
+        );
+    }
+    projections.reverse();
+    Ok((expr, projections))
+}
+
+pub fn map_field(input: Expr) -> Result<TokenStream> {
+    let (volatile, projections) = split_place(&input)?;
+
+    // Use mixed-site hygiene so that the helper variables can't clash with the user's variables.
+    let volatile_ident = Ident::new("volatile", Span::mixed_site());
+    let value_ident = Ident::new("value", Span::mixed_site());
+    let ptr_ident = Ident::new("ptr", Span::mixed_site());
+
+    // Only allow indexing arrays and slices, which does not create references, and check constant
+    // indices at compile time.
+    let mut index_checks = vec![];
+    for (i, projection) in projections.iter().enumerate() {
+        let Projection::Index(index) = projection else {
+            continue;
+        };
+        let prefix = &projections[..i];
+        let check = match &*index.index {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => quote_spanned! {lit.span()=>
+                ::volatile::__MapFieldIndex::check_const_index::<#lit>(&(*#value_ident) #(#prefix)*);
+            },
+            _ => quote_spanned! {index.bracket_token.span.join()=>
+                ::volatile::__MapFieldIndex::check_index(&(*#value_ident) #(#prefix)*);
+            },
+        };
+        index_checks.push(check);
+    }
+
+    Ok(quote! {{
+        // The base may be parenthesized, e.g., `(*self)`.
+        #[allow(unused_parens)]
+        let #volatile_ident = #volatile;
+
+        // Simulate creating a reference to the field. This is done to make
+        // sure that the field is not potentially unaligned. The body of the
+        // if statement will never be executed, so it can never cause any UB.
+        if false {
+            let #value_ident = unsafe { &*#volatile_ident.as_raw_ptr().as_ptr() };
+            let _ref_to_field = &#value_ident #(#projections)*;
+            #(#index_checks)*
+        }
+
+        unsafe {
+            #volatile_ident.map(|#ptr_ident| {
+                ::core::ptr::NonNull::new(
+                    ::core::ptr::addr_of_mut!((*#ptr_ident.as_ptr()) #(#projections)*)
+                ).unwrap()
+            })
+        }
+    }})
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn place(input: Expr) -> Result<(String, String)> {
+        let (volatile, projections) = split_place(&input)?;
+        Ok((
+            volatile.to_token_stream().to_string(),
+            quote!(#(#projections)*).to_string(),
+        ))
+    }
+
+    #[test]
+    fn test_split_place() -> Result<()> {
+        assert_eq!(
+            place(parse_quote! { volatile.field })?,
+            ("volatile".to_string(), quote!(.field).to_string())
+        );
+        assert_eq!(
+            place(parse_quote! { volatile.queues[i + 1].desc.0 })?,
+            (
+                "volatile".to_string(),
+                quote!(.queues[i + 1].desc.0).to_string()
+            )
+        );
+        assert_eq!(
+            place(parse_quote! { volatile[2].field })?,
+            ("volatile".to_string(), quote!([2].field).to_string())
+        );
+        assert_eq!(
+            place(parse_quote! { device.regs().status })?,
+            ("device . regs ()".to_string(), quote!(.status).to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_place() {
+        let err = place(parse_quote! { volatile }).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "expected a place such as `volatile.field` or `volatile.array[index]`"
+        );
+    }
+}
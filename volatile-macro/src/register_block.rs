@@ -0,0 +1,250 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, ToTokens};
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_quote, parse_quote_spanned, Attribute, Expr, ExprLit, Field, Fields, Item, ItemStruct,
+    Lit, LitInt, Meta, Result, Token, Type,
+};
+
+/// Arguments of the `#[volatile_register_block(...)]` attribute.
+#[derive(Default)]
+pub struct Args {
+    size: Option<LitInt>,
+}
+
+impl Args {
+    pub fn parse(&mut self, meta: ParseNestedMeta<'_>) -> Result<()> {
+        if meta.path.is_ident("size") {
+            let size: LitInt = meta.value()?.parse()?;
+            size.base10_parse::<usize>()?;
+            self.size = Some(size);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `#[volatile_register_block]` argument"))
+        }
+    }
+}
+
+/// Returns the `N` of the `#[volatile(offset = N)]` option of the field.
+///
+/// The option is left in place, so the derive asserts that the field ends up at that offset.
+fn field_offset(field: &Field) -> Result<(LitInt, usize)> {
+    let mut offset = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("volatile") {
+            continue;
+        }
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in nested {
+            let Meta::NameValue(name_value) = meta else {
+                continue;
+            };
+            if !name_value.path.is_ident("offset") {
+                continue;
+            }
+            if offset.is_some() {
+                bail!(name_value, "duplicate `#[volatile(offset = N)]` option");
+            }
+            let Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) = name_value.value
+            else {
+                bail!(
+                    name_value.value,
+                    "the offset in a `#[volatile_register_block]` must be an integer literal"
+                );
+            };
+            let value = lit.base10_parse::<usize>()?;
+            offset = Some((lit, value));
+        }
+    }
+
+    match offset {
+        Some(offset) => Ok(offset),
+        None => bail!(
+            &field.ident,
+            "fields of a `#[volatile_register_block]` require a `#[volatile(offset = N)]` attribute"
+        ),
+    }
+}
+
+/// A reserved field that fills the gap between `prev_end` and `offset`.
+///
+/// The field uses `volatile::padding::Reserved` instead of a byte array, which only implements
+/// `Default` for up to 32 bytes.
+fn reserved_field(index: usize, prev_end: Expr, offset: &LitInt, message: &str) -> Field {
+    let ident = format_ident!("_reserved{}", index);
+    parse_quote_spanned! {offset.span()=>
+        #[volatile(reserved)]
+        #ident: ::volatile::padding::Reserved<{
+            ::core::assert!(#offset >= #prev_end, #message);
+            #offset - (#prev_end)
+        }>
+    }
+}
+
+fn has_derive(attrs: &[Attribute]) -> Result<bool> {
+    for attr in attrs {
+        if attr.path().is_ident("derive") {
+            let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            for meta in nested {
+                let path = meta.path();
+                if path.segments.last().unwrap().ident == "VolatileFieldAccess" {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+pub fn volatile_register_block(args: Args, mut input: ItemStruct) -> Result<TokenStream> {
+    let Fields::Named(named) = &mut input.fields else {
+        bail!(
+            &input.fields,
+            "#[volatile_register_block] can only be used on structs with named fields"
+        );
+    };
+
+    let struct_ident = &input.ident;
+    let mut fields = Punctuated::<Field, Token![,]>::new();
+    let mut assertions: Vec<Item> = vec![];
+    // The previous field and its offset.
+    let mut prev: Option<(Type, LitInt, usize)> = None;
+    let mut reserved = 0;
+
+    for field in std::mem::take(&mut named.named) {
+        let (offset_lit, offset) = field_offset(&field)?;
+        let ident = field.ident.clone().unwrap();
+        if let Some(cfg) = field.attrs.iter().find(|attr| attr.path().is_ident("cfg")) {
+            bail!(
+                cfg,
+                "`#[cfg]` on fields is not supported in a `#[volatile_register_block]`"
+            );
+        }
+
+        let prev_end: Expr = match &prev {
+            Some((prev_ty, prev_lit, prev_offset)) => {
+                if offset <= *prev_offset {
+                    bail!(
+                        &offset_lit,
+                        "offset {offset_lit} of field `{ident}` must be greater than the previous offset {prev_lit}"
+                    );
+                }
+                parse_quote! { #prev_lit + ::core::mem::size_of::<#prev_ty>() }
+            }
+            None => parse_quote! { 0 },
+        };
+        if prev.is_some() || offset != 0 {
+            let message =
+                format!("field `{ident}` at offset {offset_lit} overlaps the previous field");
+            fields.push(reserved_field(reserved, prev_end, &offset_lit, &message));
+            reserved += 1;
+        }
+
+        prev = Some((field.ty.clone(), offset_lit, offset));
+        fields.push(field);
+    }
+
+    if let Some(size) = &args.size {
+        let prev_end: Expr = match &prev {
+            Some((prev_ty, prev_lit, _)) => {
+                parse_quote! { #prev_lit + ::core::mem::size_of::<#prev_ty>() }
+            }
+            None => parse_quote! { 0 },
+        };
+        let message = format!("the fields of `{struct_ident}` exceed its size {size}");
+        fields.push(reserved_field(reserved, prev_end, size, &message));
+
+        let message = format!("`{struct_ident}` does not have size {size}");
+        assertions.push(parse_quote_spanned! {size.span()=>
+            const _: () = ::core::assert!(
+                ::core::mem::size_of::<#struct_ident>() == #size,
+                #message
+            );
+        });
+    }
+
+    named.named = fields;
+
+    // The derive has to come first, since it introduces the `#[volatile]` helper attribute.
+    if !has_derive(&input.attrs)? {
+        input.attrs.insert(
+            0,
+            parse_quote! { #[derive(::volatile::VolatileFieldAccess)] },
+        );
+    }
+    if !input.attrs.iter().any(|attr| attr.path().is_ident("repr")) {
+        input.attrs.insert(0, parse_quote! { #[repr(C)] });
+    }
+
+    let mut tokens = input.into_token_stream();
+    for assertion in &assertions {
+        assertion.to_tokens(&mut tokens);
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_register_block() -> Result<()> {
+        let input = parse_quote! {
+            pub struct Uart {
+                #[volatile(offset = 0x0)]
+                data: u32,
+                #[volatile(offset = 0x10)]
+                #[access(ReadOnly)]
+                status: u32,
+            }
+        };
+
+        let result = volatile_register_block(Args::default(), input)?;
+
+        let expected: TokenStream = quote! {
+            #[repr(C)]
+            #[derive(::volatile::VolatileFieldAccess)]
+            pub struct Uart {
+                #[volatile(offset = 0x0)]
+                data: u32,
+                #[volatile(reserved)]
+                _reserved0: ::volatile::padding::Reserved<{
+                    ::core::assert!(0x10 >= 0x0 + ::core::mem::size_of::<u32>(), "field `status` at offset 0x10 overlaps the previous field");
+                    0x10 - (0x0 + ::core::mem::size_of::<u32>())
+                }>,
+                #[volatile(offset = 0x10)]
+                #[access(ReadOnly)]
+                status: u32
+            }
+        };
+
+        assert_eq!(expected.to_string(), result.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_monotonic() {
+        let input = parse_quote! {
+            pub struct Uart {
+                #[volatile(offset = 0x10)]
+                data: u32,
+                #[volatile(offset = 0x8)]
+                status: u32,
+            }
+        };
+
+        let err = volatile_register_block(Args::default(), input)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "offset 0x8 of field `status` must be greater than the previous offset 0x10"
+        );
+    }
+}
@@ -1,8 +1,13 @@
-use quote::format_ident;
+use proc_macro2::Literal;
+use quote::{format_ident, ToTokens};
+use syn::meta::ParseNestedMeta;
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::{
-    parse_quote, Attribute, Fields, Ident, Item, ItemImpl, ItemStruct, ItemTrait, Meta, Path,
-    Result, Signature, Token, Visibility,
+    parse_quote, parse_quote_spanned, Attribute, Block, ConstParam, Expr, ExprLit, ExprRange,
+    Field, FieldValue, Fields, GenericArgument, GenericParam, Ident, ImplItemFn, Item, ItemImpl,
+    ItemStruct, ItemTrait, Lit, LitStr, Meta, Path, PathArguments, RangeLimits, Result, Signature,
+    Stmt, Token, Type, Visibility,
 };
 
 fn validate_input(input: &ItemStruct) -> Result<()> {
@@ -13,10 +18,18 @@ fn validate_input(input: &ItemStruct) -> Result<()> {
         );
     }
 
-    if !input.generics.params.is_empty() {
+    for param in &input.generics.params {
+        if !matches!(param, GenericParam::Const(_)) {
+            bail!(
+                param,
+                "#[derive(VolatileFieldAccess)] only supports const generic parameters"
+            );
+        }
+    }
+    if let Some(where_clause) = &input.generics.where_clause {
         bail!(
-            &input.generics,
-            "#[derive(VolatileFieldAccess)] cannot be used with generic structs"
+            where_clause,
+            "#[derive(VolatileFieldAccess)] does not support where clauses"
         );
     }
 
@@ -43,13 +56,332 @@ fn validate_input(input: &ItemStruct) -> Result<()> {
     Ok(())
 }
 
+/// The access permissions of a field, as far as the macro can tell from the `#[access]` path.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum AccessKind {
+    ReadWrite,
+    ReadOnly,
+    WriteOnly,
+    NoAccess,
+}
+
+impl AccessKind {
+    fn from_path(path: &Path) -> Result<Self> {
+        let ident = &path.segments.last().unwrap().ident;
+        let kind = if ident == "ReadWrite" {
+            Self::ReadWrite
+        } else if ident == "ReadOnly" {
+            Self::ReadOnly
+        } else if ident == "WriteOnly" {
+            Self::WriteOnly
+        } else if ident == "NoAccess" {
+            Self::NoAccess
+        } else {
+            bail!(
+                path,
+                "unknown access type, expected one of `ReadWrite`, `ReadOnly`, `WriteOnly`, or `NoAccess`"
+            );
+        };
+        Ok(kind)
+    }
+
+    fn is_readable(self) -> bool {
+        matches!(self, Self::ReadWrite | Self::ReadOnly)
+    }
+
+    fn is_writable(self) -> bool {
+        matches!(self, Self::ReadWrite | Self::WriteOnly)
+    }
+}
+
+/// Options from `#[volatile(...)]` attributes on the struct.
+#[derive(Default)]
+struct StructOptions {
+    /// The default access for fields without an `#[access]` attribute.
+    access: Option<Path>,
+    snapshot: bool,
+    write_all: bool,
+    builder: bool,
+    strict_layout: bool,
+    /// Implies `strict_layout`.
+    no_padding: bool,
+    metadata: bool,
+    defmt: bool,
+    reset: bool,
+    modify: bool,
+    /// Overrides the visibility of the generated items.
+    vis: Option<Visibility>,
+    /// Hides the generated items from the documentation.
+    hidden: bool,
+    /// The name of the module that re-exports the generated trait.
+    accessors: Option<Ident>,
+}
+
+impl StructOptions {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("volatile") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("access") {
+                    options.access = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("snapshot") {
+                    options.snapshot = true;
+                    Ok(())
+                } else if meta.path.is_ident("write_all") {
+                    options.write_all = true;
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    options.builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("strict_layout") {
+                    options.strict_layout = true;
+                    Ok(())
+                } else if meta.path.is_ident("no_padding") {
+                    options.strict_layout = true;
+                    options.no_padding = true;
+                    Ok(())
+                } else if meta.path.is_ident("metadata") {
+                    options.metadata = true;
+                    Ok(())
+                } else if meta.path.is_ident("reset") {
+                    options.reset = true;
+                    Ok(())
+                } else if meta.path.is_ident("modify") {
+                    options.modify = true;
+                    Ok(())
+                } else if meta.path.is_ident("defmt") {
+                    if !cfg!(feature = "defmt") {
+                        return Err(meta.error(
+                            "`#[volatile(defmt)]` requires the `defmt` feature of `volatile`",
+                        ));
+                    }
+                    options.defmt = true;
+                    Ok(())
+                } else if meta.path.is_ident("vis") {
+                    let vis: LitStr = meta.value()?.parse()?;
+                    options.vis = Some(vis.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("hidden") {
+                    options.hidden = true;
+                    Ok(())
+                } else if meta.path.is_ident("accessors") {
+                    let ident = if meta.input.peek(Token![=]) {
+                        let name: LitStr = meta.value()?.parse()?;
+                        name.parse()?
+                    } else {
+                        format_ident!("volatile_accessors")
+                    };
+                    options.accessors = Some(ident);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported `#[volatile]` struct option"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// A named bit range inside an integer field, declared through `#[volatile(bits(...))]`.
+struct BitField {
+    ident: Ident,
+    /// The index of the lowest bit.
+    shift: u32,
+    /// The number of bits.
+    width: u32,
+    /// Whether the bit field was declared as a single bit and should be accessed as `bool`.
+    single: bool,
+}
+
+impl BitField {
+    fn parse(meta: ParseNestedMeta<'_>) -> Result<Self> {
+        let Some(ident) = meta.path.get_ident().cloned() else {
+            return Err(meta.error("expected bit field name"));
+        };
+
+        fn parse_bit(expr: &Expr) -> Result<u32> {
+            match expr {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                }) => lit.base10_parse(),
+                _ => bail!(expr, "expected integer literal"),
+            }
+        }
+
+        let expr: Expr = meta.value()?.parse()?;
+        let bit_field = match &expr {
+            Expr::Range(ExprRange {
+                start: Some(start),
+                limits,
+                end: Some(end),
+                ..
+            }) => {
+                let start = parse_bit(start)?;
+                let end = match limits {
+                    RangeLimits::HalfOpen(_) => parse_bit(end)?,
+                    RangeLimits::Closed(_) => parse_bit(end)? + 1,
+                };
+                if end <= start {
+                    bail!(&expr, "bit range must not be empty");
+                }
+                BitField {
+                    ident,
+                    shift: start,
+                    width: end - start,
+                    single: false,
+                }
+            }
+            expr => BitField {
+                ident,
+                shift: parse_bit(expr)?,
+                width: 1,
+                single: true,
+            },
+        };
+        if bit_field.shift + bit_field.width > 128 {
+            bail!(&expr, "bit range exceeds 128 bits");
+        }
+        Ok(bit_field)
+    }
+
+    fn mask(&self) -> Literal {
+        Literal::u128_unsuffixed(u128::MAX >> (128 - self.width))
+    }
+}
+
+/// Options from `#[volatile(...)]` attributes on a field.
+#[derive(Default)]
+struct FieldOptions {
+    read_side_effects: bool,
+    bits: Vec<BitField>,
+    reserved: bool,
+    /// The value written by `reset` instead of `Default::default()`.
+    reset: Option<Expr>,
+    /// The expected offset of the field in bytes.
+    offset: Option<Expr>,
+    /// The type of the mandated access width, given in bits.
+    width: Option<Type>,
+}
+
+impl FieldOptions {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("volatile") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("read_side_effects") {
+                    options.read_side_effects = true;
+                    Ok(())
+                } else if meta.path.is_ident("reserved") {
+                    options.reserved = true;
+                    Ok(())
+                } else if meta.path.is_ident("reset") {
+                    options.reset = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("offset") {
+                    options.offset = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("width") {
+                    let bits: syn::LitInt = meta.value()?.parse()?;
+                    let ty = match bits.base10_parse::<u32>()? {
+                        8 => parse_quote! { u8 },
+                        16 => parse_quote! { u16 },
+                        32 => parse_quote! { u32 },
+                        64 => parse_quote! { u64 },
+                        _ => bail!(bits, "the access width must be 8, 16, 32, or 64 bits"),
+                    };
+                    options.width = Some(ty);
+                    Ok(())
+                } else if meta.path.is_ident("bits") {
+                    meta.parse_nested_meta(|meta| {
+                        options.bits.push(BitField::parse(meta)?);
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("unsupported `#[volatile]` field option"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// Returns the native type of a recognized fixed-endian wrapper type.
+///
+/// This recognizes `Le<T>` and `Be<T>` as well as the byte order aware integers of
+/// `zerocopy::byteorder`, such as `U32<LE>`. The recognition is purely syntactic, the conversion
+/// itself goes through `volatile::endian::Endian`.
+fn endian_native_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    let ident = segment.ident.to_string();
+    let native = match ident.as_str() {
+        "Le" | "Be" => match arguments.args.first()? {
+            GenericArgument::Type(ty) => return Some(ty.clone()),
+            _ => return None,
+        },
+        "U16" | "U32" | "U64" | "U128" | "Usize" | "I16" | "I32" | "I64" | "I128" | "Isize" => {
+            ident.to_lowercase()
+        }
+        _ => return None,
+    };
+    let native = Ident::new(&native, segment.ident.span());
+    Some(parse_quote! { #native })
+}
+
+/// Returns whether `ty` is `volatile::padding::Reserved<N>`.
+///
+/// Like [`endian_native_type`], the recognition is purely syntactic.
+fn is_reserved_type(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Reserved")
+}
+
+struct ParsedField {
+    ident: Ident,
+    ty: Type,
+    /// The native type if `ty` is a recognized fixed-endian wrapper.
+    native: Option<Type>,
+    access: Path,
+    /// The `#[cfg]` attributes of the field, which are copied to all items generated for it.
+    cfgs: Vec<Attribute>,
+    options: FieldOptions,
+}
+
 struct ParsedInput {
     attrs: Vec<Attribute>,
     vis: Visibility,
     trait_ident: Ident,
     struct_ident: Ident,
+    /// The struct type including its const generic arguments.
+    struct_ty: Type,
+    /// The const generic parameters of the struct without defaults.
+    const_params: Vec<ConstParam>,
+    /// The const generic parameters as arguments.
+    const_args: Vec<Ident>,
     method_attrs: Vec<Vec<Attribute>>,
     sigs: Vec<Signature>,
+    /// The bodies of the field accessors.
+    accessors: Vec<Expr>,
+    fields: Vec<ParsedField>,
+    options: StructOptions,
 }
 
 fn parse_input(input: &ItemStruct) -> Result<ParsedInput> {
@@ -60,204 +392,2270 @@ fn parse_input(input: &ItemStruct) -> Result<ParsedInput> {
         }
     }
 
-    let mut method_attrs = vec![];
-    for field in &input.fields {
-        let mut attrs = vec![];
-        for attr in &field.attrs {
-            if attr.path().is_ident("doc") {
-                attrs.push(attr.clone());
-            }
-        }
-        method_attrs.push(attrs);
+    let options = StructOptions::parse(&input.attrs)?;
+    if options.hidden {
+        attrs.push(parse_quote! { #[doc(hidden)] });
     }
+    let default_access = match &options.access {
+        Some(access) => access.clone(),
+        None => parse_quote! { ::volatile::access::ReadWrite },
+    };
 
+    let mut method_attrs = vec![];
     let mut sigs = vec![];
+    let mut accessors = vec![];
+    let mut fields = vec![];
     for field in &input.fields {
         let ident = field.ident.as_ref().unwrap();
         let ty = &field.ty;
+        let field_options = FieldOptions::parse(&field.attrs)?;
+        let cfgs: Vec<Attribute> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .cloned()
+            .collect();
 
-        let mut access: Path = parse_quote! { ::volatile::access::ReadWrite };
+        let mut access = default_access.clone();
         for attr in &field.attrs {
             if attr.path().is_ident("access") {
                 access = attr.parse_args()?;
             }
         }
 
-        let sig = parse_quote! {
-            fn #ident(self) -> ::volatile::VolatilePtr<'a, #ty, A::Restricted>
-            where
-                A: ::volatile::access::RestrictAccess<#access>
-        };
-        sigs.push(sig);
+        if field_options.reserved {
+            if !matches!(ty, Type::Array(_)) && !is_reserved_type(ty) {
+                bail!(
+                    ty,
+                    "reserved fields must be arrays or `volatile::padding::Reserved`"
+                );
+            }
+            if !field_options.bits.is_empty() {
+                bail!(ident, "reserved fields cannot have bit fields");
+            }
+        } else {
+            let mut attrs = vec![];
+            for attr in &field.attrs {
+                if attr.path().is_ident("doc") || attr.path().is_ident("cfg") {
+                    attrs.push(attr.clone());
+                }
+            }
+            method_attrs.push(attrs);
+
+            let (sig, accessor) = match &field_options.width {
+                Some(width) => (
+                    parse_quote! {
+                        fn #ident(self) -> ::volatile::width::WidePtr<'a, #ty, #width, A::Restricted>
+                        where
+                            A: ::volatile::access::RestrictAccess<#access>
+                    },
+                    parse_quote! {
+                        // SAFETY: The containing word is within the struct, which is checked by the
+                        // generated assertions.
+                        unsafe { ::volatile::width::WidePtr::new(::volatile::map_field!(self.#ident).restrict()) }
+                    },
+                ),
+                None => (
+                    parse_quote! {
+                        fn #ident(self) -> ::volatile::VolatilePtr<'a, #ty, A::Restricted>
+                        where
+                            A: ::volatile::access::RestrictAccess<#access>
+                    },
+                    parse_quote! { ::volatile::map_field!(self.#ident).restrict() },
+                ),
+            };
+            sigs.push(sig);
+            accessors.push(accessor);
+        }
+
+        if let Some(width) = &field_options.width {
+            if field_options.reserved {
+                bail!(width, "reserved fields cannot have an access width");
+            }
+            if endian_native_type(ty).is_some() {
+                bail!(width, "fixed-endian fields cannot have an access width");
+            }
+            let unsupported = [
+                ("write_all", options.write_all),
+                ("reset", options.reset),
+                ("builder", options.builder),
+                ("modify", options.modify),
+            ];
+            if let Some((option, _)) = unsupported.iter().find(|(_, enabled)| *enabled) {
+                bail!(
+                    width,
+                    "`#[volatile(width = ...)]` is not supported with `#[volatile({option})]`"
+                );
+            }
+        }
+
+        if let Some(reset) = &field_options.reset {
+            if !options.reset {
+                bail!(
+                    reset,
+                    "reset values require `#[volatile(reset)]` on the struct"
+                );
+            }
+            if field_options.reserved {
+                bail!(reset, "reserved fields cannot have reset values");
+            }
+        }
+
+        if let Some(cfg) = cfgs.first() {
+            let unsupported = [
+                ("builder", options.builder),
+                ("modify", options.modify),
+                ("no_padding", options.no_padding),
+                ("strict_layout", options.strict_layout),
+                ("metadata", options.metadata),
+                ("defmt", options.defmt),
+            ];
+            if let Some((option, _)) = unsupported.iter().find(|(_, enabled)| *enabled) {
+                bail!(
+                    cfg,
+                    "`#[cfg]` on fields is not supported with `#[volatile({option})]`"
+                );
+            }
+        }
+
+        fields.push(ParsedField {
+            ident: ident.clone(),
+            ty: ty.clone(),
+            native: endian_native_type(ty),
+            access,
+            cfgs,
+            options: field_options,
+        });
+    }
+
+    let const_params: Vec<ConstParam> = input
+        .generics
+        .const_params()
+        .map(|param| ConstParam {
+            attrs: vec![],
+            eq_token: None,
+            default: None,
+            ..param.clone()
+        })
+        .collect();
+    let const_args: Vec<Ident> = const_params
+        .iter()
+        .map(|param| param.ident.clone())
+        .collect();
+    let struct_ident = &input.ident;
+    let struct_ty = if const_args.is_empty() {
+        parse_quote! { #struct_ident }
+    } else {
+        parse_quote! { #struct_ident<#(#const_args),*> }
+    };
+
+    if let Some(param) = const_params.first() {
+        if options.strict_layout {
+            let option = if options.no_padding {
+                "no_padding"
+            } else {
+                "strict_layout"
+            };
+            bail!(
+                param,
+                "`#[volatile({option})]` is not supported for const generic structs"
+            );
+        }
+        if let Some(offset) = fields
+            .iter()
+            .find_map(|field| field.options.offset.as_ref())
+        {
+            bail!(
+                offset,
+                "`#[volatile(offset = ...)]` is not supported for const generic structs"
+            );
+        }
+        if let Some(width) = fields.iter().find_map(|field| field.options.width.as_ref()) {
+            bail!(
+                width,
+                "`#[volatile(width = ...)]` is not supported for const generic structs"
+            );
+        }
     }
 
     Ok(ParsedInput {
         attrs,
-        vis: input.vis.clone(),
+        vis: options.vis.clone().unwrap_or_else(|| input.vis.clone()),
         trait_ident: format_ident!("{}VolatileFieldAccess", input.ident),
         struct_ident: input.ident.clone(),
+        struct_ty,
+        const_params,
+        const_args,
         method_attrs,
         sigs,
+        accessors,
+        fields,
+        options,
     })
 }
 
-fn emit_trait(
-    ParsedInput {
-        attrs,
-        vis,
-        trait_ident,
-        method_attrs,
-        sigs,
-        ..
-    }: &ParsedInput,
-) -> ItemTrait {
-    parse_quote! {
-        #(#attrs)*
-        #[allow(non_camel_case_types)]
-        #vis trait #trait_ident <'a, A> {
-            #(
-                #(#method_attrs)*
-                #sigs;
-            )*
-        }
+/// A pointer to `field` of the struct behind `base`, which accesses it at its mandated width.
+fn field_ptr(field: &ParsedField, base: &Expr) -> Expr {
+    let ident = &field.ident;
+    match &field.options.width {
+        Some(width) => parse_quote! {
+            // SAFETY: The containing word is within the struct, which is checked by the
+            // generated assertions.
+            unsafe {
+                ::volatile::width::WidePtr::<_, #width, _>::new(::volatile::map_field!(#base.#ident))
+            }
+        },
+        None => parse_quote! { ::volatile::map_field!(#base.#ident) },
     }
 }
 
-fn emit_impl(
+/// Signature and body of the generated `snapshot` method.
+fn emit_snapshot(
     ParsedInput {
-        trait_ident,
         struct_ident,
-        sigs,
+        struct_ty,
+        fields,
         ..
     }: &ParsedInput,
-) -> ItemImpl {
-    let fields = sigs.iter().map(|sig| &sig.ident);
+) -> Result<(Signature, Block)> {
+    let sig = parse_quote! {
+        fn snapshot(self) -> #struct_ty
+        where
+            A: ::volatile::access::Readable
+    };
 
-    parse_quote! {
-        #[automatically_derived]
-        impl<'a, A> #trait_ident<'a, A> for ::volatile::VolatilePtr<'a, #struct_ident, A> {
-            #(
-                #sigs,
-                {
-                    ::volatile::map_field!(self.#fields).restrict()
+    let mut values: Vec<Expr> = vec![];
+    for field in fields {
+        if field.options.reserved {
+            match &field.ty {
+                Type::Array(array) => {
+                    let len = &array.len;
+                    values.push(parse_quote! { [0; #len] });
                 }
-            )*
+                _ => values.push(parse_quote! { ::volatile::padding::Reserved::new() }),
+            }
+            continue;
         }
+        let readable = AccessKind::from_path(&field.access)?.is_readable();
+        let value = if readable && !field.options.read_side_effects {
+            let field_ptr = field_ptr(field, &parse_quote! { self });
+            parse_quote! { #field_ptr.read() }
+        } else {
+            parse_quote! { ::core::default::Default::default() }
+        };
+        values.push(value);
     }
+    let idents = fields.iter().map(|field| &field.ident);
+    let cfgs = fields.iter().map(|field| &field.cfgs);
+
+    let body = parse_quote! {{
+        #struct_ident {
+            #(#(#cfgs)* #idents: #values,)*
+        }
+    }};
+    Ok((sig, body))
 }
 
-pub fn derive_volatile(input: ItemStruct) -> Result<Vec<Item>> {
-    validate_input(&input)?;
-    let parsed_input = parse_input(&input)?;
-    let item_trait = emit_trait(&parsed_input);
-    let item_impl = emit_impl(&parsed_input);
-    Ok(vec![Item::Trait(item_trait), Item::Impl(item_impl)])
+/// Signatures and bodies of the generated `write_all` and `init` methods.
+fn emit_write_all(
+    ParsedInput {
+        struct_ty, fields, ..
+    }: &ParsedInput,
+) -> Result<[(Signature, Block); 2]> {
+    let write_all_sig = parse_quote! {
+        fn write_all(self, value: &#struct_ty)
+        where
+            A: ::volatile::access::Writable
+    };
+    let init_sig = parse_quote! {
+        fn init(self, value: &#struct_ty)
+        where
+            A: ::volatile::access::Writable
+    };
+
+    let mut idents = vec![];
+    let mut cfgs = vec![];
+    for field in fields {
+        if !field.options.reserved && AccessKind::from_path(&field.access)?.is_writable() {
+            idents.push(&field.ident);
+            cfgs.push(&field.cfgs);
+        }
+    }
+
+    let write_all_body = parse_quote! {{
+        #(
+            #(#cfgs)*
+            ::volatile::map_field!(self.#idents).write(value.#idents);
+        )*
+    }};
+    let init_body = parse_quote! {{
+        ::core::sync::atomic::fence(::core::sync::atomic::Ordering::SeqCst);
+        self.write_all(value);
+        ::core::sync::atomic::fence(::core::sync::atomic::Ordering::SeqCst);
+    }};
+    Ok([(write_all_sig, write_all_body), (init_sig, init_body)])
 }
 
-#[cfg(test)]
-mod tests {
-    use quote::{quote, ToTokens};
+/// Signature and body of the generated `reset` method.
+///
+/// Fixed-endian fields with an explicit reset value are written in native byte order.
+fn emit_reset(ParsedInput { fields, .. }: &ParsedInput) -> Result<(Signature, Block)> {
+    let sig = parse_quote! {
+        fn reset(self)
+        where
+            A: ::volatile::access::Writable
+    };
 
-    use super::*;
+    let mut writes: Vec<Stmt> = vec![];
+    for field in fields {
+        if field.options.reserved || !AccessKind::from_path(&field.access)?.is_writable() {
+            continue;
+        }
+        let ParsedField { ident, cfgs, .. } = field;
+        let write = match (&field.options.reset, &field.native) {
+            (Some(value), Some(_)) => parse_quote! {
+                #(#cfgs)*
+                ::volatile::map_field!(self.#ident).write_native(#value);
+            },
+            (Some(value), None) => parse_quote! {
+                #(#cfgs)*
+                ::volatile::map_field!(self.#ident).write(#value);
+            },
+            (None, _) => parse_quote! {
+                #(#cfgs)*
+                ::volatile::map_field!(self.#ident).write(::core::default::Default::default());
+            },
+        };
+        writes.push(write);
+    }
 
-    #[test]
-    fn test_derive() -> Result<()> {
-        let input = parse_quote! {
-            /// Struct documentation.
-            ///
-            /// This is a wonderful struct.
-            #[repr(C)]
-            #[derive(VolatileFieldAccess, Default)]
-            pub struct DeviceConfig {
-                feature_select: u32,
+    let body = parse_quote! {{
+        #(#writes)*
+    }};
+    Ok((sig, body))
+}
 
-                /// Feature.
-                ///
-                /// This is a good field.
-                #[access(ReadOnly)]
-                feature: u32,
-            }
+/// Getters and setters for the bit fields of all fields.
+///
+/// Bit fields of fixed-endian fields operate on the value in native byte order.
+fn emit_bit_fields(ParsedInput { fields, .. }: &ParsedInput, extra: &mut ExtraMethods) {
+    for field in fields {
+        let ParsedField {
+            ident: field_ident,
+            access,
+            cfgs,
+            ..
+        } = field;
+        let (ty, read, update) = match &field.native {
+            Some(native) => (
+                native,
+                format_ident!("read_native"),
+                format_ident!("update_native"),
+            ),
+            None => (&field.ty, format_ident!("read"), format_ident!("update")),
         };
 
-        let result = derive_volatile(input)?;
+        for bit_field in &field.options.bits {
+            let ident = &bit_field.ident;
+            let setter = format_ident!("set_{}", ident);
+            let shift = Literal::u32_unsuffixed(bit_field.shift);
+            let mask = bit_field.mask();
+            let (value_ty, getter_body, value): (Type, Block, Expr) = if bit_field.single {
+                (
+                    parse_quote! { bool },
+                    parse_quote! {{
+                        ((self.#field_ident().#read() >> #shift) & #mask) != 0
+                    }},
+                    parse_quote! { <#ty as ::core::convert::From<bool>>::from(value) },
+                )
+            } else {
+                (
+                    ty.clone(),
+                    parse_quote! {{
+                        (self.#field_ident().#read() >> #shift) & #mask
+                    }},
+                    parse_quote! { value & #mask },
+                )
+            };
 
-        let expected_trait = quote! {
-            /// Struct documentation.
-            ///
-            /// This is a wonderful struct.
-            #[allow(non_camel_case_types)]
-            pub trait DeviceConfigVolatileFieldAccess<'a, A> {
-                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+            let getter_doc = format!("Reads the `{ident}` bits of `{field_ident}`.");
+            extra.attrs.push(parse_quote! {
+                #[doc = #getter_doc]
+                #(#cfgs)*
+            });
+            extra.sigs.push(parse_quote! {
+                fn #ident(self) -> #value_ty
                 where
-                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+                    A: ::volatile::access::RestrictAccess<#access>,
+                    A::Restricted: ::volatile::access::Readable
+            });
+            extra.bodies.push(getter_body);
 
-                /// Feature.
-                ///
-                /// This is a good field.
-                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+            let setter_doc = format!(
+                "Updates the `{ident}` bits of `{field_ident}` through a volatile read-modify-write."
+            );
+            extra.attrs.push(parse_quote! {
+                #[doc = #setter_doc]
+                #(#cfgs)*
+            });
+            extra.sigs.push(parse_quote! {
+                fn #setter(self, value: #value_ty)
                 where
-                    A: ::volatile::access::RestrictAccess<ReadOnly>;
-            }
-        };
+                    A: ::volatile::access::RestrictAccess<#access>,
+                    A::Restricted: ::volatile::access::Readable + ::volatile::access::Writable
+            });
+            extra.bodies.push(parse_quote! {{
+                let value = #value;
+                self.#field_ident()
+                    .#update(|old| (old & !(#mask << #shift)) | (value << #shift));
+            }});
+        }
+    }
+}
 
-        let expected_impl = quote! {
-            #[automatically_derived]
-            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
-                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
-                where
-                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
-                {
-                    ::volatile::map_field!(self.feature_select).restrict()
-                }
+/// Compile-time assertions for `#[volatile(strict_layout)]`.
+///
+/// Each field must be `Copy`, and there must not be any implicit padding between or after the fields.
+fn emit_layout_assertions(
+    ParsedInput {
+        struct_ident,
+        fields,
+        ..
+    }: &ParsedInput,
+    items: &mut Vec<Item>,
+) {
+    let mut expected_offset: Expr = parse_quote! { 0 };
+    for field in fields {
+        let ParsedField { ident, ty, .. } = field;
 
-                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
-                where
-                    A: ::volatile::access::RestrictAccess<ReadOnly>,
-                {
-                    ::volatile::map_field!(self.feature).restrict()
-                }
-            }
+        items.push(parse_quote_spanned! {ty.span()=>
+            const _: fn() = || {
+                fn assert_copy<T: ::core::marker::Copy>() {}
+                assert_copy::<#ty>();
+            };
+        });
+
+        let message = format!("implicit padding before field `{ident}`");
+        items.push(parse_quote_spanned! {ident.span()=>
+            const _: () = ::core::assert!(
+                ::core::mem::offset_of!(#struct_ident, #ident) == #expected_offset,
+                #message
+            );
+        });
+
+        expected_offset = parse_quote! {
+            ::core::mem::offset_of!(#struct_ident, #ident) + ::core::mem::size_of::<#ty>()
         };
+    }
 
-        assert_eq!(
-            expected_trait.to_string(),
-            result[0].to_token_stream().to_string()
-        );
-        assert_eq!(
-            expected_impl.to_string(),
-            result[1].to_token_stream().to_string()
+    let message = format!("implicit padding at the end of `{struct_ident}`");
+    items.push(parse_quote_spanned! {struct_ident.span()=>
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<#struct_ident>() == #expected_offset,
+            #message
         );
+    });
+}
 
-        Ok(())
+/// Compile-time assertions that check the generated code against the actual types.
+fn emit_assertions(parsed_input: &ParsedInput) -> Vec<Item> {
+    let mut items = vec![];
+    for field in &parsed_input.fields {
+        let ParsedField {
+            ident: field_ident,
+            cfgs,
+            ..
+        } = field;
+        let ty = field.native.as_ref().unwrap_or(&field.ty);
+
+        if let Some(offset) = &field.options.offset {
+            let struct_ident = &parsed_input.struct_ident;
+            let message = format!(
+                "field `{field_ident}` is not at offset {}",
+                offset.to_token_stream()
+            );
+            items.push(parse_quote_spanned! {offset.span()=>
+                #(#cfgs)*
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(#struct_ident, #field_ident) == #offset,
+                    #message
+                );
+            });
+        }
+
+        if let Some(width) = &field.options.width {
+            let struct_ident = &parsed_input.struct_ident;
+            let message =
+                format!("`{struct_ident}` must be aligned to the access width of `{field_ident}`");
+            items.push(parse_quote_spanned! {width.span()=>
+                #(#cfgs)*
+                const _: () = ::core::assert!(
+                    ::core::mem::align_of::<#struct_ident>() >= ::core::mem::size_of::<#width>(),
+                    #message
+                );
+            });
+            let message = format!(
+                "field `{field_ident}` is not contained in a single word of its access width"
+            );
+            items.push(parse_quote_spanned! {width.span()=>
+                #(#cfgs)*
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(#struct_ident, #field_ident) % ::core::mem::size_of::<#width>()
+                        + ::core::mem::size_of::<#ty>()
+                        <= ::core::mem::size_of::<#width>(),
+                    #message
+                );
+            });
+        }
+
+        for bit_field in &field.options.bits {
+            let end = Literal::u32_unsuffixed(bit_field.shift + bit_field.width);
+            let message = format!(
+                "bit field `{}` exceeds the size of field `{field_ident}`",
+                bit_field.ident
+            );
+            items.push(parse_quote_spanned! {bit_field.ident.span()=>
+                #(#cfgs)*
+                const _: () = ::core::assert!(#end <= <#ty>::BITS, #message);
+            });
+        }
     }
 
-    #[test]
-    fn test_align() -> Result<()> {
-        let input = parse_quote! {
-            #[repr(C, align(8))]
-            #[derive(VolatileFieldAccess)]
-            pub struct DeviceConfig {}
-        };
+    if parsed_input.options.strict_layout {
+        emit_layout_assertions(parsed_input, &mut items);
+    }
 
-        let result = derive_volatile(input)?;
+    items
+}
 
-        let expected_trait = quote! {
-            #[allow(non_camel_case_types)]
-            pub trait DeviceConfigVolatileFieldAccess<'a, A> {}
-        };
+/// The `NoPadding` implementation for `#[volatile(no_padding)]`.
+///
+/// The absence of padding is checked by the layout assertions, and the where clause requires
+/// that all fields are `NoPadding` themselves.
+fn emit_no_padding(
+    ParsedInput {
+        struct_ident,
+        fields,
+        ..
+    }: &ParsedInput,
+) -> Item {
+    let tys = fields.iter().map(|field| &field.ty);
+    parse_quote! {
+        #[automatically_derived]
+        unsafe impl ::volatile::padding::NoPadding for #struct_ident
+        where
+            #(#tys: ::volatile::padding::NoPadding,)*
+        {}
+    }
+}
 
-        let expected_impl = quote! {
-            #[automatically_derived]
-            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {}
-        };
+/// The module for `#[volatile(accessors)]`, which re-exports the trait anonymously, so that
+/// glob imports of the module bring its methods into scope.
+fn emit_accessors(
+    ParsedInput {
+        vis,
+        trait_ident,
+        struct_ident,
+        options,
+        ..
+    }: &ParsedInput,
+    module: &Ident,
+) -> Item {
+    let doc = format!("Brings the methods of [`{trait_ident}`](super::{trait_ident}) for [`{struct_ident}`](super::{struct_ident}) into scope.");
+    let reexport_vis: Visibility = match vis {
+        Visibility::Inherited => parse_quote! { pub(super) },
+        vis => vis.clone(),
+    };
+    let hidden = options.hidden.then(|| -> Attribute {
+        parse_quote! { #[doc(hidden)] }
+    });
+    parse_quote! {
+        #[doc = #doc]
+        #hidden
+        #vis mod #module {
+            #reexport_vis use super::#trait_ident as _;
+        }
+    }
+}
 
-        assert_eq!(
-            expected_trait.to_string(),
-            result[0].to_token_stream().to_string()
-        );
-        assert_eq!(
-            expected_impl.to_string(),
-            result[1].to_token_stream().to_string()
+/// The `VolatileFields` implementation for `#[volatile(metadata)]`.
+///
+/// Reserved fields are not part of the table.
+fn emit_metadata(
+    ParsedInput {
+        struct_ty,
+        const_params,
+        fields,
+        ..
+    }: &ParsedInput,
+) -> Item {
+    let mut descriptors: Vec<Expr> = vec![];
+    for field in fields {
+        if field.options.reserved {
+            continue;
+        }
+        let ParsedField {
+            ident, ty, access, ..
+        } = field;
+        let name = ident.to_string();
+        descriptors.push(parse_quote! {
+            ::volatile::metadata::FieldDescriptor {
+                name: #name,
+                offset: ::core::mem::offset_of!(#struct_ty, #ident),
+                size: ::core::mem::size_of::<#ty>(),
+                access: <#access as ::volatile::access::Access>::KIND,
+            }
+        });
+    }
+
+    parse_quote! {
+        #[automatically_derived]
+        impl<#(#const_params),*> ::volatile::metadata::VolatileFields for #struct_ty {
+            const FIELDS: &'static [::volatile::metadata::FieldDescriptor] = &[
+                #(#descriptors,)*
+            ];
+        }
+    }
+}
+
+/// The `defmt` method and the adapter type that implements `defmt::Format`.
+///
+/// Like snapshots, the adapter only reads fields that are readable and free of read side effects.
+fn emit_defmt(
+    ParsedInput {
+        vis,
+        struct_ident,
+        struct_ty,
+        const_params,
+        const_args,
+        fields,
+        ..
+    }: &ParsedInput,
+    extra: &mut ExtraMethods,
+) -> Result<()> {
+    let adapter_ident = format_ident!("{}VolatileDefmt", struct_ident);
+
+    let mut idents = vec![];
+    let mut field_ptrs = vec![];
+    for field in fields {
+        if !field.options.reserved
+            && !field.options.read_side_effects
+            && AccessKind::from_path(&field.access)?.is_readable()
+        {
+            idents.push(&field.ident);
+            field_ptrs.push(field_ptr(field, &parse_quote! { ptr }));
+        }
+    }
+    let format_fields = idents
+        .iter()
+        .map(|ident| format!("{ident}: {{}}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let format = if idents.is_empty() {
+        struct_ident.to_string()
+    } else {
+        format!("{struct_ident} {{{{ {format_fields} }}}}")
+    };
+
+    extra.attrs.push(parse_quote! {
+        /// Returns an adapter that volatile-reads the readable fields when formatted with `defmt`.
+    });
+    extra.sigs.push(parse_quote! {
+        fn defmt(self) -> #adapter_ident<'a, A #(, #const_args)*>
+        where
+            A: ::volatile::access::Readable
+    });
+    extra.bodies.push(parse_quote! {{
+        #adapter_ident { __ptr: self }
+    }});
+
+    let doc =
+        format!("Formats [`{struct_ident}`] with `defmt` by volatile-reading its readable fields.");
+    extra.items.push(parse_quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #adapter_ident<'a, A #(, #const_params)*> {
+            __ptr: ::volatile::VolatilePtr<'a, #struct_ty, A>,
+        }
+    });
+    extra.items.push(parse_quote! {
+        #[automatically_derived]
+        impl<'a, A #(, #const_params)*> ::defmt::Format for #adapter_ident<'a, A #(, #const_args)*>
+        where
+            A: ::volatile::access::Readable,
+        {
+            fn format(&self, f: ::defmt::Formatter<'_>) {
+                let ptr = self.__ptr;
+                ::defmt::write!(f, #format, #(#field_ptrs.read()),*);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// The `modify` method and the modifier type that batches field writes.
+///
+/// The modifier records full values for writable fields and masked updates for the bit fields of
+/// readable and writable fields. When the closure returns, each modified field is written once in
+/// declaration order. Fields that only have bit field updates are updated through a single
+/// read-modify-write.
+fn emit_modify(
+    ParsedInput {
+        vis,
+        struct_ident,
+        struct_ty,
+        const_params,
+        const_args,
+        fields,
+        ..
+    }: &ParsedInput,
+    extra: &mut ExtraMethods,
+) -> Result<()> {
+    let modify_ident = format_ident!("{}VolatileModify", struct_ident);
+
+    let mut state_fields: Vec<Field> = vec![];
+    let mut state_inits: Vec<FieldValue> = vec![];
+    let mut setters: Vec<ImplItemFn> = vec![];
+    let mut writes: Vec<Stmt> = vec![];
+    let mut needs_read = false;
+    for field in fields {
+        let kind = AccessKind::from_path(&field.access)?;
+        if field.options.reserved || !kind.is_writable() {
+            continue;
+        }
+        let ParsedField { ident, ty, .. } = field;
+
+        state_fields.push(parse_quote! { #ident: ::core::option::Option<#ty> });
+        state_inits.push(parse_quote! { #ident: ::core::option::Option::None });
+        let doc = format!("Sets `{ident}` to `value`.");
+        setters.push(parse_quote! {
+            #[doc = #doc]
+            pub fn #ident(mut self, value: #ty) -> Self {
+                self.#ident = ::core::option::Option::Some(value);
+                self
+            }
+        });
+
+        if field.options.bits.is_empty() || !kind.is_readable() {
+            writes.push(parse_quote! {
+                if let ::core::option::Option::Some(value) = modify.#ident {
+                    ::volatile::map_field!(ptr.#ident).write(value);
+                }
+            });
+            continue;
+        }
+
+        needs_read = true;
+        let bits_ident = format_ident!("__{}_bits", ident);
+        let native = field.native.as_ref().unwrap_or(ty);
+        state_fields.push(parse_quote! { #bits_ident: (#native, #native) });
+        state_inits.push(parse_quote! { #bits_ident: (0, 0) });
+        for bit_field in &field.options.bits {
+            let bit_ident = &bit_field.ident;
+            let shift = Literal::u32_unsuffixed(bit_field.shift);
+            let mask = bit_field.mask();
+            let (value_ty, value): (Type, Expr) = if bit_field.single {
+                (
+                    parse_quote! { bool },
+                    parse_quote! { <#native as ::core::convert::From<bool>>::from(value) },
+                )
+            } else {
+                (native.clone(), parse_quote! { value & #mask })
+            };
+            let doc = format!("Sets the `{bit_ident}` bits of `{ident}` to `value`.");
+            setters.push(parse_quote! {
+                #[doc = #doc]
+                pub fn #bit_ident(mut self, value: #value_ty) -> Self {
+                    let value = #value;
+                    let (mask, bits) = self.#bits_ident;
+                    self.#bits_ident = (
+                        mask | (#mask << #shift),
+                        (bits & !(#mask << #shift)) | (value << #shift),
+                    );
+                    self
+                }
+            });
+        }
+
+        writes.push(if field.native.is_some() {
+            parse_quote! {
+                {
+                    let (mask, bits) = modify.#bits_ident;
+                    let field = ::volatile::map_field!(ptr.#ident);
+                    match modify.#ident {
+                        ::core::option::Option::Some(value) => {
+                            let value = ::volatile::endian::Endian::to_native(value);
+                            field.write_native((value & !mask) | bits);
+                        }
+                        ::core::option::Option::None if mask != 0 => {
+                            field.update_native(|old| (old & !mask) | bits);
+                        }
+                        ::core::option::Option::None => {}
+                    }
+                }
+            }
+        } else {
+            parse_quote! {
+                {
+                    let (mask, bits) = modify.#bits_ident;
+                    let field = ::volatile::map_field!(ptr.#ident);
+                    match modify.#ident {
+                        ::core::option::Option::Some(value) => {
+                            field.write((value & !mask) | bits);
+                        }
+                        ::core::option::Option::None if mask != 0 => {
+                            field.update(|old| (old & !mask) | bits);
+                        }
+                        ::core::option::Option::None => {}
+                    }
+                }
+            }
+        });
+    }
+
+    let mut access_bounds: Vec<Path> = vec![];
+    if needs_read {
+        access_bounds.push(parse_quote! { ::volatile::access::Readable });
+    }
+    access_bounds.push(parse_quote! { ::volatile::access::Writable });
+
+    extra.attrs.push(parse_quote! {
+        /// Applies the field updates recorded by `f`.
+        ///
+        /// Each modified field is written once in declaration order. Fields with only bit field
+        /// updates are updated through a single volatile read-modify-write. No other field is
+        /// read or written.
+    });
+    let modify_ty: Type = parse_quote! { #modify_ident<'a, A #(, #const_args)*> };
+    extra.sigs.push(parse_quote! {
+        fn modify<F>(self, f: F)
+        where
+            A: #(#access_bounds)+*,
+            F: ::core::ops::FnOnce(#modify_ty) -> #modify_ty
+    });
+    extra.bodies.push(parse_quote! {{
+        let modify = f(#modify_ident {
+            __ptr: self,
+            #(#state_inits,)*
+        });
+        let ptr = modify.__ptr;
+        #(#writes)*
+    }});
+
+    let doc = format!(
+        "Records field updates for [`{struct_ident}`], which are applied when the `modify` closure returns."
+    );
+    extra.items.push(parse_quote! {
+        #[doc = #doc]
+        #[must_use]
+        #vis struct #modify_ident<'a, A #(, #const_params)*> {
+            __ptr: ::volatile::VolatilePtr<'a, #struct_ty, A>,
+            #(#state_fields,)*
+        }
+    });
+    extra.items.push(parse_quote! {
+        impl<'a, A #(, #const_params)*> #modify_ident<'a, A #(, #const_args)*> {
+            #(#setters)*
+        }
+    });
+
+    Ok(())
+}
+
+/// The `builder` method and the typestate builder type for writing all writable fields.
+///
+/// The builder has one type parameter per writable field, which is `()` while the field is unset
+/// and the field type once it has been set. `write` is only available once all fields are set.
+fn emit_builder(
+    ParsedInput {
+        vis,
+        struct_ident,
+        struct_ty,
+        const_params,
+        const_args,
+        fields,
+        ..
+    }: &ParsedInput,
+    extra: &mut ExtraMethods,
+) -> Result<()> {
+    let builder_ident = format_ident!("{}VolatileBuilder", struct_ident);
+
+    let mut writable = vec![];
+    for field in fields {
+        if !field.options.reserved && AccessKind::from_path(&field.access)?.is_writable() {
+            writable.push(field);
+        }
+    }
+    let idents: Vec<&Ident> = writable.iter().map(|field| &field.ident).collect();
+    let tys: Vec<&Type> = writable.iter().map(|field| &field.ty).collect();
+    let params: Vec<Ident> = (0..writable.len())
+        .map(|i| format_ident!("__F{}", i))
+        .collect();
+    let unit: Type = parse_quote! { () };
+
+    extra.attrs.push(parse_quote! {
+        /// Returns a builder that writes all writable fields in a single ordered pass.
+    });
+    let unset = params.iter().map(|_| &unit);
+    extra.sigs.push(parse_quote! {
+        fn builder(self) -> #builder_ident<'a, A #(, #const_args)*, #(#unset),*>
+        where
+            A: ::volatile::access::Writable
+    });
+    extra.bodies.push(parse_quote! {{
+        #builder_ident {
+            __ptr: self,
+            #(#idents: (),)*
+        }
+    }});
+
+    let doc = format!(
+        "Builder for writing all writable fields of [`{struct_ident}`] in declaration order.\n\n\
+         Each type parameter is `()` until the corresponding field has been set."
+    );
+    extra.items.push(parse_quote! {
+        #[doc = #doc]
+        #[must_use]
+        #vis struct #builder_ident<'a, A #(, #const_params)*, #(#params),*> {
+            __ptr: ::volatile::VolatilePtr<'a, #struct_ty, A>,
+            #(#idents: #params,)*
+        }
+    });
+
+    for (i, (ident, ty)) in idents.iter().zip(&tys).enumerate() {
+        let impl_params = params
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, param)| param);
+        let self_args = params.iter().enumerate().map(|(j, param)| -> Type {
+            if j == i {
+                unit.clone()
+            } else {
+                parse_quote!(#param)
+            }
+        });
+        let ret_args = params.iter().enumerate().map(|(j, param)| -> Type {
+            if j == i {
+                (*ty).clone()
+            } else {
+                parse_quote!(#param)
+            }
+        });
+        let others = idents
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, ident)| ident);
+        let doc = format!("Sets the value for `{ident}`.");
+
+        extra.items.push(parse_quote! {
+            impl<'a, A #(, #const_params)*, #(#impl_params),*> #builder_ident<'a, A #(, #const_args)*, #(#self_args),*> {
+                #[doc = #doc]
+                pub fn #ident(self, value: #ty) -> #builder_ident<'a, A #(, #const_args)*, #(#ret_args),*> {
+                    #builder_ident {
+                        __ptr: self.__ptr,
+                        #ident: value,
+                        #(#others: self.#others,)*
+                    }
+                }
+            }
+        });
+    }
+
+    extra.items.push(parse_quote! {
+        impl<'a, A #(, #const_params)*> #builder_ident<'a, A #(, #const_args)*, #(#tys),*>
+        where
+            A: ::volatile::access::Writable,
+        {
+            /// Writes all fields in declaration order.
+            pub fn write(self) {
+                let ptr = self.__ptr;
+                #(::volatile::map_field!(ptr.#idents).write(self.#idents);)*
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Additional trait methods that are generated on request through `#[volatile(...)]` options.
+struct ExtraMethods {
+    attrs: Vec<Vec<Attribute>>,
+    sigs: Vec<Signature>,
+    bodies: Vec<Block>,
+    /// Items that are used by the methods, such as helper types.
+    items: Vec<Item>,
+}
+
+fn emit_extra_methods(parsed_input: &ParsedInput) -> Result<ExtraMethods> {
+    let mut extra = ExtraMethods {
+        attrs: vec![],
+        sigs: vec![],
+        bodies: vec![],
+        items: vec![],
+    };
+
+    if parsed_input.options.snapshot {
+        let (sig, body) = emit_snapshot(parsed_input)?;
+        extra.attrs.push(parse_quote! {
+            /// Reads all readable fields and returns them as an owned value.
+            ///
+            /// Fields that are not readable or marked with `#[volatile(read_side_effects)]`
+            /// are set to their default value.
+        });
+        extra.sigs.push(sig);
+        extra.bodies.push(body);
+    }
+
+    if parsed_input.options.write_all {
+        let [(write_all_sig, write_all_body), (init_sig, init_body)] =
+            emit_write_all(parsed_input)?;
+        extra.attrs.push(parse_quote! {
+            /// Writes all writable fields of `value` in declaration order.
+        });
+        extra.sigs.push(write_all_sig);
+        extra.bodies.push(write_all_body);
+        extra.attrs.push(parse_quote! {
+            /// Like `write_all`, but surrounded by sequentially consistent fences.
+            ///
+            /// This makes sure that all previous memory accesses are visible before the first
+            /// field is written and that all fields are written before any following access.
+        });
+        extra.sigs.push(init_sig);
+        extra.bodies.push(init_body);
+    }
+
+    if parsed_input.options.reset {
+        let (sig, body) = emit_reset(parsed_input)?;
+        extra.attrs.push(parse_quote! {
+            /// Writes the reset value of each writable field in declaration order.
+            ///
+            /// The reset value is given by `#[volatile(reset = ...)]` and defaults to
+            /// `Default::default()`.
+        });
+        extra.sigs.push(sig);
+        extra.bodies.push(body);
+    }
+
+    if parsed_input.options.builder {
+        emit_builder(parsed_input, &mut extra)?;
+    }
+
+    if parsed_input.options.modify {
+        emit_modify(parsed_input, &mut extra)?;
+    }
+
+    if parsed_input.options.defmt {
+        emit_defmt(parsed_input, &mut extra)?;
+    }
+
+    emit_bit_fields(parsed_input, &mut extra);
+
+    Ok(extra)
+}
+
+fn emit_trait(
+    ParsedInput {
+        attrs,
+        vis,
+        trait_ident,
+        const_params,
+        method_attrs,
+        sigs,
+        ..
+    }: &ParsedInput,
+    extra: &ExtraMethods,
+) -> ItemTrait {
+    let extra_attrs = &extra.attrs;
+    let extra_sigs = &extra.sigs;
+
+    parse_quote! {
+        #(#attrs)*
+        #[allow(non_camel_case_types)]
+        #vis trait #trait_ident <'a, A #(, #const_params)*> {
+            #(
+                #(#method_attrs)*
+                #sigs;
+            )*
+            #(
+                #(#extra_attrs)*
+                #extra_sigs;
+            )*
+        }
+    }
+}
+
+/// Returns only the `#[cfg]` attributes, which have to be repeated on the impl items.
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .collect()
+}
+
+fn emit_impl(
+    ParsedInput {
+        trait_ident,
+        struct_ty,
+        const_params,
+        const_args,
+        method_attrs,
+        sigs,
+        accessors,
+        ..
+    }: &ParsedInput,
+    extra: &ExtraMethods,
+) -> ItemImpl {
+    let cfgs = method_attrs.iter().map(|attrs| cfg_attrs(attrs));
+    let extra_cfgs = extra.attrs.iter().map(|attrs| cfg_attrs(attrs));
+    let extra_sigs = &extra.sigs;
+    let extra_bodies = &extra.bodies;
+
+    parse_quote! {
+        #[automatically_derived]
+        impl<'a, A #(, #const_params)*> #trait_ident<'a, A #(, #const_args)*>
+        for ::volatile::VolatilePtr<'a, #struct_ty, A>
+        {
+            #(
+                #(#cfgs)*
+                #sigs,
+                {
+                    #accessors
+                }
+            )*
+            #(
+                #(#extra_cfgs)*
+                #extra_sigs,
+                #extra_bodies
+            )*
+        }
+    }
+}
+
+pub fn derive_volatile(input: ItemStruct) -> Result<Vec<Item>> {
+    validate_input(&input)?;
+    let parsed_input = parse_input(&input)?;
+    let extra = emit_extra_methods(&parsed_input)?;
+    let item_trait = emit_trait(&parsed_input, &extra);
+    let item_impl = emit_impl(&parsed_input, &extra);
+    let mut items = vec![Item::Trait(item_trait), Item::Impl(item_impl)];
+    items.extend(emit_assertions(&parsed_input));
+    for mut item in extra.items {
+        if let Item::Struct(item_struct) = &mut item {
+            if parsed_input.options.hidden {
+                item_struct.attrs.push(parse_quote! { #[doc(hidden)] });
+            }
+        }
+        items.push(item);
+    }
+    if parsed_input.options.no_padding {
+        items.push(emit_no_padding(&parsed_input));
+    }
+    if parsed_input.options.metadata {
+        items.push(emit_metadata(&parsed_input));
+    }
+    if let Some(module) = &parsed_input.options.accessors {
+        items.push(emit_accessors(&parsed_input, module));
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_derive() -> Result<()> {
+        let input = parse_quote! {
+            /// Struct documentation.
+            ///
+            /// This is a wonderful struct.
+            #[repr(C)]
+            #[derive(VolatileFieldAccess, Default)]
+            pub struct DeviceConfig {
+                feature_select: u32,
+
+                /// Feature.
+                ///
+                /// This is a good field.
+                #[access(ReadOnly)]
+                feature: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            /// Struct documentation.
+            ///
+            /// This is a wonderful struct.
+            #[allow(non_camel_case_types)]
+            pub trait DeviceConfigVolatileFieldAccess<'a, A> {
+                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+
+                /// Feature.
+                ///
+                /// This is a good field.
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadOnly>;
+            }
+        };
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
+                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.feature_select).restrict()
+                }
+
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadOnly>,
+                {
+                    ::volatile::map_field!(self.feature).restrict()
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_align() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C, align(8))]
+            #[derive(VolatileFieldAccess)]
+            pub struct DeviceConfig {}
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait DeviceConfigVolatileFieldAccess<'a, A> {}
+        };
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {}
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(snapshot)]
+            pub struct DeviceConfig {
+                feature_select: u32,
+                #[access(WriteOnly)]
+                command: u32,
+                #[volatile(read_side_effects)]
+                fifo: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
+                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.feature_select).restrict()
+                }
+
+                fn command(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<WriteOnly>,
+                {
+                    ::volatile::map_field!(self.command).restrict()
+                }
+
+                fn fifo(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.fifo).restrict()
+                }
+
+                fn snapshot(self) -> DeviceConfig
+                where
+                    A: ::volatile::access::Readable,
+                {
+                    DeviceConfig {
+                        feature_select: ::volatile::map_field!(self.feature_select).read(),
+                        command: ::core::default::Default::default(),
+                        fifo: ::core::default::Default::default(),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_all() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(write_all)]
+            pub struct DeviceConfig {
+                #[access(WriteOnly)]
+                feature_select: u32,
+                #[access(ReadOnly)]
+                feature: u32,
+                command: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
+                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<WriteOnly>,
+                {
+                    ::volatile::map_field!(self.feature_select).restrict()
+                }
+
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadOnly>,
+                {
+                    ::volatile::map_field!(self.feature).restrict()
+                }
+
+                fn command(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.command).restrict()
+                }
+
+                fn write_all(self, value: &DeviceConfig)
+                where
+                    A: ::volatile::access::Writable,
+                {
+                    ::volatile::map_field!(self.feature_select).write(value.feature_select);
+                    ::volatile::map_field!(self.command).write(value.command);
+                }
+
+                fn init(self, value: &DeviceConfig)
+                where
+                    A: ::volatile::access::Writable,
+                {
+                    ::core::sync::atomic::fence(::core::sync::atomic::Ordering::SeqCst);
+                    self.write_all(value);
+                    ::core::sync::atomic::fence(::core::sync::atomic::Ordering::SeqCst);
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bits() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            pub struct DeviceConfig {
+                #[volatile(bits(enable = 0, mode = 1..3))]
+                control: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait DeviceConfigVolatileFieldAccess<'a, A> {
+                fn control(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+
+                #[doc = "Reads the `enable` bits of `control`."]
+                fn enable(self) -> bool
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable;
+
+                #[doc = "Updates the `enable` bits of `control` through a volatile read-modify-write."]
+                fn set_enable(self, value: bool)
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable + ::volatile::access::Writable;
+
+                #[doc = "Reads the `mode` bits of `control`."]
+                fn mode(self) -> u32
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable;
+
+                #[doc = "Updates the `mode` bits of `control` through a volatile read-modify-write."]
+                fn set_mode(self, value: u32)
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable + ::volatile::access::Writable;
+            }
+        };
+
+        let expected_assertion = quote! {
+            const _: () = ::core::assert!(3 <= <u32>::BITS, "bit field `mode` exceeds the size of field `control`");
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(
+            expected_assertion.to_string(),
+            result[3].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_layout() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(strict_layout)]
+            pub struct DeviceConfig {
+                feature_select: u32,
+                feature: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_assertions = [
+            quote! {
+                const _: fn() = || {
+                    fn assert_copy<T: ::core::marker::Copy>() {}
+                    assert_copy::<u32>();
+                };
+            },
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(DeviceConfig, feature_select) == 0,
+                    "implicit padding before field `feature_select`"
+                );
+            },
+            quote! {
+                const _: fn() = || {
+                    fn assert_copy<T: ::core::marker::Copy>() {}
+                    assert_copy::<u32>();
+                };
+            },
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(DeviceConfig, feature)
+                        == ::core::mem::offset_of!(DeviceConfig, feature_select)
+                            + ::core::mem::size_of::<u32>(),
+                    "implicit padding before field `feature`"
+                );
+            },
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::size_of::<DeviceConfig>()
+                        == ::core::mem::offset_of!(DeviceConfig, feature)
+                            + ::core::mem::size_of::<u32>(),
+                    "implicit padding at the end of `DeviceConfig`"
+                );
+            },
+        ];
+
+        assert_eq!(result.len(), 2 + expected_assertions.len());
+        for (expected, item) in expected_assertions.into_iter().zip(&result[2..]) {
+            // Round-trip through `syn` to get the same token spacing for closures.
+            let expected: Item = parse_quote!(#expected);
+            assert_eq!(
+                expected.to_token_stream().to_string(),
+                item.to_token_stream().to_string()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(builder)]
+            pub struct DeviceConfig {
+                #[access(WriteOnly)]
+                feature_select: u32,
+                #[access(ReadOnly)]
+                feature: u32,
+                command: u16,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_items = [
+            quote! {
+                #[doc = "Builder for writing all writable fields of [`DeviceConfig`] in declaration order.\n\nEach type parameter is `()` until the corresponding field has been set."]
+                #[must_use]
+                pub struct DeviceConfigVolatileBuilder<'a, A, __F0, __F1> {
+                    __ptr: ::volatile::VolatilePtr<'a, DeviceConfig, A>,
+                    feature_select: __F0,
+                    command: __F1,
+                }
+            },
+            quote! {
+                impl<'a, A, __F1> DeviceConfigVolatileBuilder<'a, A, (), __F1> {
+                    #[doc = "Sets the value for `feature_select`."]
+                    pub fn feature_select(self, value: u32) -> DeviceConfigVolatileBuilder<'a, A, u32, __F1> {
+                        DeviceConfigVolatileBuilder {
+                            __ptr: self.__ptr,
+                            feature_select: value,
+                            command: self.command,
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<'a, A, __F0> DeviceConfigVolatileBuilder<'a, A, __F0, ()> {
+                    #[doc = "Sets the value for `command`."]
+                    pub fn command(self, value: u16) -> DeviceConfigVolatileBuilder<'a, A, __F0, u16> {
+                        DeviceConfigVolatileBuilder {
+                            __ptr: self.__ptr,
+                            command: value,
+                            feature_select: self.feature_select,
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<'a, A> DeviceConfigVolatileBuilder<'a, A, u32, u16>
+                where
+                    A: ::volatile::access::Writable,
+                {
+                    /// Writes all fields in declaration order.
+                    pub fn write(self) {
+                        let ptr = self.__ptr;
+                        ::volatile::map_field!(ptr.feature_select).write(self.feature_select);
+                        ::volatile::map_field!(ptr.command).write(self.command);
+                    }
+                }
+            },
+        ];
+
+        assert_eq!(result.len(), 2 + expected_items.len());
+        for (expected, item) in expected_items.iter().zip(&result[2..]) {
+            assert_eq!(expected.to_string(), item.to_token_stream().to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_access() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(access = ReadOnly)]
+            pub struct DeviceConfig {
+                feature: u32,
+                #[access(ReadWrite)]
+                feature_select: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait DeviceConfigVolatileFieldAccess<'a, A> {
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadOnly>;
+
+                fn feature_select(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadWrite>;
+            }
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cfg() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(snapshot)]
+            pub struct DeviceConfig {
+                #[cfg(target_arch = "x86_64")]
+                feature: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait DeviceConfigVolatileFieldAccess<'a, A> {
+                #[cfg(target_arch = "x86_64")]
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+
+                /// Reads all readable fields and returns them as an owned value.
+                ///
+                /// Fields that are not readable or marked with `#[volatile(read_side_effects)]`
+                /// are set to their default value.
+                fn snapshot(self) -> DeviceConfig
+                where
+                    A: ::volatile::access::Readable;
+            }
+        };
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
+                #[cfg(target_arch = "x86_64")]
+                fn feature(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.feature).restrict()
+                }
+
+                fn snapshot(self) -> DeviceConfig
+                where
+                    A: ::volatile::access::Readable,
+                {
+                    DeviceConfig {
+                        #[cfg(target_arch = "x86_64")]
+                        feature: ::volatile::map_field!(self.feature).read(),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(metadata)]
+            pub struct DeviceConfig {
+                feature_select: u32,
+                #[volatile(reserved)]
+                _reserved: [u8; 4],
+                #[access(ReadOnly)]
+                feature: u16,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl ::volatile::metadata::VolatileFields for DeviceConfig {
+                const FIELDS: &'static [::volatile::metadata::FieldDescriptor] = &[
+                    ::volatile::metadata::FieldDescriptor {
+                        name: "feature_select",
+                        offset: ::core::mem::offset_of!(DeviceConfig, feature_select),
+                        size: ::core::mem::size_of::<u32>(),
+                        access: <::volatile::access::ReadWrite as ::volatile::access::Access>::KIND,
+                    },
+                    ::volatile::metadata::FieldDescriptor {
+                        name: "feature",
+                        offset: ::core::mem::offset_of!(DeviceConfig, feature),
+                        size: ::core::mem::size_of::<u16>(),
+                        access: <ReadOnly as ::volatile::access::Access>::KIND,
+                    },
+                ];
+            }
+        };
+
+        assert_eq!(
+            expected_impl.to_string(),
+            result.last().unwrap().to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_defmt() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(defmt)]
+            pub struct Uart {
+                status: u32,
+                #[volatile(read_side_effects)]
+                rx_fifo: u32,
+                #[access(WriteOnly)]
+                tx_fifo: u32,
+                control: u16,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_items = [
+            quote! {
+                #[doc = "Formats [`Uart`] with `defmt` by volatile-reading its readable fields."]
+                #[derive(Clone, Copy)]
+                pub struct UartVolatileDefmt<'a, A> {
+                    __ptr: ::volatile::VolatilePtr<'a, Uart, A>,
+                }
+            },
+            quote! {
+                #[automatically_derived]
+                impl<'a, A> ::defmt::Format for UartVolatileDefmt<'a, A>
+                where
+                    A: ::volatile::access::Readable,
+                {
+                    fn format(&self, f: ::defmt::Formatter<'_>) {
+                        let ptr = self.__ptr;
+                        ::defmt::write!(
+                            f,
+                            "Uart {{ status: {}, control: {} }}",
+                            ::volatile::map_field!(ptr.status).read(),
+                            ::volatile::map_field!(ptr.control).read()
+                        );
+                    }
+                }
+            },
+        ];
+
+        assert_eq!(result.len(), 2 + expected_items.len());
+        for (expected, item) in expected_items.iter().zip(&result[2..]) {
+            assert_eq!(expected.to_string(), item.to_token_stream().to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_generics() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            pub struct Config<const N: usize = 4> {
+                queues: [u32; N],
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait ConfigVolatileFieldAccess<'a, A, const N: usize> {
+                fn queues(self) -> ::volatile::VolatilePtr<'a, [u32; N], A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+            }
+        };
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A, const N: usize> ConfigVolatileFieldAccess<'a, A, N> for ::volatile::VolatilePtr<'a, Config<N>, A> {
+                fn queues(self) -> ::volatile::VolatilePtr<'a, [u32; N], A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.queues).restrict()
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_endian_native_type() {
+        let native = |ty: Type| endian_native_type(&ty).map(|ty| ty.to_token_stream().to_string());
+
+        assert_eq!(native(parse_quote! { Le<u32> }), Some("u32".to_string()));
+        assert_eq!(
+            native(parse_quote! { volatile::endian::Be<u16> }),
+            Some("u16".to_string())
+        );
+        assert_eq!(
+            native(parse_quote! { zerocopy::byteorder::U64<LE> }),
+            Some("u64".to_string())
+        );
+        assert_eq!(native(parse_quote! { u32 }), None);
+        assert_eq!(native(parse_quote! { Option<u32> }), None);
+    }
+
+    #[test]
+    fn test_reset() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(reset)]
+            pub struct DeviceConfig {
+                #[volatile(reset = 0x8000)]
+                control: u32,
+                #[access(ReadOnly)]
+                status: u32,
+                #[volatile(reset = 1)]
+                mtu: Le<u16>,
+                data: u16,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> DeviceConfigVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, DeviceConfig, A> {
+                fn control(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.control).restrict()
+                }
+
+                fn status(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<ReadOnly>,
+                {
+                    ::volatile::map_field!(self.status).restrict()
+                }
+
+                fn mtu(self) -> ::volatile::VolatilePtr<'a, Le<u16>, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.mtu).restrict()
+                }
+
+                fn data(self) -> ::volatile::VolatilePtr<'a, u16, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    ::volatile::map_field!(self.data).restrict()
+                }
+
+                fn reset(self)
+                where
+                    A: ::volatile::access::Writable,
+                {
+                    ::volatile::map_field!(self.control).write(0x8000);
+                    ::volatile::map_field!(self.mtu).write_native(1);
+                    ::volatile::map_field!(self.data).write(::core::default::Default::default());
+                }
+            }
+        };
+
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offset() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            pub struct DeviceConfig {
+                #[volatile(offset = 0x0)]
+                feature_select: u32,
+                #[cfg(target_arch = "x86_64")]
+                #[volatile(offset = 0x4)]
+                feature: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_assertions = [
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(DeviceConfig, feature_select) == 0x0,
+                    "field `feature_select` is not at offset 0x0"
+                );
+            },
+            quote! {
+                #[cfg(target_arch = "x86_64")]
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(DeviceConfig, feature) == 0x4,
+                    "field `feature` is not at offset 0x4"
+                );
+            },
+        ];
+
+        assert_eq!(result.len(), 2 + expected_assertions.len());
+        for (expected, item) in expected_assertions.iter().zip(&result[2..]) {
+            assert_eq!(expected.to_string(), item.to_token_stream().to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_padding() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess, Clone, Copy)]
+            #[volatile(no_padding)]
+            pub struct Descriptor {
+                address: u64,
+                len: u32,
+                flags: Le<u32>,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected = quote! {
+            #[automatically_derived]
+            unsafe impl ::volatile::padding::NoPadding for Descriptor
+            where
+                u64: ::volatile::padding::NoPadding,
+                u32: ::volatile::padding::NoPadding,
+                Le<u32>: ::volatile::padding::NoPadding,
+            {}
+        };
+        // The field accessors, six field assertions, and the end of struct assertion come first.
+        assert_eq!(result.len(), 2 + 7 + 1);
+        assert_eq!(
+            expected.to_string(),
+            result.last().unwrap().to_token_stream().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_width() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C, align(4))]
+            #[derive(VolatileFieldAccess)]
+            pub struct Uart {
+                #[volatile(width = 32)]
+                data: u8,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_impl = quote! {
+            #[automatically_derived]
+            impl<'a, A> UartVolatileFieldAccess<'a, A> for ::volatile::VolatilePtr<'a, Uart, A> {
+                fn data(self) -> ::volatile::width::WidePtr<'a, u8, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                {
+                    // SAFETY: The containing word is within the struct, which is checked by the
+                    // generated assertions.
+                    unsafe { ::volatile::width::WidePtr::new(::volatile::map_field!(self.data).restrict()) }
+                }
+            }
+        };
+        let expected_assertions = [
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::align_of::<Uart>() >= ::core::mem::size_of::<u32>(),
+                    "`Uart` must be aligned to the access width of `data`"
+                );
+            },
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::mem::offset_of!(Uart, data) % ::core::mem::size_of::<u32>()
+                        + ::core::mem::size_of::<u8>()
+                        <= ::core::mem::size_of::<u32>(),
+                    "field `data` is not contained in a single word of its access width"
+                );
+            },
+        ];
+
+        assert_eq!(
+            expected_impl.to_string(),
+            result[1].to_token_stream().to_string()
+        );
+        assert_eq!(result.len(), 2 + expected_assertions.len());
+        for (expected, item) in expected_assertions.iter().zip(&result[2..]) {
+            assert_eq!(expected.to_string(), item.to_token_stream().to_string());
+        }
+
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(write_all)]
+            pub struct Uart {
+                #[volatile(width = 24)]
+                data: u8,
+            }
+        };
+        assert!(derive_volatile(input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_modify() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(modify)]
+            pub struct Timer {
+                #[volatile(bits(enable = 0))]
+                control: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_trait = quote! {
+            #[allow(non_camel_case_types)]
+            pub trait TimerVolatileFieldAccess<'a, A> {
+                fn control(self) -> ::volatile::VolatilePtr<'a, u32, A::Restricted>
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>;
+
+                /// Applies the field updates recorded by `f`.
+                ///
+                /// Each modified field is written once in declaration order. Fields with only bit field
+                /// updates are updated through a single volatile read-modify-write. No other field is
+                /// read or written.
+                fn modify<F>(self, f: F)
+                where
+                    A: ::volatile::access::Readable + ::volatile::access::Writable,
+                    F: ::core::ops::FnOnce(TimerVolatileModify<'a, A>) -> TimerVolatileModify<'a, A>;
+
+                #[doc = "Reads the `enable` bits of `control`."]
+                fn enable(self) -> bool
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable;
+
+                #[doc = "Updates the `enable` bits of `control` through a volatile read-modify-write."]
+                fn set_enable(self, value: bool)
+                where
+                    A: ::volatile::access::RestrictAccess<::volatile::access::ReadWrite>,
+                    A::Restricted: ::volatile::access::Readable + ::volatile::access::Writable;
+            }
+        };
+
+        let expected_items = [
+            quote! {
+                #[doc = "Records field updates for [`Timer`], which are applied when the `modify` closure returns."]
+                #[must_use]
+                pub struct TimerVolatileModify<'a, A> {
+                    __ptr: ::volatile::VolatilePtr<'a, Timer, A>,
+                    control: ::core::option::Option<u32>,
+                    __control_bits: (u32, u32),
+                }
+            },
+            quote! {
+                impl<'a, A> TimerVolatileModify<'a, A> {
+                    #[doc = "Sets `control` to `value`."]
+                    pub fn control(mut self, value: u32) -> Self {
+                        self.control = ::core::option::Option::Some(value);
+                        self
+                    }
+
+                    #[doc = "Sets the `enable` bits of `control` to `value`."]
+                    pub fn enable(mut self, value: bool) -> Self {
+                        let value = <u32 as ::core::convert::From<bool>>::from(value);
+                        let (mask, bits) = self.__control_bits;
+                        self.__control_bits = (
+                            mask | (1 << 0),
+                            (bits & !(1 << 0)) | (value << 0),
+                        );
+                        self
+                    }
+                }
+            },
+        ];
+
+        assert_eq!(
+            expected_trait.to_string(),
+            result[0].to_token_stream().to_string()
+        );
+        assert_eq!(result.len(), 3 + expected_items.len());
+        for (expected, item) in expected_items.into_iter().zip(&result[3..]) {
+            // Round-trip through `syn` to get the same token spacing for `>>`.
+            let expected: Item = parse_quote!(#expected);
+            assert_eq!(
+                expected.to_token_stream().to_string(),
+                item.to_token_stream().to_string()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vis_hidden() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(vis = "pub(crate)", hidden, modify)]
+            pub struct Control {
+                #[volatile(bits(enable = 0))]
+                control: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let Item::Trait(item_trait) = &result[0] else {
+            panic!("expected a trait");
+        };
+        assert_eq!(
+            quote! { pub(crate) }.to_string(),
+            item_trait.vis.to_token_stream().to_string()
+        );
+        let hidden: Attribute = parse_quote! { #[doc(hidden)] };
+        assert!(item_trait.attrs.contains(&hidden));
+
+        let structs: Vec<_> = result
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(item_struct) => Some(item_struct),
+                _ => None,
+            })
+            .collect();
+        assert!(!structs.is_empty());
+        for item_struct in structs {
+            assert_eq!(
+                quote! { pub(crate) }.to_string(),
+                item_struct.vis.to_token_stream().to_string()
+            );
+            assert!(item_struct.attrs.contains(&hidden));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_accessors() -> Result<()> {
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(accessors = "uart_accessors")]
+            pub struct Uart {
+                data: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_module = quote! {
+            #[doc = "Brings the methods of [`UartVolatileFieldAccess`](super::UartVolatileFieldAccess) for [`Uart`](super::Uart) into scope."]
+            pub mod uart_accessors {
+                pub use super::UartVolatileFieldAccess as _;
+            }
+        };
+
+        assert_eq!(
+            expected_module.to_string(),
+            result.last().unwrap().to_token_stream().to_string()
+        );
+
+        let input = parse_quote! {
+            #[repr(C)]
+            #[derive(VolatileFieldAccess)]
+            #[volatile(accessors)]
+            struct Uart {
+                data: u32,
+            }
+        };
+
+        let result = derive_volatile(input)?;
+
+        let expected_module = quote! {
+            #[doc = "Brings the methods of [`UartVolatileFieldAccess`](super::UartVolatileFieldAccess) for [`Uart`](super::Uart) into scope."]
+            mod volatile_accessors {
+                pub(super) use super::UartVolatileFieldAccess as _;
+            }
+        };
+
+        assert_eq!(
+            expected_module.to_string(),
+            result.last().unwrap().to_token_stream().to_string()
         );
 
         Ok(())